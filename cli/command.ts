@@ -90,8 +90,19 @@ programCommand('create-market')
   .requiredOption('-b --base_mint <string>')
   .requiredOption('-q --quote_mint <string>')
   .requiredOption('-n --name <string>')
+  .requiredOption('-opu, --order_per_user <number>')
+  .requiredOption('-opb, --order_per_book <number>')
   .action(async (directory, cmd) => {
-    const { env, keypair, rpc, base_mint, quote_mint, name } = cmd.opts();
+    const {
+      env,
+      keypair,
+      rpc,
+      base_mint,
+      quote_mint,
+      name,
+      order_per_user,
+      order_per_book,
+    } = cmd.opts();
 
     console.log('Solana Cluster:', env);
     console.log('Keypair Path:', keypair);
@@ -101,7 +112,9 @@ programCommand('create-market')
     await createMarket(
       new PublicKey(base_mint),
       new PublicKey(quote_mint),
-      name
+      name,
+      Number(order_per_user),
+      Number(order_per_book)
     );
   });
 
@@ -162,13 +162,17 @@ export const changeConfig = async (
 export const createMarket = async (
   baseMint: PublicKey,
   quoteMint: PublicKey,
-  name: string
+  name: string,
+  maxOrdersPerUser: number,
+  maxOrdersPerBook: number
 ) => {
   const tx = await createMarketTx(
     payer.publicKey,
     baseMint,
     quoteMint,
     name,
+    maxOrdersPerUser,
+    maxOrdersPerBook,
     program
   );
 
@@ -327,6 +331,8 @@ export const getMarketInfo = async (market: PublicKey) => {
     baseTotalVolume: data.baseTotalVolume.toNumber(),
     quoteTotalVolume: data.quoteTotalVolume.toNumber(),
     orderSeqNum: data.orderSeqNum.toNumber(),
+    maxOrdersPerUser: data.maxOrdersPerUser.toNumber(),
+    maxOrdersPerBook: data.maxOrdersPerBook.toNumber(),
   };
 };
 
@@ -0,0 +1,184 @@
+use anchor_lang::AnchorDeserialize;
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hybrid_dex_client::decode_return_data;
+use serde::Serialize;
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+/// Connection, signer and output settings shared by all subcommands
+pub struct Config {
+    pub rpc: RpcClient,
+    pub signer: Pubkey,
+    pub keypair: Option<Keypair>,
+    pub offline: bool,
+    pub blockhash: Option<Hash>,
+    pub json: bool,
+}
+
+pub fn cluster_url(env: &str) -> Result<&'static str> {
+    match env {
+        "mainnet-beta" | "mainnet" => Ok("https://api.mainnet-beta.solana.com"),
+        "testnet" => Ok("https://api.testnet.solana.com"),
+        "devnet" => Ok("https://api.devnet.solana.com"),
+        "localnet" | "localhost" => Ok("http://127.0.0.1:8899"),
+        _ => Err(anyhow!("Unknown cluster env {}", env)),
+    }
+}
+
+impl Config {
+    pub fn new(
+        env: &str,
+        rpc: Option<String>,
+        keypair_path: &str,
+        signer: Option<Pubkey>,
+        offline: bool,
+        blockhash: Option<Hash>,
+        json: bool,
+    ) -> Result<Config> {
+        let url = match rpc {
+            Some(url) => url,
+            None => cluster_url(env)?.to_string(),
+        };
+        let rpc = RpcClient::new_with_commitment(url, CommitmentConfig::confirmed());
+
+        // offline signer only needs a public key, the keypair stays elsewhere
+        let keypair = match (offline, signer) {
+            (true, Some(_)) => None,
+            _ => Some(
+                read_keypair_file(keypair_path)
+                    .map_err(|err| anyhow!("Failed to read keypair {}: {}", keypair_path, err))?,
+            ),
+        };
+        let signer = match (&keypair, signer) {
+            (_, Some(signer)) => signer,
+            (Some(keypair), None) => keypair.pubkey(),
+            (None, None) => unreachable!(),
+        };
+
+        Ok(Config {
+            rpc,
+            signer,
+            keypair,
+            offline,
+            blockhash,
+            json,
+        })
+    }
+
+    /// Sign and send instructions, or print the unsigned transaction in offline mode
+    pub fn submit(&self, instructions: &[Instruction]) -> Result<()> {
+        let blockhash = match self.blockhash {
+            Some(blockhash) => blockhash,
+            None => self
+                .rpc
+                .get_latest_blockhash()
+                .context("Failed to get latest blockhash")?,
+        };
+
+        let mut tx = Transaction::new_with_payer(instructions, Some(&self.signer));
+
+        if self.offline {
+            tx.message.recent_blockhash = blockhash;
+            let serialized = STANDARD.encode(bincode::serialize(&tx)?);
+
+            #[derive(Serialize)]
+            struct OfflineTransaction {
+                transaction: String,
+                signer: String,
+                blockhash: String,
+            }
+
+            return self.print(
+                &OfflineTransaction {
+                    transaction: serialized.clone(),
+                    signer: self.signer.to_string(),
+                    blockhash: blockhash.to_string(),
+                },
+                &serialized,
+            );
+        }
+
+        let keypair = self
+            .keypair
+            .as_ref()
+            .ok_or_else(|| anyhow!("Keypair is required to send transactions"))?;
+        if keypair.pubkey() != self.signer {
+            return Err(anyhow!("Signer override is only supported in offline mode"));
+        }
+        tx.sign(&[keypair], blockhash);
+
+        let signature = self
+            .rpc
+            .send_and_confirm_transaction(&tx)
+            .context("Failed to send transaction")?;
+
+        #[derive(Serialize)]
+        struct SentTransaction {
+            signature: String,
+        }
+
+        self.print(
+            &SentTransaction {
+                signature: signature.to_string(),
+            },
+            &format!("Transaction: {}", signature),
+        )
+    }
+
+    /// Simulate a view instruction unsigned and decode the result it returns
+    pub fn simulate<T: AnchorDeserialize>(&self, instruction: Instruction) -> Result<T> {
+        let tx = Transaction::new_unsigned(Message::new(&[instruction], Some(&self.signer)));
+        let simulation = self
+            .rpc
+            .simulate_transaction_with_config(
+                &tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )
+            .context("Failed to simulate transaction")?
+            .value;
+
+        if let Some(err) = simulation.err {
+            return Err(anyhow!(
+                "Simulation failed: {}\n{}",
+                err,
+                simulation.logs.unwrap_or_default().join("\n")
+            ));
+        }
+        let return_data = simulation
+            .return_data
+            .ok_or_else(|| anyhow!("Simulation returned no data"))?;
+
+        Ok(decode_return_data(&STANDARD.decode(return_data.data.0)?)?)
+    }
+
+    /// Print JSON in json mode, human readable text otherwise
+    pub fn print<T: Serialize>(&self, value: &T, human: &str) -> Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(value)?);
+        } else {
+            println!("{}", human);
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Print account views as JSON or pretty debug output
+    pub fn show<T: Serialize + std::fmt::Debug>(&self, value: &T) -> Result<()> {
+        self.print(value, &format!("{:#?}", value))
+    }
+}
@@ -0,0 +1,1230 @@
+mod config;
+mod views;
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use hybrid_dex_client::{
+    fetch_books, fetch_clmm_observation, fetch_global_pool, fetch_legacy_book, fetch_market_keys,
+    fetch_market_stats, fetch_pegged_book, fetch_recent_trades, fetch_solvency_report,
+    fetch_trigger_book, fetch_user_market_orders, instructions, observe_price, to_atomic_amount,
+    twap, user_market_orders_address, Market, MarketKeys, MarketOrderQuote, OracleKind, OrderBook,
+    PriceObservation, Side, TriggerCondition, TriggerOrderParams, TriggerOrderType, TriggerSource,
+    UserMarketOrders,
+};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{hash::Hash, pubkey::Pubkey};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{config::Config, views::*};
+
+#[derive(Parser)]
+#[command(name = "hybrid-dex", about = "Hybrid DEX command line tool")]
+struct Cli {
+    /// Solana cluster env name: mainnet-beta, testnet, devnet, localnet
+    #[arg(short, long, global = true, default_value = "devnet")]
+    env: String,
+
+    /// Solana cluster RPC url, overrides env
+    #[arg(short, long, global = true)]
+    rpc: Option<String>,
+
+    /// Solana wallet keypair path
+    #[arg(short, long, global = true, default_value = "../deploy.json")]
+    keypair: String,
+
+    /// Print results as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Print the serialized unsigned transaction instead of sending it
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Signer public key used in offline mode instead of keypair
+    #[arg(long, global = true, requires = "offline")]
+    signer: Option<Pubkey>,
+
+    /// Recent blockhash used in offline mode instead of fetching it
+    #[arg(long, global = true, requires = "offline")]
+    blockhash: Option<Hash>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SideArg {
+    Bid,
+    Ask,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TriggerSourceArg {
+    /// Last price of the market trades worth at least the trigger minimum
+    Last,
+    /// Market oracle price
+    Oracle,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TriggerConditionArg {
+    /// Sell at or below, buy at or above the trigger price
+    Stop,
+    /// Sell at or above, buy at or below the trigger price
+    TakeProfit,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TriggerTypeArg {
+    Limit,
+    Market,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OracleArg {
+    None,
+    Pyth,
+    RaydiumClmm,
+}
+
+#[derive(Args)]
+struct MarketArg {
+    /// Market address
+    #[arg(short, long)]
+    market: Pubkey,
+}
+
+#[derive(Args)]
+struct OpenOrdersArg {
+    /// Open orders sub account index
+    #[arg(long, default_value_t = 0)]
+    account_index: u8,
+
+    /// Open orders owner when signing as its delegate
+    #[arg(long)]
+    owner: Option<Pubkey>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show global config
+    Status,
+    /// Initialize global config as admin
+    Init {
+        #[arg(long)]
+        order_per_user: u64,
+        #[arg(long)]
+        order_per_book: u64,
+    },
+    /// Transfer admin authority
+    ChangeAdmin {
+        #[arg(short = 'a', long)]
+        new_admin: Pubkey,
+    },
+    /// Change global config as admin
+    ChangeConfig {
+        #[arg(long)]
+        order_per_user: Option<u64>,
+        #[arg(long)]
+        order_per_book: Option<u64>,
+    },
+    /// Grow global config into the current account layout as admin
+    MigrateGlobal,
+    /// Create market with its own order limits
+    CreateMarket {
+        #[arg(short, long)]
+        base_mint: Pubkey,
+        #[arg(short, long)]
+        quote_mint: Pubkey,
+        #[arg(short, long)]
+        name: String,
+        #[arg(long)]
+        order_per_user: u64,
+        #[arg(long)]
+        order_per_book: u64,
+    },
+    /// Close empty market as market authority
+    CloseMarket {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Grow market book capacity as market authority
+    ResizeBook {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(long)]
+        order_per_book: u64,
+    },
+    /// Grow market into the current account layout
+    MigrateMarket {
+        /// Market seed, legacy markets can't be loaded by address
+        #[arg(long)]
+        seed: u64,
+    },
+    /// Convert legacy books to the current format as market authority
+    MigrateBooks {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Create trade history of a market created before trades were recorded
+    CreateTradeHistory {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Create stats of a market created before candles were recorded
+    CreateMarketStats {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Create pegged book of a market created before pegged orders existed
+    CreatePeggedBook {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Create trigger book of a market created before trigger orders existed
+    CreateTriggerBook {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Attach or detach the price band oracle as market authority
+    SetOracle {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(long, value_enum)]
+        kind: OracleArg,
+        /// Pyth price account or Raydium CLMM pool, whose TWAP is followed, unused with `none`
+        #[arg(long)]
+        oracle: Option<Pubkey>,
+        /// Orders must rest within this many basis points of the oracle price
+        #[arg(long, default_value_t = 0)]
+        band_bps: u16,
+    },
+    /// Replace market candle series as market authority
+    SetCandleIntervals {
+        #[command(flatten)]
+        market: MarketArg,
+        /// Candle lengths in seconds, comma separated and increasing
+        #[arg(long, value_delimiter = ',')]
+        intervals: Vec<i64>,
+    },
+    /// Set the smallest quote values of orders and trigger trades as market authority
+    SetOrderMinimums {
+        #[command(flatten)]
+        market: MarketArg,
+        /// Quote value a new pegged or trigger order must reach at its price
+        #[arg(long)]
+        min_order_value: String,
+        /// Quote value a trade must reach to move last price triggers
+        #[arg(long)]
+        min_trigger_trade_value: String,
+    },
+    /// Create user open orders account
+    CreateUserOrders {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(long, default_value_t = 0)]
+        account_index: u8,
+    },
+    /// Close empty user open orders account
+    CloseUserOrders {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(long, default_value_t = 0)]
+        account_index: u8,
+    },
+    /// Grow user open orders account into the current account layout
+    MigrateUserOrders {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+    },
+    /// Set or clear open orders delegate
+    SetDelegate {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(long, default_value_t = 0)]
+        account_index: u8,
+        /// Delegate address, clears delegate when omitted
+        #[arg(short, long)]
+        delegate: Option<Pubkey>,
+    },
+    /// Deposit decimal token amounts into open orders free balance
+    Deposit {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+        #[arg(long, default_value = "0")]
+        base: String,
+        #[arg(long, default_value = "0")]
+        quote: String,
+    },
+    /// Withdraw decimal token amounts from open orders free balance
+    Withdraw {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+        #[arg(long, default_value = "0")]
+        base: String,
+        #[arg(long, default_value = "0")]
+        quote: String,
+    },
+    /// Place order, price in quote and quantity in base
+    PlaceOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+        #[arg(short, long)]
+        side: SideArg,
+        #[arg(short, long)]
+        price: String,
+        #[arg(short, long)]
+        quantity: String,
+    },
+    /// Cancel order
+    CancelOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+        #[arg(short, long)]
+        side: SideArg,
+        #[arg(short, long)]
+        order_id: u64,
+    },
+    /// Place order pegged to the market oracle price, offset and limit in quote
+    PlacePeggedOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+        #[arg(short, long)]
+        side: SideArg,
+        /// Added to the oracle price, negative to rest below it
+        #[arg(long, allow_hyphen_values = true)]
+        offset: String,
+        /// Highest bid price or lowest ask price
+        #[arg(long)]
+        limit: String,
+        #[arg(short, long)]
+        quantity: String,
+    },
+    /// Cancel pegged order
+    CancelPeggedOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+        #[arg(short, long)]
+        order_id: u64,
+    },
+    /// Take pegged order at the current oracle price, quantity in base
+    TakePeggedOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(short = 'a', long)]
+        maker: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        maker_account_index: u8,
+        #[arg(long, default_value_t = 0)]
+        account_index: u8,
+        #[arg(short, long)]
+        order_id: u64,
+        #[arg(short, long)]
+        quantity: String,
+        /// Lowest price selling into a bid or highest price buying from an ask
+        #[arg(long)]
+        limit: String,
+    },
+    /// Place stop or take profit order out of free balance, prices in quote
+    PlaceTriggerOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+        #[arg(short, long)]
+        side: SideArg,
+        #[arg(long, value_enum, default_value = "last")]
+        source: TriggerSourceArg,
+        #[arg(long, value_enum)]
+        condition: TriggerConditionArg,
+        #[arg(long = "type", value_enum)]
+        order_type: TriggerTypeArg,
+        #[arg(long)]
+        trigger: String,
+        /// Limit order price or worst market fill price, optional for market asks
+        #[arg(long)]
+        limit: Option<String>,
+        #[arg(short, long)]
+        quantity: String,
+    },
+    /// Cancel trigger order
+    CancelTriggerOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[command(flatten)]
+        open_orders: OpenOrdersArg,
+        #[arg(short, long)]
+        order_id: u64,
+    },
+    /// Execute trigger order whose price has crossed and earn its bounty
+    ExecuteTrigger {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(short, long)]
+        order_id: u64,
+    },
+    /// Take whole order
+    TakeOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(short = 'a', long)]
+        maker: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        maker_account_index: u8,
+        #[arg(long, default_value_t = 0)]
+        account_index: u8,
+        #[arg(short, long)]
+        side: SideArg,
+        #[arg(short, long)]
+        order_id: u64,
+    },
+    /// Take order partially, quantity in base
+    PartialTakeOrder {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(short = 'a', long)]
+        maker: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        maker_account_index: u8,
+        #[arg(long, default_value_t = 0)]
+        account_index: u8,
+        #[arg(short, long)]
+        side: SideArg,
+        #[arg(short, long)]
+        order_id: u64,
+        #[arg(short, long)]
+        quantity: String,
+    },
+    /// Show market state
+    Market {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// List all markets, optionally filtered by mints
+    AllMarkets {
+        #[arg(short, long)]
+        base: Option<Pubkey>,
+        #[arg(short, long)]
+        quote: Option<Pubkey>,
+    },
+    /// Show user open orders account
+    UserOrders {
+        #[command(flatten)]
+        market: MarketArg,
+        #[arg(short = 'a', long)]
+        user_address: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        account_index: u8,
+    },
+    /// Show market bids and asks
+    OrderBook {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Show market oracle pegged orders
+    PeggedOrders {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Show market trigger orders waiting for their price
+    TriggerOrders {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Show market price levels with cumulative size
+    Depth {
+        #[command(flatten)]
+        market: MarketArg,
+        /// Maximum price levels per side
+        #[arg(short, long)]
+        levels: Option<usize>,
+    },
+    /// Show recent market trades, newest first
+    Trades {
+        #[command(flatten)]
+        market: MarketArg,
+        /// Maximum trades to show
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
+    /// Show market last trade price and day volume
+    Stats {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+    /// Show market OHLCV candles, oldest first
+    Candles {
+        #[command(flatten)]
+        market: MarketArg,
+        /// Candle length in seconds
+        #[arg(short, long, default_value_t = 3600)]
+        interval: i64,
+    },
+    /// Quote a market order of `quantity` base against the book, by simulation
+    Quote {
+        #[command(flatten)]
+        market: MarketArg,
+        /// Taker side, bid buys from asks and ask sells to bids
+        #[arg(short, long)]
+        side: SideArg,
+        #[arg(short, long)]
+        quantity: String,
+    },
+    /// Show market TWAP from an earlier price observation until now
+    Twap {
+        #[command(flatten)]
+        market: MarketArg,
+        /// Unix timestamp of the earlier observation
+        #[arg(long)]
+        start_timestamp: i64,
+        /// Price accumulator of the earlier observation, as shown by `stats` or `twap`
+        #[arg(long)]
+        start_cumulative: u128,
+    },
+    /// Check market vaults cover resting orders and all open orders balances
+    Audit {
+        #[command(flatten)]
+        market: MarketArg,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config = Config::new(
+        &cli.env,
+        cli.rpc,
+        &cli.keypair,
+        cli.signer,
+        cli.offline,
+        cli.blockhash,
+        cli.json,
+    )?;
+
+    run(&config, cli.command)
+}
+
+fn load_market(config: &Config, market: &Pubkey) -> Result<(Market, MarketKeys)> {
+    Ok(fetch_market_keys(&config.rpc, market)?)
+}
+
+/// Signed decimal quote amount, pegged order offsets may be negative
+fn to_atomic_offset(value: &str, decimals: u8) -> Result<i64> {
+    let (negative, amount) = match value.strip_prefix('-') {
+        Some(amount) => (true, amount),
+        None => (false, value),
+    };
+    let offset = i64::try_from(to_atomic_amount(amount, decimals)?)?;
+    Ok(if negative { -offset } else { offset })
+}
+
+/// Addresses of every open orders account of `market`
+fn market_open_orders(config: &Config, market: &Pubkey) -> Result<Vec<Pubkey>> {
+    let accounts = config.rpc.get_program_accounts_with_config(
+        &hybrid_dex_client::ID,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    0,
+                    UserMarketOrders::DISCRIMINATOR.to_vec(),
+                )),
+                // market follows discriminator and owner address
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(40, market.to_bytes().to_vec())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 0,
+                    length: 0,
+                }),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        },
+    )?;
+
+    Ok(accounts.into_iter().map(|(address, _)| address).collect())
+}
+
+fn run(config: &Config, command: Command) -> Result<()> {
+    let signer = config.signer;
+
+    match command {
+        Command::Status => config.show(&GlobalView::from(&fetch_global_pool(&config.rpc)?)),
+        Command::Init {
+            order_per_user,
+            order_per_book,
+        } => config.submit(&[instructions::initialize(
+            &signer,
+            order_per_user,
+            order_per_book,
+        )]),
+        Command::ChangeAdmin { new_admin } => {
+            config.submit(&[instructions::transfer_admin(&signer, &new_admin)])
+        }
+        Command::ChangeConfig {
+            order_per_user,
+            order_per_book,
+        } => config.submit(&[instructions::change_config(
+            &signer,
+            order_per_user,
+            order_per_book,
+        )]),
+        Command::MigrateGlobal => config.submit(&[instructions::migrate_global_pool(&signer)]),
+        Command::CreateMarket {
+            base_mint,
+            quote_mint,
+            name,
+            order_per_user,
+            order_per_book,
+        } => {
+            let global = fetch_global_pool(&config.rpc)?;
+            config.submit(&[instructions::create_market(
+                &signer,
+                global.market_seq_num,
+                &base_mint,
+                &quote_mint,
+                &name,
+                order_per_user,
+                order_per_book,
+            )])
+        }
+        Command::CloseMarket { market } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            config.submit(&[instructions::close_market(&signer, &keys)])
+        }
+        Command::ResizeBook {
+            market,
+            order_per_book,
+        } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            config.submit(&[instructions::resize_book(&signer, &keys, order_per_book)])
+        }
+        Command::MigrateMarket { seed } => {
+            config.submit(&[instructions::migrate_market(&signer, seed)])
+        }
+        Command::MigrateBooks { market } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let bids = fetch_legacy_book(&config.rpc, &state.bids)?;
+
+            // legacy orders all rest on sub account 0
+            let mut makers: Vec<Pubkey> = vec![];
+            for order in bids.orders.iter().take(bids.orders_count as usize) {
+                let address = keys.user_market_orders(&order.owner, 0);
+                if !makers.contains(&address) {
+                    makers.push(address);
+                }
+            }
+
+            config.submit(&[instructions::migrate_books(&signer, &keys, &makers)])
+        }
+        Command::CreateTradeHistory { market } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            config.submit(&[instructions::create_trade_history(&signer, &keys)])
+        }
+        Command::CreateMarketStats { market } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            config.submit(&[instructions::create_market_stats(&signer, &keys)])
+        }
+        Command::CreatePeggedBook { market } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            config.submit(&[instructions::create_pegged_book(&signer, &keys)])
+        }
+        Command::CreateTriggerBook { market } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            config.submit(&[instructions::create_trigger_book(&signer, &keys)])
+        }
+        Command::SetOracle {
+            market,
+            kind,
+            oracle,
+            band_bps,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let (kind, oracle, oracle_pool) = match kind {
+                OracleArg::None => (OracleKind::None, oracle, None),
+                OracleArg::Pyth => (OracleKind::Pyth, oracle, None),
+                OracleArg::RaydiumClmm => {
+                    let pool = oracle.ok_or_else(|| anyhow!("--oracle pool is required"))?;
+                    let observation = fetch_clmm_observation(&config.rpc, &state, &pool)?;
+                    (OracleKind::RaydiumClmm, Some(observation), Some(pool))
+                }
+            };
+            config.submit(&[instructions::set_oracle(
+                &signer,
+                &keys,
+                kind,
+                oracle,
+                oracle_pool,
+                band_bps,
+            )])
+        }
+        Command::SetCandleIntervals { market, intervals } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            config.submit(&[instructions::set_candle_intervals(
+                &signer, &keys, &intervals,
+            )])
+        }
+        Command::SetOrderMinimums {
+            market,
+            min_order_value,
+            min_trigger_trade_value,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            config.submit(&[instructions::set_order_minimums(
+                &signer,
+                &keys,
+                to_atomic_amount(&min_order_value, state.quote_decimal)?,
+                to_atomic_amount(&min_trigger_trade_value, state.quote_decimal)?,
+            )])
+        }
+        Command::CreateUserOrders {
+            market,
+            account_index,
+        } => config.submit(&[instructions::create_open_orders(
+            &signer,
+            &market.market,
+            account_index,
+        )]),
+        Command::CloseUserOrders {
+            market,
+            account_index,
+        } => config.submit(&[instructions::close_open_orders(
+            &signer,
+            &market.market,
+            account_index,
+        )]),
+        Command::MigrateUserOrders {
+            market,
+            open_orders,
+        } => {
+            let owner = open_orders.owner.unwrap_or(signer);
+            config.submit(&[instructions::migrate_open_orders(
+                &signer,
+                &user_market_orders_address(&market.market, &owner, open_orders.account_index),
+            )])
+        }
+        Command::SetDelegate {
+            market,
+            account_index,
+            delegate,
+        } => config.submit(&[instructions::set_delegate(
+            &signer,
+            &market.market,
+            account_index,
+            delegate,
+        )]),
+        Command::Deposit {
+            market,
+            open_orders,
+            base,
+            quote,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let owner = open_orders.owner.unwrap_or(signer);
+            config.submit(&[instructions::deposit(
+                &signer,
+                &owner,
+                open_orders.account_index,
+                &keys,
+                to_atomic_amount(&base, state.base_decimal)?,
+                to_atomic_amount(&quote, state.quote_decimal)?,
+            )])
+        }
+        Command::Withdraw {
+            market,
+            open_orders,
+            base,
+            quote,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let owner = open_orders.owner.unwrap_or(signer);
+            let [create_base, create_quote] =
+                instructions::create_user_token_accounts(&signer, &owner, &keys);
+            config.submit(&[
+                create_base,
+                create_quote,
+                instructions::withdraw(
+                    &signer,
+                    &owner,
+                    open_orders.account_index,
+                    &keys,
+                    to_atomic_amount(&base, state.base_decimal)?,
+                    to_atomic_amount(&quote, state.quote_decimal)?,
+                ),
+            ])
+        }
+        Command::PlaceOrder {
+            market,
+            open_orders,
+            side,
+            price,
+            quantity,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let owner = open_orders.owner.unwrap_or(signer);
+            let price = to_atomic_amount(&price, state.quote_decimal)?;
+            let quantity = to_atomic_amount(&quantity, state.base_decimal)?;
+
+            let ix = match side {
+                SideArg::Bid => instructions::place_buy_order(
+                    &signer,
+                    &owner,
+                    open_orders.account_index,
+                    &keys,
+                    price,
+                    quantity,
+                ),
+                SideArg::Ask => instructions::place_sell_order(
+                    &signer,
+                    &owner,
+                    open_orders.account_index,
+                    &keys,
+                    price,
+                    quantity,
+                ),
+            };
+            config.submit(&[ix])
+        }
+        Command::CancelOrder {
+            market,
+            open_orders,
+            side,
+            order_id,
+        } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            let owner = open_orders.owner.unwrap_or(signer);
+
+            let ix = match side {
+                SideArg::Bid => instructions::cancel_buy_order(
+                    &signer,
+                    &owner,
+                    open_orders.account_index,
+                    &keys,
+                    order_id,
+                ),
+                SideArg::Ask => instructions::cancel_sell_order(
+                    &signer,
+                    &owner,
+                    open_orders.account_index,
+                    &keys,
+                    order_id,
+                ),
+            };
+            config.submit(&[ix])
+        }
+        Command::PlacePeggedOrder {
+            market,
+            open_orders,
+            side,
+            offset,
+            limit,
+            quantity,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let owner = open_orders.owner.unwrap_or(signer);
+            let side = match side {
+                SideArg::Bid => Side::Bid,
+                SideArg::Ask => Side::Ask,
+            };
+            config.submit(&[instructions::place_pegged_order(
+                &signer,
+                &owner,
+                open_orders.account_index,
+                &keys,
+                side,
+                to_atomic_offset(&offset, state.quote_decimal)?,
+                to_atomic_amount(&limit, state.quote_decimal)?,
+                to_atomic_amount(&quantity, state.base_decimal)?,
+            )])
+        }
+        Command::CancelPeggedOrder {
+            market,
+            open_orders,
+            order_id,
+        } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            let owner = open_orders.owner.unwrap_or(signer);
+            config.submit(&[instructions::cancel_pegged_order(
+                &signer,
+                &owner,
+                open_orders.account_index,
+                &keys,
+                order_id,
+            )])
+        }
+        Command::TakePeggedOrder {
+            market,
+            maker,
+            maker_account_index,
+            account_index,
+            order_id,
+            quantity,
+            limit,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let accounts = instructions::TakeAccounts {
+                taker: signer,
+                taker_account_index: account_index,
+                maker,
+                maker_account_index,
+            };
+
+            let ix = instructions::take_pegged_order(
+                &accounts,
+                &keys,
+                order_id,
+                to_atomic_amount(&quantity, state.base_decimal)?,
+                to_atomic_amount(&limit, state.quote_decimal)?,
+            );
+            let [create_base, create_quote] =
+                instructions::create_user_token_accounts(&signer, &signer, &keys);
+            config.submit(&[create_base, create_quote, ix])
+        }
+        Command::PlaceTriggerOrder {
+            market,
+            open_orders,
+            side,
+            source,
+            condition,
+            order_type,
+            trigger,
+            limit,
+            quantity,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let owner = open_orders.owner.unwrap_or(signer);
+            let params = TriggerOrderParams {
+                side: match side {
+                    SideArg::Bid => Side::Bid,
+                    SideArg::Ask => Side::Ask,
+                },
+                source: match source {
+                    TriggerSourceArg::Last => TriggerSource::LastPrice,
+                    TriggerSourceArg::Oracle => TriggerSource::Oracle,
+                },
+                condition: match condition {
+                    TriggerConditionArg::Stop => TriggerCondition::Stop,
+                    TriggerConditionArg::TakeProfit => TriggerCondition::TakeProfit,
+                },
+                order_type: match order_type {
+                    TriggerTypeArg::Limit => TriggerOrderType::Limit,
+                    TriggerTypeArg::Market => TriggerOrderType::Market,
+                },
+                trigger_price: to_atomic_amount(&trigger, state.quote_decimal)?,
+                limit_price: match limit {
+                    Some(limit) => to_atomic_amount(&limit, state.quote_decimal)?,
+                    None => 0,
+                },
+                quantity: to_atomic_amount(&quantity, state.base_decimal)?,
+            };
+
+            config.submit(&[instructions::place_trigger_order(
+                &signer,
+                &owner,
+                open_orders.account_index,
+                &keys,
+                params,
+            )])
+        }
+        Command::CancelTriggerOrder {
+            market,
+            open_orders,
+            order_id,
+        } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            let owner = open_orders.owner.unwrap_or(signer);
+            config.submit(&[instructions::cancel_trigger_order(
+                &signer,
+                &owner,
+                open_orders.account_index,
+                &keys,
+                order_id,
+            )])
+        }
+        Command::ExecuteTrigger { market, order_id } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let order = fetch_trigger_book(&config.rpc, &market.market)?
+                .orders
+                .into_iter()
+                .find(|order| order.order_id == order_id)
+                .ok_or_else(|| anyhow!("trigger order {order_id} not found"))?;
+
+            let makers = match order.order_type {
+                TriggerOrderType::Limit => vec![],
+                TriggerOrderType::Market => {
+                    let (bids, asks) = fetch_books(&config.rpc, &state)?;
+                    let book = match order.side {
+                        Side::Bid => &asks,
+                        Side::Ask => &bids,
+                    };
+                    instructions::trigger_makers(&keys, book, &order)
+                }
+            };
+
+            config.submit(&[instructions::execute_trigger(
+                &signer,
+                &order.owner,
+                order.account_index,
+                &keys,
+                order_id,
+                &makers,
+            )])
+        }
+        Command::TakeOrder {
+            market,
+            maker,
+            maker_account_index,
+            account_index,
+            side,
+            order_id,
+        } => {
+            let (_, keys) = load_market(config, &market.market)?;
+            let accounts = instructions::TakeAccounts {
+                taker: signer,
+                taker_account_index: account_index,
+                maker,
+                maker_account_index,
+            };
+
+            let ix = match side {
+                SideArg::Bid => instructions::take_buy_order(&accounts, &keys, order_id),
+                SideArg::Ask => instructions::take_sell_order(&accounts, &keys, order_id),
+            };
+            let [create_base, create_quote] =
+                instructions::create_user_token_accounts(&signer, &signer, &keys);
+            config.submit(&[create_base, create_quote, ix])
+        }
+        Command::PartialTakeOrder {
+            market,
+            maker,
+            maker_account_index,
+            account_index,
+            side,
+            order_id,
+            quantity,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let accounts = instructions::TakeAccounts {
+                taker: signer,
+                taker_account_index: account_index,
+                maker,
+                maker_account_index,
+            };
+            let amount = to_atomic_amount(&quantity, state.base_decimal)?;
+
+            let ix = match side {
+                SideArg::Bid => {
+                    instructions::partial_take_buy_order(&accounts, &keys, order_id, amount)
+                }
+                SideArg::Ask => {
+                    instructions::partial_take_sell_order(&accounts, &keys, order_id, amount)
+                }
+            };
+            let [create_base, create_quote] =
+                instructions::create_user_token_accounts(&signer, &signer, &keys);
+            config.submit(&[create_base, create_quote, ix])
+        }
+        Command::Market { market } => {
+            let (state, _) = load_market(config, &market.market)?;
+            config.show(&MarketView::new(&market.market, &state))
+        }
+        Command::AllMarkets { base, quote } => {
+            let accounts = config.rpc.get_program_accounts_with_config(
+                &hybrid_dex_client::ID,
+                RpcProgramAccountsConfig {
+                    filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                        0,
+                        Market::DISCRIMINATOR.to_vec(),
+                    ))]),
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
+                },
+            )?;
+
+            let mut markets = vec![];
+            for (address, account) in accounts {
+                let state = Market::try_deserialize(&mut &account.data[..])
+                    .map_err(|err| anyhow!("Failed to decode market {}: {}", address, err))?;
+                if base.is_none_or(|base| base == state.base_mint)
+                    && quote.is_none_or(|quote| quote == state.quote_mint)
+                {
+                    markets.push(MarketView::new(&address, &state));
+                }
+            }
+            markets.sort_by_key(|market| market.seed);
+
+            config.show(&markets)
+        }
+        Command::UserOrders {
+            market,
+            user_address,
+            account_index,
+        } => {
+            let orders = fetch_user_market_orders(
+                &config.rpc,
+                &market.market,
+                &user_address,
+                account_index,
+            )?;
+            config.show(&UserOrdersView::from(&orders))
+        }
+        Command::OrderBook { market } => {
+            let (state, _) = load_market(config, &market.market)?;
+            let (bids, asks) = fetch_books(&config.rpc, &state)?;
+
+            config.show(&OrderBookView {
+                market: market.market.to_string(),
+                bids: BookView::new(&bids, &state),
+                asks: BookView::new(&asks, &state),
+            })
+        }
+        Command::PeggedOrders { market } => {
+            let (state, _) = load_market(config, &market.market)?;
+            let pegged_book = fetch_pegged_book(&config.rpc, &market.market)?;
+
+            config.show(
+                &pegged_book
+                    .orders
+                    .iter()
+                    .map(|order| PeggedOrderView::new(order, &state))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        Command::TriggerOrders { market } => {
+            let (state, _) = load_market(config, &market.market)?;
+            let trigger_book = fetch_trigger_book(&config.rpc, &market.market)?;
+
+            config.show(
+                &trigger_book
+                    .orders
+                    .iter()
+                    .map(|order| TriggerOrderView::new(order, &state))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        Command::Depth { market, levels } => {
+            let (state, _) = load_market(config, &market.market)?;
+            let (bids, asks) = fetch_books(&config.rpc, &state)?;
+            let order_book = OrderBook::new(&bids, &asks);
+
+            config.show(&DepthView::new(
+                &market.market,
+                &order_book,
+                &order_book.depth(levels),
+                &state,
+            ))
+        }
+        Command::Trades { market, limit } => {
+            let (state, _) = load_market(config, &market.market)?;
+            let trades = fetch_recent_trades(&config.rpc, &market.market)?;
+
+            config.show(
+                &trades
+                    .iter()
+                    .take(limit.unwrap_or(trades.len()))
+                    .map(|trade| TradeView::new(trade, &state))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        Command::Stats { market } => {
+            let (state, _) = load_market(config, &market.market)?;
+            let stats = fetch_market_stats(&config.rpc, &market.market)?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+            let volume_24h = stats
+                .volume_24h(now)
+                .map_err(|err| anyhow!("Failed to sum day volume: {}", err))?;
+
+            config.show(&StatsView::new(&market.market, &stats, &state, volume_24h))
+        }
+        Command::Candles { market, interval } => {
+            let (state, _) = load_market(config, &market.market)?;
+            let stats = fetch_market_stats(&config.rpc, &market.market)?;
+            let candles = stats.candles(interval).ok_or_else(|| {
+                anyhow!(
+                    "Market keeps no {}s candles, intervals: {:?}",
+                    interval,
+                    stats
+                        .candle_series
+                        .iter()
+                        .map(|series| series.interval)
+                        .collect::<Vec<_>>()
+                )
+            })?;
+
+            config.show(
+                &candles
+                    .iter()
+                    .map(|candle| CandleView::new(candle, &state))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        Command::Quote {
+            market,
+            side,
+            quantity,
+        } => {
+            let (state, keys) = load_market(config, &market.market)?;
+            let side = match side {
+                SideArg::Bid => Side::Bid,
+                SideArg::Ask => Side::Ask,
+            };
+            let quote: MarketOrderQuote = config.simulate(instructions::quote_market_order(
+                &keys,
+                side,
+                to_atomic_amount(&quantity, state.base_decimal)?,
+            ))?;
+
+            config.show(&QuoteView::new(&market.market, &quote, &state))
+        }
+        Command::Twap {
+            market,
+            start_timestamp,
+            start_cumulative,
+        } => {
+            let (state, _) = load_market(config, &market.market)?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+            let start = PriceObservation {
+                timestamp: start_timestamp,
+                price_cumulative: start_cumulative,
+            };
+            let end = observe_price(&config.rpc, &market.market, now)?;
+
+            config.show(&TwapView::new(
+                &market.market,
+                &start,
+                &end,
+                twap(&start, &end)?,
+                &state,
+            ))
+        }
+        Command::Audit { market } => {
+            let open_orders = market_open_orders(config, &market.market)?;
+            let report = fetch_solvency_report(&config.rpc, &market.market, &open_orders)?;
+
+            config.show(&AuditView::new(&market.market, open_orders.len(), &report))
+        }
+    }
+}
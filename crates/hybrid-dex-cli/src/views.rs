@@ -0,0 +1,538 @@
+//! Serializable views of program accounts, amounts keep full u64 precision
+
+use hybrid_dex_client::{
+    to_ui_amount, Book, Candle, GlobalPool, L2Depth, Level, Market, MarketOrderQuote, MarketStats,
+    OpenedOrder, OracleKind, OrderBook, PeggedOrder, PriceObservation, Side, SolvencyReport, Trade,
+    TriggerCondition, TriggerOrder, TriggerOrderType, TriggerSource, UserMarketOrders,
+};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, Serialize)]
+pub struct GlobalView {
+    pub admin: String,
+    pub max_orders_per_user: u64,
+    pub max_orders_per_book: u64,
+    pub total_market_count: u64,
+    pub market_seq_num: u64,
+    pub version: u8,
+}
+
+impl From<&GlobalPool> for GlobalView {
+    fn from(global: &GlobalPool) -> GlobalView {
+        GlobalView {
+            admin: global.admin.to_string(),
+            max_orders_per_user: global.max_orders_per_user,
+            max_orders_per_book: global.max_orders_per_book,
+            total_market_count: global.total_market_count,
+            market_seq_num: global.market_seq_num,
+            version: global.version,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarketView {
+    pub address: String,
+    pub seed: u64,
+    pub name: String,
+    pub market_authority: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub base_decimal: u8,
+    pub quote_decimal: u8,
+    pub bids: String,
+    pub asks: String,
+    pub created_at: i64,
+    pub base_total_volume: u64,
+    pub quote_total_volume: u64,
+    pub order_seq_num: u64,
+    pub max_orders_per_user: u64,
+    pub max_orders_per_book: u64,
+    pub book_version: u8,
+    pub version: u8,
+    pub oracle_kind: String,
+    pub oracle: Option<String>,
+    pub price_band_bps: u16,
+    pub min_order_value: u64,
+    pub min_trigger_trade_value: u64,
+}
+
+impl MarketView {
+    pub fn new(address: &Pubkey, market: &Market) -> MarketView {
+        MarketView {
+            address: address.to_string(),
+            seed: market.seed,
+            name: market.name().to_string(),
+            market_authority: market.market_authority.to_string(),
+            base_mint: market.base_mint.to_string(),
+            quote_mint: market.quote_mint.to_string(),
+            base_decimal: market.base_decimal,
+            quote_decimal: market.quote_decimal,
+            bids: market.bids.to_string(),
+            asks: market.asks.to_string(),
+            created_at: market.created_at,
+            base_total_volume: market.base_total_volume,
+            quote_total_volume: market.quote_total_volume,
+            order_seq_num: market.order_seq_num,
+            max_orders_per_user: market.max_orders_per_user,
+            max_orders_per_book: market.max_orders_per_book,
+            book_version: market.book_version,
+            version: market.version,
+            oracle_kind: format!("{:?}", market.oracle_kind),
+            oracle: (market.oracle_kind != OracleKind::None).then(|| market.oracle.to_string()),
+            price_band_bps: market.price_band_bps,
+            min_order_value: market.min_order_value,
+            min_trigger_trade_value: market.min_trigger_trade_value,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderView {
+    pub order_id: u64,
+    pub owner: String,
+    pub account_index: u8,
+    pub price: u64,
+    pub ui_price: String,
+    pub quantity: u64,
+    pub ui_quantity: String,
+    pub created_at: i64,
+}
+
+impl OrderView {
+    pub fn new(order: &OpenedOrder, market: &Market) -> OrderView {
+        OrderView {
+            order_id: order.order_id,
+            owner: order.owner.to_string(),
+            account_index: order.account_index,
+            price: order.price,
+            ui_price: to_ui_amount(order.price, market.quote_decimal),
+            quantity: order.quantity,
+            ui_quantity: to_ui_amount(order.quantity, market.base_decimal),
+            created_at: order.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeggedOrderView {
+    pub order_id: u64,
+    pub owner: String,
+    pub account_index: u8,
+    pub side: &'static str,
+    pub price_offset: i64,
+    pub limit_price: u64,
+    pub ui_limit_price: String,
+    pub quantity: u64,
+    pub ui_quantity: String,
+    pub created_at: i64,
+}
+
+impl PeggedOrderView {
+    pub fn new(order: &PeggedOrder, market: &Market) -> PeggedOrderView {
+        PeggedOrderView {
+            order_id: order.order_id,
+            owner: order.owner.to_string(),
+            account_index: order.account_index,
+            side: match order.side {
+                Side::Bid => "bid",
+                Side::Ask => "ask",
+            },
+            price_offset: order.price_offset,
+            limit_price: order.limit_price,
+            ui_limit_price: to_ui_amount(order.limit_price, market.quote_decimal),
+            quantity: order.quantity,
+            ui_quantity: to_ui_amount(order.quantity, market.base_decimal),
+            created_at: order.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TriggerOrderView {
+    pub order_id: u64,
+    pub owner: String,
+    pub account_index: u8,
+    pub side: &'static str,
+    pub source: &'static str,
+    pub condition: &'static str,
+    pub order_type: &'static str,
+    pub trigger_price: u64,
+    pub ui_trigger_price: String,
+    pub limit_price: u64,
+    pub ui_limit_price: String,
+    pub quantity: u64,
+    pub ui_quantity: String,
+    pub bounty: u64,
+    pub created_at: i64,
+}
+
+impl TriggerOrderView {
+    pub fn new(order: &TriggerOrder, market: &Market) -> TriggerOrderView {
+        TriggerOrderView {
+            order_id: order.order_id,
+            owner: order.owner.to_string(),
+            account_index: order.account_index,
+            side: match order.side {
+                Side::Bid => "bid",
+                Side::Ask => "ask",
+            },
+            source: match order.source {
+                TriggerSource::LastPrice => "last",
+                TriggerSource::Oracle => "oracle",
+            },
+            condition: match order.condition {
+                TriggerCondition::Stop => "stop",
+                TriggerCondition::TakeProfit => "take-profit",
+            },
+            order_type: match order.order_type {
+                TriggerOrderType::Limit => "limit",
+                TriggerOrderType::Market => "market",
+            },
+            trigger_price: order.trigger_price,
+            ui_trigger_price: to_ui_amount(order.trigger_price, market.quote_decimal),
+            limit_price: order.limit_price,
+            ui_limit_price: to_ui_amount(order.limit_price, market.quote_decimal),
+            quantity: order.quantity,
+            ui_quantity: to_ui_amount(order.quantity, market.base_decimal),
+            bounty: order.bounty,
+            created_at: order.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BookView {
+    pub side: &'static str,
+    pub orders_count: u64,
+    pub orders: Vec<OrderView>,
+}
+
+impl BookView {
+    pub fn new(book: &Book, market: &Market) -> BookView {
+        BookView {
+            side: match book.side {
+                Side::Bid => "bid",
+                Side::Ask => "ask",
+            },
+            orders_count: book.orders_count,
+            orders: book
+                .orders
+                .iter()
+                .map(|order| OrderView::new(order, market))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderBookView {
+    pub market: String,
+    pub bids: BookView,
+    pub asks: BookView,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserOrdersView {
+    pub address: String,
+    pub market: String,
+    pub delegate: Option<String>,
+    pub account_index: u8,
+    pub opened_orders_count: u64,
+    pub base_deposit_total: u64,
+    pub quote_deposit_total: u64,
+    pub base_free: u64,
+    pub quote_free: u64,
+    pub base_total_volume: u64,
+    pub quote_total_volume: u64,
+    pub version: u8,
+}
+
+impl From<&UserMarketOrders> for UserOrdersView {
+    fn from(orders: &UserMarketOrders) -> UserOrdersView {
+        UserOrdersView {
+            address: orders.address.to_string(),
+            market: orders.market.to_string(),
+            delegate: (orders.delegate != Pubkey::default()).then(|| orders.delegate.to_string()),
+            account_index: orders.account_index,
+            opened_orders_count: orders.opened_orders_count,
+            base_deposit_total: orders.base_deposit_total,
+            quote_deposit_total: orders.quote_deposit_total,
+            base_free: orders.base_free,
+            quote_free: orders.quote_free,
+            base_total_volume: orders.base_total_volume,
+            quote_total_volume: orders.quote_total_volume,
+            version: orders.version,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LevelView {
+    pub price: u64,
+    pub ui_price: String,
+    pub quantity: u64,
+    pub ui_quantity: String,
+    pub cumulative_quantity: u64,
+    pub orders_count: u64,
+}
+
+impl LevelView {
+    pub fn new(level: &Level, market: &Market) -> LevelView {
+        LevelView {
+            price: level.price,
+            ui_price: to_ui_amount(level.price, market.quote_decimal),
+            quantity: level.quantity,
+            ui_quantity: to_ui_amount(level.quantity, market.base_decimal),
+            cumulative_quantity: level.cumulative_quantity,
+            orders_count: level.orders_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DepthView {
+    pub market: String,
+    pub best_bid: Option<u64>,
+    pub best_ask: Option<u64>,
+    pub spread: Option<u64>,
+    pub mid_price: Option<u64>,
+    pub bids: Vec<LevelView>,
+    pub asks: Vec<LevelView>,
+}
+
+impl DepthView {
+    pub fn new(
+        address: &Pubkey,
+        order_book: &OrderBook,
+        depth: &L2Depth,
+        market: &Market,
+    ) -> DepthView {
+        DepthView {
+            market: address.to_string(),
+            best_bid: order_book.best_bid(),
+            best_ask: order_book.best_ask(),
+            spread: order_book.spread(),
+            mid_price: order_book.mid_price(),
+            bids: depth
+                .bids
+                .iter()
+                .map(|level| LevelView::new(level, market))
+                .collect(),
+            asks: depth
+                .asks
+                .iter()
+                .map(|level| LevelView::new(level, market))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TradeView {
+    pub seq_num: u64,
+    pub order_id: u64,
+    pub taker_side: &'static str,
+    pub price: u64,
+    pub ui_price: String,
+    pub base_amount: u64,
+    pub ui_base_amount: String,
+    pub quote_amount: u64,
+    pub ui_quote_amount: String,
+    pub maker: String,
+    pub taker: String,
+    pub timestamp: i64,
+}
+
+impl TradeView {
+    pub fn new(trade: &Trade, market: &Market) -> TradeView {
+        TradeView {
+            seq_num: trade.seq_num,
+            order_id: trade.order_id,
+            taker_side: match trade.taker_side {
+                Side::Bid => "buy",
+                Side::Ask => "sell",
+            },
+            price: trade.price,
+            ui_price: to_ui_amount(trade.price, market.quote_decimal),
+            base_amount: trade.base_amount,
+            ui_base_amount: to_ui_amount(trade.base_amount, market.base_decimal),
+            quote_amount: trade.quote_amount,
+            ui_quote_amount: to_ui_amount(trade.quote_amount, market.quote_decimal),
+            maker: trade.maker.to_string(),
+            taker: trade.taker.to_string(),
+            timestamp: trade.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsView {
+    pub market: String,
+    pub last_price: u64,
+    pub ui_last_price: String,
+    pub last_trade_at: i64,
+    pub base_volume_24h: u64,
+    pub ui_base_volume_24h: String,
+    pub quote_volume_24h: u64,
+    pub ui_quote_volume_24h: String,
+    pub candle_intervals: Vec<i64>,
+    /// Price accumulator at `last_trade_at`, a string as it may exceed JSON integers
+    pub price_cumulative: String,
+}
+
+impl StatsView {
+    /// `volume_24h` is the base and quote day volume at the time of the request
+    pub fn new(
+        address: &Pubkey,
+        stats: &MarketStats,
+        market: &Market,
+        volume_24h: (u64, u64),
+    ) -> StatsView {
+        let (base_volume, quote_volume) = volume_24h;
+
+        StatsView {
+            market: address.to_string(),
+            last_price: stats.last_price,
+            ui_last_price: to_ui_amount(stats.last_price, market.quote_decimal),
+            last_trade_at: stats.last_trade_at,
+            base_volume_24h: base_volume,
+            ui_base_volume_24h: to_ui_amount(base_volume, market.base_decimal),
+            quote_volume_24h: quote_volume,
+            ui_quote_volume_24h: to_ui_amount(quote_volume, market.quote_decimal),
+            candle_intervals: stats
+                .candle_series
+                .iter()
+                .map(|series| series.interval)
+                .collect(),
+            price_cumulative: stats.price_cumulative.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuoteView {
+    pub market: String,
+    pub base_amount: u64,
+    pub ui_base_amount: String,
+    pub quote_amount: u64,
+    pub ui_quote_amount: String,
+    pub orders_filled: u64,
+    pub worst_price: u64,
+    pub ui_worst_price: String,
+}
+
+impl QuoteView {
+    pub fn new(address: &Pubkey, quote: &MarketOrderQuote, market: &Market) -> QuoteView {
+        QuoteView {
+            market: address.to_string(),
+            base_amount: quote.base_amount,
+            ui_base_amount: to_ui_amount(quote.base_amount, market.base_decimal),
+            quote_amount: quote.quote_amount,
+            ui_quote_amount: to_ui_amount(quote.quote_amount, market.quote_decimal),
+            orders_filled: quote.orders_filled,
+            worst_price: quote.worst_price,
+            ui_worst_price: to_ui_amount(quote.worst_price, market.quote_decimal),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwapView {
+    pub market: String,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    /// Accumulator at the end, start of a later window
+    pub end_price_cumulative: String,
+    pub twap: u64,
+    pub ui_twap: String,
+}
+
+impl TwapView {
+    pub fn new(
+        address: &Pubkey,
+        start: &PriceObservation,
+        end: &PriceObservation,
+        twap: u64,
+        market: &Market,
+    ) -> TwapView {
+        TwapView {
+            market: address.to_string(),
+            start_timestamp: start.timestamp,
+            end_timestamp: end.timestamp,
+            end_price_cumulative: end.price_cumulative.to_string(),
+            twap,
+            ui_twap: to_ui_amount(twap, market.quote_decimal),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CandleView {
+    pub open_time: i64,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub base_volume: u64,
+    pub ui_base_volume: String,
+    pub quote_volume: u64,
+    pub ui_quote_volume: String,
+}
+
+impl CandleView {
+    pub fn new(candle: &Candle, market: &Market) -> CandleView {
+        CandleView {
+            open_time: candle.open_time,
+            open: to_ui_amount(candle.open, market.quote_decimal),
+            high: to_ui_amount(candle.high, market.quote_decimal),
+            low: to_ui_amount(candle.low, market.quote_decimal),
+            close: to_ui_amount(candle.close, market.quote_decimal),
+            base_volume: candle.base_volume,
+            ui_base_volume: to_ui_amount(candle.base_volume, market.base_decimal),
+            quote_volume: candle.quote_volume,
+            ui_quote_volume: to_ui_amount(candle.quote_volume, market.quote_decimal),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditView {
+    pub market: String,
+    pub open_orders_accounts: usize,
+    pub solvent: bool,
+    pub base_locked: u64,
+    pub base_free: u64,
+    pub base_vault: u64,
+    pub quote_locked: u64,
+    pub quote_free: u64,
+    pub quote_vault: u64,
+    pub discrepancies: Vec<String>,
+}
+
+impl AuditView {
+    pub fn new(
+        address: &Pubkey,
+        open_orders_accounts: usize,
+        report: &SolvencyReport,
+    ) -> AuditView {
+        let audit = &report.audit;
+        AuditView {
+            market: address.to_string(),
+            open_orders_accounts,
+            solvent: report.is_solvent(),
+            base_locked: audit.base_locked,
+            base_free: audit.base_free,
+            base_vault: audit.base_vault,
+            quote_locked: audit.quote_locked,
+            quote_free: audit.quote_free,
+            quote_vault: audit.quote_vault,
+            discrepancies: report
+                .discrepancies
+                .iter()
+                .map(|discrepancy| format!("{:?}", discrepancy))
+                .collect(),
+        }
+    }
+}
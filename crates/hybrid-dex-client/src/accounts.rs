@@ -0,0 +1,206 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, AnchorDeserialize, Discriminator};
+use hybrid_dex::{
+    Book, BookV0, GlobalPool, Market, MarketStats, PeggedBook, Trade, TradeHistory, TriggerBook,
+    UserMarketOrders,
+};
+
+use crate::{
+    error::{ClientError, Result},
+    pda::{
+        global_pool_address, market_stats_address, pegged_book_address, trade_history_address,
+        trigger_book_address, user_market_orders_address, MarketKeys,
+    },
+    rpc::HybridDexRpc,
+};
+
+/// Decode anchor account data, checking its discriminator
+pub fn decode_account<T: AccountDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..]).map_err(|err| ClientError::AccountDecode(*address, err))
+}
+
+/// Decode the result a view instruction publishes as transaction return data
+pub fn decode_return_data<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_from_slice(data).map_err(|err| ClientError::InvalidReturnData(err.to_string()))
+}
+
+pub fn fetch_account<T: AccountDeserialize>(
+    rpc: &impl HybridDexRpc,
+    address: &Pubkey,
+) -> Result<T> {
+    let data = rpc
+        .get_account_data(address)?
+        .ok_or(ClientError::AccountNotFound(*address))?;
+
+    decode_account(address, &data)
+}
+
+pub fn fetch_global_pool(rpc: &impl HybridDexRpc) -> Result<GlobalPool> {
+    fetch_account(rpc, &global_pool_address())
+}
+
+pub fn fetch_market(rpc: &impl HybridDexRpc, market: &Pubkey) -> Result<Market> {
+    fetch_account(rpc, market)
+}
+
+/// Market state and keys, without the accounts a market created before them lacks
+pub fn fetch_market_keys(rpc: &impl HybridDexRpc, market: &Pubkey) -> Result<(Market, MarketKeys)> {
+    let state = fetch_market(rpc, market)?;
+    let keys = MarketKeys::from_market(&state);
+
+    Ok((state, keys))
+}
+
+/// Observation account of a Raydium CLMM `pool`, the oracle of a market following it
+pub fn fetch_clmm_observation(
+    rpc: &impl HybridDexRpc,
+    market: &Market,
+    pool: &Pubkey,
+) -> Result<Pubkey> {
+    let data = rpc
+        .get_account_data(pool)?
+        .ok_or(ClientError::AccountNotFound(*pool))?;
+
+    market
+        .clmm_observation(&data)
+        .map_err(|err| ClientError::AccountDecode(*pool, err))
+}
+
+pub fn fetch_book(rpc: &impl HybridDexRpc, book: &Pubkey) -> Result<Book> {
+    fetch_account(rpc, book)
+}
+
+/// Fetch a book still in its legacy format, before the market `book_version` is current
+pub fn fetch_legacy_book(rpc: &impl HybridDexRpc, book: &Pubkey) -> Result<BookV0> {
+    let data = rpc
+        .get_account_data(book)?
+        .ok_or(ClientError::AccountNotFound(*book))?;
+    if !data.starts_with(&Book::DISCRIMINATOR) {
+        return Err(ClientError::AccountDecode(
+            *book,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into(),
+        ));
+    }
+
+    BookV0::deserialize(&mut &data[8..])
+        .map_err(|err| ClientError::AccountDecode(*book, err.into()))
+}
+
+/// Fetch bids and asks books of a market in one request
+pub fn fetch_books(rpc: &impl HybridDexRpc, market: &Market) -> Result<(Book, Book)> {
+    let addresses = [market.bids, market.asks];
+    let mut accounts = rpc.get_multiple_account_data(&addresses)?.into_iter();
+
+    let mut next_book = |address: &Pubkey| -> Result<Book> {
+        let data = accounts
+            .next()
+            .flatten()
+            .ok_or(ClientError::AccountNotFound(*address))?;
+        decode_account(address, &data)
+    };
+
+    let bids = next_book(&addresses[0])?;
+    let asks = next_book(&addresses[1])?;
+
+    Ok((bids, asks))
+}
+
+pub fn fetch_trade_history(rpc: &impl HybridDexRpc, market: &Pubkey) -> Result<TradeHistory> {
+    fetch_account(rpc, &trade_history_address(market))
+}
+
+/// Recorded trades of a market, newest first
+pub fn fetch_recent_trades(rpc: &impl HybridDexRpc, market: &Pubkey) -> Result<Vec<Trade>> {
+    Ok(fetch_trade_history(rpc, market)?.recent())
+}
+
+pub fn fetch_market_stats(rpc: &impl HybridDexRpc, market: &Pubkey) -> Result<MarketStats> {
+    fetch_account(rpc, &market_stats_address(market))
+}
+
+pub fn fetch_pegged_book(rpc: &impl HybridDexRpc, market: &Pubkey) -> Result<PeggedBook> {
+    fetch_account(rpc, &pegged_book_address(market))
+}
+
+pub fn fetch_trigger_book(rpc: &impl HybridDexRpc, market: &Pubkey) -> Result<TriggerBook> {
+    fetch_account(rpc, &trigger_book_address(market))
+}
+
+pub fn fetch_user_market_orders(
+    rpc: &impl HybridDexRpc,
+    market: &Pubkey,
+    user: &Pubkey,
+    account_index: u8,
+) -> Result<UserMarketOrders> {
+    fetch_account(
+        rpc,
+        &user_market_orders_address(market, user, account_index),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AccountSerialize;
+
+    use super::*;
+    use crate::rpc::AccountStore;
+
+    fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = vec![];
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn fetch_decodes_stored_accounts() {
+        let keys = MarketKeys::new(3, Pubkey::new_unique(), Pubkey::new_unique());
+        let market = Market {
+            seed: keys.seed,
+            base_mint: keys.base_mint,
+            quote_mint: keys.quote_mint,
+            bids: keys.bids,
+            asks: keys.asks,
+            has_trade_history: true,
+            has_market_stats: true,
+            has_pegged_book: true,
+            has_trigger_book: true,
+            ..Market::default()
+        };
+
+        let mut store = AccountStore::default();
+        store.insert(keys.address, serialize(&market));
+
+        let (fetched, fetched_keys) = fetch_market_keys(&store, &keys.address).unwrap();
+        assert_eq!(fetched_keys, keys);
+
+        // keys skip the accounts added after a market until it records them
+        let legacy = Market {
+            has_trade_history: false,
+            has_market_stats: false,
+            has_pegged_book: false,
+            has_trigger_book: false,
+            ..fetched.clone()
+        };
+        assert_eq!(
+            MarketKeys::from_market(&legacy),
+            MarketKeys {
+                trade_history: None,
+                stats: None,
+                pegged_book: None,
+                trigger_book: None,
+                ..keys
+            }
+        );
+
+        // books are not stored yet
+        assert!(matches!(
+            fetch_books(&store, &fetched),
+            Err(ClientError::AccountNotFound(address)) if address == keys.bids
+        ));
+
+        // market data is not a global pool
+        assert!(matches!(
+            decode_account::<GlobalPool>(&keys.address, &serialize(&market)),
+            Err(ClientError::AccountDecode(..))
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+//! Conversion between human decimal amounts and token atomic units
+
+use crate::error::{ClientError, Result};
+
+/// Parse decimal string like `12.5` into atomic units with `decimals` precision
+pub fn to_atomic_amount(value: &str, decimals: u8) -> Result<u64> {
+    let invalid = || ClientError::InvalidAmount(value.to_string());
+
+    let (whole, fraction) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+    if whole.is_empty() && fraction.is_empty()
+        || fraction.len() > decimals as usize
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let scale = 10u64.checked_pow(decimals as u32).ok_or_else(invalid)?;
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let fraction: u64 = if fraction.is_empty() {
+        0
+    } else {
+        let padded = format!("{:0<width$}", fraction, width = decimals as usize);
+        padded.parse().map_err(|_| invalid())?
+    };
+
+    whole
+        .checked_mul(scale)
+        .and_then(|amount| amount.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+/// Format atomic units as decimal string without trailing zeros
+pub fn to_ui_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let scale = 10u128.pow(decimals as u32);
+    let whole = amount as u128 / scale;
+    let fraction = amount as u128 % scale;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+
+    let fraction = format!("{:0>width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_round_trip() {
+        assert_eq!(to_atomic_amount("12.5", 6).unwrap(), 12_500_000);
+        assert_eq!(to_atomic_amount(".000001", 6).unwrap(), 1);
+        assert_eq!(
+            to_atomic_amount("18446744073709551615", 0).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(to_ui_amount(12_500_000, 6), "12.5");
+        assert_eq!(to_ui_amount(u64::MAX, 9), "18446744073.709551615");
+
+        assert!(to_atomic_amount("1.0000001", 6).is_err());
+        assert!(to_atomic_amount("-1", 6).is_err());
+        assert!(to_atomic_amount(".", 6).is_err());
+        assert!(to_atomic_amount("18446744073709551616", 0).is_err());
+    }
+}
@@ -0,0 +1,442 @@
+//! Off-chain vault solvency check, the counterpart of the `audit_market` instruction
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::TokenAccount;
+use hybrid_dex::{
+    Book, Market, MarketAudit, OpenedOrder, PeggedBook, PeggedOrder, Side, TriggerBook,
+    TriggerOrder, UserMarketOrders,
+};
+
+use crate::{
+    accounts::{decode_account, fetch_account, fetch_books, fetch_market_keys},
+    error::Result,
+    pda::MarketKeys,
+    rpc::HybridDexRpc,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Asset {
+    Base,
+    Quote,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// Book `orders_count` differs from the stored orders
+    OrdersCount {
+        side: Side,
+        orders_count: u64,
+        orders_len: u64,
+    },
+    /// Vault holds less than resting orders and free balances
+    VaultShortfall {
+        asset: Asset,
+        required: u64,
+        vault: u64,
+    },
+    /// Resting orders of an open orders account differ from its deposit total
+    UserDeposit {
+        owner: Pubkey,
+        account_index: u8,
+        asset: Asset,
+        orders_total: u64,
+        deposit_total: u64,
+    },
+    /// Resting order whose open orders account was not checked
+    UnknownOrder {
+        side: Side,
+        order_id: u64,
+        owner: Pubkey,
+        account_index: u8,
+    },
+    /// Open orders account belongs to another market
+    ForeignOpenOrders { owner: Pubkey, account_index: u8 },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolvencyReport {
+    /// Same totals `audit_market` returns for the checked open orders accounts
+    pub audit: MarketAudit,
+    pub base_deposits: u64,
+    pub quote_deposits: u64,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl SolvencyReport {
+    pub fn is_solvent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// Funds a resting order locks, base quantity of asks and quote value of bids
+fn locked_amount(market: &Market, book: &Book, order: &OpenedOrder) -> u64 {
+    match book.side {
+        Side::Bid => market.bid_locked_quote(order).unwrap_or(u64::MAX),
+        Side::Ask => order.quantity,
+    }
+}
+
+fn orders_total(
+    market: &Market,
+    book: &Book,
+    pegged: &[PeggedOrder],
+    triggers: &[TriggerOrder],
+    open_orders: Option<&UserMarketOrders>,
+) -> u64 {
+    let book_total = book
+        .orders
+        .iter()
+        .filter(|order| open_orders.is_none_or(|open_orders| open_orders.is_order_owner(order)))
+        .fold(0u64, |total, order| {
+            total.saturating_add(locked_amount(market, book, order))
+        });
+
+    // pegged and trigger bids lock quote at their limit price
+    let pegged_total = pegged
+        .iter()
+        .filter(|order| order.side == book.side)
+        .filter(|order| {
+            open_orders.is_none_or(|open_orders| open_orders.is_pegged_order_owner(order))
+        })
+        .fold(book_total, |total, order| {
+            let locked = order
+                .locked_amount(order.quantity, market.base_decimal)
+                .unwrap_or(u64::MAX);
+            total.saturating_add(locked)
+        });
+
+    triggers
+        .iter()
+        .filter(|order| order.side == book.side)
+        .filter(|order| {
+            open_orders.is_none_or(|open_orders| open_orders.is_trigger_order_owner(order))
+        })
+        .fold(pegged_total, |total, order| {
+            let locked = order
+                .locked_amount(order.quantity, market.base_decimal)
+                .unwrap_or(u64::MAX);
+            total.saturating_add(locked)
+        })
+}
+
+/// Compare resting orders and open orders balances against vault amounts,
+/// `open_orders` should hold every open orders account of the market
+#[allow(clippy::too_many_arguments)]
+pub fn check_solvency(
+    market: &Market,
+    bids: &Book,
+    asks: &Book,
+    pegged: &[PeggedOrder],
+    triggers: &[TriggerOrder],
+    open_orders: &[UserMarketOrders],
+    base_vault: u64,
+    quote_vault: u64,
+) -> SolvencyReport {
+    let address = MarketKeys::from_market(market).address;
+    let mut report = SolvencyReport {
+        audit: MarketAudit {
+            base_locked: orders_total(market, asks, pegged, triggers, None),
+            quote_locked: orders_total(market, bids, pegged, triggers, None),
+            base_vault,
+            quote_vault,
+            ..MarketAudit::default()
+        },
+        ..SolvencyReport::default()
+    };
+
+    for book in [bids, asks] {
+        if book.orders_count != book.orders.len() as u64 {
+            report.discrepancies.push(Discrepancy::OrdersCount {
+                side: book.side,
+                orders_count: book.orders_count,
+                orders_len: book.orders.len() as u64,
+            });
+        }
+
+        for order in book.orders.iter() {
+            if !open_orders
+                .iter()
+                .any(|orders| orders.is_order_owner(order))
+            {
+                report.discrepancies.push(Discrepancy::UnknownOrder {
+                    side: book.side,
+                    order_id: order.order_id,
+                    owner: order.owner,
+                    account_index: order.account_index,
+                });
+            }
+        }
+    }
+
+    for order in pegged {
+        if !open_orders
+            .iter()
+            .any(|orders| orders.is_pegged_order_owner(order))
+        {
+            report.discrepancies.push(Discrepancy::UnknownOrder {
+                side: order.side,
+                order_id: order.order_id,
+                owner: order.owner,
+                account_index: order.account_index,
+            });
+        }
+    }
+
+    for order in triggers {
+        if !open_orders
+            .iter()
+            .any(|orders| orders.is_trigger_order_owner(order))
+        {
+            report.discrepancies.push(Discrepancy::UnknownOrder {
+                side: order.side,
+                order_id: order.order_id,
+                owner: order.owner,
+                account_index: order.account_index,
+            });
+        }
+    }
+
+    for orders in open_orders {
+        if orders.market != address {
+            report.discrepancies.push(Discrepancy::ForeignOpenOrders {
+                owner: orders.address,
+                account_index: orders.account_index,
+            });
+            continue;
+        }
+
+        let audit = &mut report.audit;
+        audit.base_free = audit.base_free.saturating_add(orders.base_free);
+        audit.quote_free = audit.quote_free.saturating_add(orders.quote_free);
+        report.base_deposits = report
+            .base_deposits
+            .saturating_add(orders.base_deposit_total);
+        report.quote_deposits = report
+            .quote_deposits
+            .saturating_add(orders.quote_deposit_total);
+
+        for (asset, book, deposit_total) in [
+            (Asset::Base, asks, orders.base_deposit_total),
+            (Asset::Quote, bids, orders.quote_deposit_total),
+        ] {
+            let total = orders_total(market, book, pegged, triggers, Some(orders));
+            if total != deposit_total {
+                report.discrepancies.push(Discrepancy::UserDeposit {
+                    owner: orders.address,
+                    account_index: orders.account_index,
+                    asset,
+                    orders_total: total,
+                    deposit_total,
+                });
+            }
+        }
+    }
+
+    let audit = report.audit;
+    for (asset, required, vault) in [
+        (
+            Asset::Base,
+            audit.base_locked.saturating_add(audit.base_free),
+            base_vault,
+        ),
+        (
+            Asset::Quote,
+            audit.quote_locked.saturating_add(audit.quote_free),
+            quote_vault,
+        ),
+    ] {
+        if required > vault {
+            report.discrepancies.push(Discrepancy::VaultShortfall {
+                asset,
+                required,
+                vault,
+            });
+        }
+    }
+
+    report
+}
+
+/// Vault token amount, zero before the vault is created
+pub fn fetch_vault_amount(rpc: &impl HybridDexRpc, vault: &Pubkey) -> Result<u64> {
+    match rpc.get_account_data(vault)? {
+        Some(data) => Ok(decode_account::<TokenAccount>(vault, &data)?.amount),
+        None => Ok(0),
+    }
+}
+
+/// Fetch market state and check solvency against the given open orders accounts
+pub fn fetch_solvency_report(
+    rpc: &impl HybridDexRpc,
+    market: &Pubkey,
+    open_orders: &[Pubkey],
+) -> Result<SolvencyReport> {
+    let (state, keys) = fetch_market_keys(rpc, market)?;
+    let (bids, asks) = fetch_books(rpc, &state)?;
+    // markets created before pegged and trigger orders have no such book until one is created
+    let pegged = match keys.pegged_book {
+        Some(address) => fetch_account::<PeggedBook>(rpc, &address)?.orders,
+        None => vec![],
+    };
+    let triggers = match keys.trigger_book {
+        Some(address) => fetch_account::<TriggerBook>(rpc, &address)?.orders,
+        None => vec![],
+    };
+
+    let open_orders = open_orders
+        .iter()
+        .map(|address| fetch_account(rpc, address))
+        .collect::<Result<Vec<UserMarketOrders>>>()?;
+
+    Ok(check_solvency(
+        &state,
+        &bids,
+        &asks,
+        &pegged,
+        &triggers,
+        &open_orders,
+        fetch_vault_amount(rpc, &keys.base_vault)?,
+        fetch_vault_amount(rpc, &keys.quote_vault)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use hybrid_dex::{TriggerCondition, TriggerOrderType, TriggerSource, BOOK_VERSION};
+
+    use super::*;
+
+    #[test]
+    fn reports_discrepancies() {
+        let state = Market {
+            book_version: BOOK_VERSION,
+            ..Market::default()
+        };
+        let market = MarketKeys::from_market(&state).address;
+        let maker = Pubkey::new_unique();
+
+        let bid = OpenedOrder {
+            order_id: 0,
+            owner: maker,
+            price: 2,
+            quantity: 5,
+            created_at: 0,
+            account_index: 0,
+        };
+        let bids = Book {
+            side: Side::Bid,
+            market,
+            orders_count: 1,
+            orders: vec![bid],
+        };
+        let asks = Book {
+            side: Side::Ask,
+            market,
+            orders_count: 0,
+            orders: vec![],
+        };
+        let open_orders = UserMarketOrders {
+            address: maker,
+            market,
+            quote_deposit_total: 10,
+            quote_free: 5,
+            ..UserMarketOrders::default()
+        };
+
+        let report = check_solvency(
+            &state,
+            &bids,
+            &asks,
+            &[],
+            &[],
+            std::slice::from_ref(&open_orders),
+            0,
+            15,
+        );
+        assert!(report.is_solvent());
+        assert_eq!(report.audit.quote_locked, 10);
+        assert_eq!(report.audit.quote_free, 5);
+        assert_eq!(report.quote_deposits, 10);
+
+        // pegged bid locks quote at its limit price
+        let pegged = PeggedOrder {
+            order_id: 2,
+            owner: maker,
+            account_index: 0,
+            side: Side::Bid,
+            price_offset: -1,
+            limit_price: 3,
+            quantity: 2,
+            created_at: 0,
+        };
+        let with_pegged = UserMarketOrders {
+            quote_deposit_total: 16,
+            ..open_orders.clone()
+        };
+        let report = check_solvency(&state, &bids, &asks, &[pegged], &[], &[with_pegged], 0, 21);
+        assert!(report.is_solvent());
+        assert_eq!(report.audit.quote_locked, 16);
+
+        // trigger ask escrows its base quantity
+        let trigger = TriggerOrder {
+            order_id: 3,
+            owner: maker,
+            account_index: 0,
+            side: Side::Ask,
+            source: TriggerSource::LastPrice,
+            condition: TriggerCondition::Stop,
+            order_type: TriggerOrderType::Market,
+            trigger_price: 2,
+            limit_price: 0,
+            quantity: 4,
+            bounty: 0,
+            created_at: 0,
+        };
+        let with_trigger = UserMarketOrders {
+            base_deposit_total: 4,
+            ..open_orders.clone()
+        };
+        let report = check_solvency(
+            &state,
+            &bids,
+            &asks,
+            &[],
+            &[trigger],
+            &[with_trigger],
+            4,
+            15,
+        );
+        assert!(report.is_solvent());
+        assert_eq!(report.audit.base_locked, 4);
+
+        // vault short by one and order of an unchecked sub account
+        let mut bids = bids;
+        bids.orders.push(OpenedOrder {
+            order_id: 1,
+            account_index: 1,
+            ..bid
+        });
+        let report = check_solvency(&state, &bids, &asks, &[], &[], &[open_orders], 0, 14);
+        assert_eq!(
+            report.discrepancies,
+            vec![
+                Discrepancy::OrdersCount {
+                    side: Side::Bid,
+                    orders_count: 1,
+                    orders_len: 2,
+                },
+                Discrepancy::UnknownOrder {
+                    side: Side::Bid,
+                    order_id: 1,
+                    owner: maker,
+                    account_index: 1,
+                },
+                Discrepancy::VaultShortfall {
+                    asset: Asset::Quote,
+                    required: 25,
+                    vault: 14,
+                },
+            ]
+        );
+    }
+}
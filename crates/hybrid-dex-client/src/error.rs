@@ -0,0 +1,20 @@
+use anchor_lang::prelude::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("Account {0} could not be decoded: {1}")]
+    AccountDecode(Pubkey, anchor_lang::error::Error),
+    #[error("Return data could not be decoded: {0}")]
+    InvalidReturnData(String),
+    #[error("Invalid decimal amount: {0}")]
+    InvalidAmount(String),
+    #[error("TWAP could not be computed: {0}")]
+    Twap(anchor_lang::error::Error),
+    #[error("Rpc request failed: {0}")]
+    Rpc(String),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
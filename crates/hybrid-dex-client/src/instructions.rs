@@ -0,0 +1,1035 @@
+//! Instruction builders, one per `hybrid_dex` program instruction
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program, sysvar,
+    },
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        self, spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token,
+};
+use hybrid_dex::{
+    accounts as ix_accounts, instruction as ix_data, Book, OracleKind, Side, TriggerOrder,
+    TriggerOrderParams, ID,
+};
+
+use crate::pda::{
+    asks_book_address, bids_book_address, global_pool_address, market_address,
+    market_stats_address, pegged_book_address, trade_history_address, trigger_book_address,
+    user_market_orders_address, MarketKeys,
+};
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn initialize(
+    admin: &Pubkey,
+    max_orders_per_user: u64,
+    max_orders_per_book: u64,
+) -> Instruction {
+    build(
+        ix_accounts::Initialize {
+            admin: *admin,
+            global_pool: global_pool_address(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::Initialize {
+            max_orders_per_user,
+            max_orders_per_book,
+        },
+    )
+}
+
+pub fn migrate_global_pool(admin: &Pubkey) -> Instruction {
+    build(
+        ix_accounts::MigrateGlobalPool {
+            admin: *admin,
+            global_pool: global_pool_address(),
+            system_program: system_program::ID,
+        },
+        ix_data::MigrateGlobalPool {},
+    )
+}
+
+pub fn transfer_admin(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build(
+        ix_accounts::TransferAdmin {
+            admin: *admin,
+            global_pool: global_pool_address(),
+        },
+        ix_data::TransferAdmin {
+            new_admin: *new_admin,
+        },
+    )
+}
+
+pub fn change_config(
+    admin: &Pubkey,
+    max_orders_per_user: Option<u64>,
+    max_orders_per_book: Option<u64>,
+) -> Instruction {
+    build(
+        ix_accounts::ChangeConfig {
+            admin: *admin,
+            global_pool: global_pool_address(),
+        },
+        ix_data::ChangeConfig {
+            max_orders_per_user,
+            max_orders_per_book,
+        },
+    )
+}
+
+/// `market_seq_num` is the current `GlobalPool.market_seq_num`, used as new market seed
+pub fn create_market(
+    authority: &Pubkey,
+    market_seq_num: u64,
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+    name: &str,
+    max_orders_per_user: u64,
+    max_orders_per_book: u64,
+) -> Instruction {
+    let market = market_address(market_seq_num);
+
+    build(
+        ix_accounts::CreateMarket {
+            authority: *authority,
+            global_pool: global_pool_address(),
+            market,
+            base_mint: *base_mint,
+            quote_mint: *quote_mint,
+            bids_book: bids_book_address(&market),
+            asks_book: asks_book_address(&market),
+            trade_history: trade_history_address(&market),
+            market_stats: market_stats_address(&market),
+            pegged_book: pegged_book_address(&market),
+            trigger_book: trigger_book_address(&market),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::CreateMarket {
+            name: name.to_string(),
+            max_orders_per_user,
+            max_orders_per_book,
+        },
+    )
+}
+
+pub fn close_market(authority: &Pubkey, market: &MarketKeys) -> Instruction {
+    build(
+        ix_accounts::CloseMarket {
+            authority: *authority,
+            global_pool: global_pool_address(),
+            market: market.address,
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            bids_book: market.bids,
+            asks_book: market.asks,
+            trade_history: market.trade_history,
+            market_stats: market.stats,
+            pegged_book: market.pegged_book,
+            trigger_book: market.trigger_book,
+            base_vault_account: market.base_vault,
+            quote_vault_account: market.quote_vault,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::CloseMarket { seed: market.seed },
+    )
+}
+
+/// Trade history of a market created before trades were recorded
+pub fn create_trade_history(payer: &Pubkey, market: &MarketKeys) -> Instruction {
+    build(
+        ix_accounts::CreateTradeHistory {
+            payer: *payer,
+            market: market.address,
+            trade_history: trade_history_address(&market.address),
+            system_program: system_program::ID,
+        },
+        ix_data::CreateTradeHistory { seed: market.seed },
+    )
+}
+
+/// Stats of a market created before candles were recorded
+pub fn create_market_stats(payer: &Pubkey, market: &MarketKeys) -> Instruction {
+    build(
+        ix_accounts::CreateMarketStats {
+            payer: *payer,
+            market: market.address,
+            market_stats: market_stats_address(&market.address),
+            system_program: system_program::ID,
+        },
+        ix_data::CreateMarketStats { seed: market.seed },
+    )
+}
+
+/// Pegged book of a market created before pegged orders existed
+pub fn create_pegged_book(payer: &Pubkey, market: &MarketKeys) -> Instruction {
+    build(
+        ix_accounts::CreatePeggedBook {
+            payer: *payer,
+            market: market.address,
+            pegged_book: pegged_book_address(&market.address),
+            system_program: system_program::ID,
+        },
+        ix_data::CreatePeggedBook { seed: market.seed },
+    )
+}
+
+/// Trigger book of a market created before trigger orders existed
+pub fn create_trigger_book(payer: &Pubkey, market: &MarketKeys) -> Instruction {
+    build(
+        ix_accounts::CreateTriggerBook {
+            payer: *payer,
+            market: market.address,
+            trigger_book: trigger_book_address(&market.address),
+            system_program: system_program::ID,
+        },
+        ix_data::CreateTriggerBook { seed: market.seed },
+    )
+}
+
+/// `oracle` is required unless `oracle_kind` is `OracleKind::None`
+/// `oracle_pool` is the Raydium CLMM pool of `oracle` observations, unused by other kinds
+pub fn set_oracle(
+    authority: &Pubkey,
+    market: &MarketKeys,
+    oracle_kind: OracleKind,
+    oracle: Option<Pubkey>,
+    oracle_pool: Option<Pubkey>,
+    price_band_bps: u16,
+) -> Instruction {
+    build(
+        ix_accounts::SetOracle {
+            authority: *authority,
+            market: market.address,
+            oracle,
+            oracle_pool,
+        },
+        ix_data::SetOracle {
+            seed: market.seed,
+            oracle_kind,
+            price_band_bps,
+        },
+    )
+}
+
+pub fn set_candle_intervals(
+    authority: &Pubkey,
+    market: &MarketKeys,
+    intervals: &[i64],
+) -> Instruction {
+    build(
+        ix_accounts::SetCandleIntervals {
+            authority: *authority,
+            market: market.address,
+            market_stats: market_stats_address(&market.address),
+        },
+        ix_data::SetCandleIntervals {
+            seed: market.seed,
+            intervals: intervals.to_vec(),
+        },
+    )
+}
+
+pub fn set_order_minimums(
+    authority: &Pubkey,
+    market: &MarketKeys,
+    min_order_value: u64,
+    min_trigger_trade_value: u64,
+) -> Instruction {
+    build(
+        ix_accounts::SetOrderMinimums {
+            authority: *authority,
+            market: market.address,
+        },
+        ix_data::SetOrderMinimums {
+            seed: market.seed,
+            min_order_value,
+            min_trigger_trade_value,
+        },
+    )
+}
+
+pub fn resize_book(
+    authority: &Pubkey,
+    market: &MarketKeys,
+    max_orders_per_book: u64,
+) -> Instruction {
+    build(
+        ix_accounts::ResizeBook {
+            authority: *authority,
+            global_pool: global_pool_address(),
+            market: market.address,
+            bids_book: market.bids,
+            asks_book: market.asks,
+            system_program: system_program::ID,
+        },
+        ix_data::ResizeBook {
+            seed: market.seed,
+            max_orders_per_book,
+        },
+    )
+}
+
+/// `open_orders` are open orders accounts of the market whose free balance is audited
+pub fn audit_market(market: &MarketKeys, open_orders: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        ix_accounts::AuditMarket {
+            market: market.address,
+            bids_book: market.bids,
+            asks_book: market.asks,
+            pegged_book: market.pegged_book,
+            trigger_book: market.trigger_book,
+            base_vault_account: market.base_vault,
+            quote_vault_account: market.quote_vault,
+        },
+        ix_data::AuditMarket { seed: market.seed },
+    );
+    ix.accounts.extend(
+        open_orders
+            .iter()
+            .map(|address| AccountMeta::new_readonly(*address, false)),
+    );
+    ix
+}
+
+/// View instruction, simulate it and decode `BestPrices` with `decode_return_data`
+pub fn get_best_prices(market: &MarketKeys) -> Instruction {
+    build(
+        ix_accounts::GetBestPrices {
+            market: market.address,
+            bids_book: market.bids,
+            asks_book: market.asks,
+        },
+        ix_data::GetBestPrices { seed: market.seed },
+    )
+}
+
+/// View instruction returning `MarketOrderQuote` of a `side` taker for `amount` base
+pub fn quote_market_order(market: &MarketKeys, side: Side, amount: u64) -> Instruction {
+    build(
+        ix_accounts::QuoteMarketOrder {
+            market: market.address,
+            bids_book: market.bids,
+            asks_book: market.asks,
+        },
+        ix_data::QuoteMarketOrder {
+            seed: market.seed,
+            side,
+            amount,
+        },
+    )
+}
+
+/// View instruction returning `UserPosition` of an open orders account
+pub fn get_user_position(market: &MarketKeys, user_open_orders: &Pubkey) -> Instruction {
+    build(
+        ix_accounts::GetUserPosition {
+            market: market.address,
+            user_open_orders: *user_open_orders,
+        },
+        ix_data::GetUserPosition { seed: market.seed },
+    )
+}
+
+/// Market is addressed by seed, legacy layouts can't be loaded into `MarketKeys`,
+/// the global pool must be migrated first
+pub fn migrate_market(payer: &Pubkey, seed: u64) -> Instruction {
+    let market = market_address(seed);
+
+    build(
+        ix_accounts::MigrateMarket {
+            payer: *payer,
+            global_pool: global_pool_address(),
+            market,
+            bids_book: bids_book_address(&market),
+            system_program: system_program::ID,
+        },
+        ix_data::MigrateMarket { seed },
+    )
+}
+
+/// `open_orders` are open orders accounts of every resting bid maker
+pub fn migrate_books(
+    authority: &Pubkey,
+    market: &MarketKeys,
+    open_orders: &[Pubkey],
+) -> Instruction {
+    let mut ix = build(
+        ix_accounts::MigrateBooks {
+            authority: *authority,
+            market: market.address,
+            bids_book: market.bids,
+            asks_book: market.asks,
+            system_program: system_program::ID,
+        },
+        ix_data::MigrateBooks { seed: market.seed },
+    );
+    ix.accounts.extend(
+        open_orders
+            .iter()
+            .map(|address| AccountMeta::new(*address, false)),
+    );
+    ix
+}
+
+pub fn create_open_orders(user: &Pubkey, market: &Pubkey, account_index: u8) -> Instruction {
+    build(
+        ix_accounts::CreateOpenOrders {
+            user: *user,
+            market: *market,
+            user_open_orders: user_market_orders_address(market, user, account_index),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::CreateOpenOrders { account_index },
+    )
+}
+
+pub fn migrate_open_orders(payer: &Pubkey, user_open_orders: &Pubkey) -> Instruction {
+    build(
+        ix_accounts::MigrateOpenOrders {
+            payer: *payer,
+            user_open_orders: *user_open_orders,
+            system_program: system_program::ID,
+        },
+        ix_data::MigrateOpenOrders {},
+    )
+}
+
+pub fn close_open_orders(user: &Pubkey, market: &Pubkey, account_index: u8) -> Instruction {
+    build(
+        ix_accounts::CloseOpenOrders {
+            user: *user,
+            market: *market,
+            user_open_orders: user_market_orders_address(market, user, account_index),
+            system_program: system_program::ID,
+        },
+        ix_data::CloseOpenOrders {},
+    )
+}
+
+pub fn set_delegate(
+    user: &Pubkey,
+    market: &Pubkey,
+    account_index: u8,
+    delegate: Option<Pubkey>,
+) -> Instruction {
+    build(
+        ix_accounts::SetDelegate {
+            user: *user,
+            market: *market,
+            user_open_orders: user_market_orders_address(market, user, account_index),
+        },
+        ix_data::SetDelegate { delegate },
+    )
+}
+
+/// Idempotently create the base and quote ATAs of `owner`, withdraw and take
+/// paths no longer create the token accounts they pay out to
+pub fn create_user_token_accounts(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    market: &MarketKeys,
+) -> [Instruction; 2] {
+    [market.base_mint, market.quote_mint]
+        .map(|mint| create_associated_token_account_idempotent(payer, owner, &mint, &token::ID))
+}
+
+/// `authority` is the open orders owner or its delegate
+pub fn deposit(
+    authority: &Pubkey,
+    user: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    base_amount: u64,
+    quote_amount: u64,
+) -> Instruction {
+    build(
+        ix_accounts::Deposit {
+            authority: *authority,
+            user: *user,
+            market: market.address,
+            user_open_orders: market.user_market_orders(user, account_index),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            user_base_token_account: market.user_base_token_account(user),
+            user_quote_token_account: market.user_quote_token_account(user),
+            base_vault_account: market.base_vault,
+            quote_vault_account: market.quote_vault,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::Deposit {
+            base_amount,
+            quote_amount,
+        },
+    )
+}
+
+/// `authority` is the open orders owner or its delegate
+pub fn withdraw(
+    authority: &Pubkey,
+    user: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    base_amount: u64,
+    quote_amount: u64,
+) -> Instruction {
+    build(
+        ix_accounts::Withdraw {
+            authority: *authority,
+            user: *user,
+            market: market.address,
+            user_open_orders: market.user_market_orders(user, account_index),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            user_base_token_account: market.user_base_token_account(user),
+            user_quote_token_account: market.user_quote_token_account(user),
+            base_vault_account: market.base_vault,
+            quote_vault_account: market.quote_vault,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::Withdraw {
+            seed: market.seed,
+            base_amount,
+            quote_amount,
+        },
+    )
+}
+
+/// `authority` is the open orders owner or its delegate
+pub fn place_buy_order(
+    authority: &Pubkey,
+    maker: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    price: u64,
+    quantity: u64,
+) -> Instruction {
+    build(
+        ix_accounts::PlaceBuyOrder {
+            authority: *authority,
+            maker: *maker,
+            market: market.address,
+            user_open_orders: market.user_market_orders(maker, account_index),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            user_quote_token_account: market.user_quote_token_account(maker),
+            quote_vault_account: market.quote_vault,
+            bids_book: market.bids,
+            oracle: market.oracle,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::PlaceBuyOrder { price, quantity },
+    )
+}
+
+/// `authority` is the open orders owner or its delegate
+pub fn place_sell_order(
+    authority: &Pubkey,
+    maker: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    price: u64,
+    quantity: u64,
+) -> Instruction {
+    build(
+        ix_accounts::PlaceSellOrder {
+            authority: *authority,
+            maker: *maker,
+            market: market.address,
+            user_open_orders: market.user_market_orders(maker, account_index),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            user_base_token_account: market.user_base_token_account(maker),
+            base_vault_account: market.base_vault,
+            asks_book: market.asks,
+            oracle: market.oracle,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::PlaceSellOrder { price, quantity },
+    )
+}
+
+/// `authority` is the open orders owner or its delegate
+pub fn cancel_buy_order(
+    authority: &Pubkey,
+    maker: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    order_id: u64,
+) -> Instruction {
+    build(
+        ix_accounts::CancelBuyOrder {
+            authority: *authority,
+            maker: *maker,
+            market: market.address,
+            user_open_orders: market.user_market_orders(maker, account_index),
+            bids_book: market.bids,
+        },
+        ix_data::CancelBuyOrder {
+            seed: market.seed,
+            order_id,
+        },
+    )
+}
+
+/// `authority` is the open orders owner or its delegate
+pub fn cancel_sell_order(
+    authority: &Pubkey,
+    maker: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    order_id: u64,
+) -> Instruction {
+    build(
+        ix_accounts::CancelSellOrder {
+            authority: *authority,
+            maker: *maker,
+            market: market.address,
+            user_open_orders: market.user_market_orders(maker, account_index),
+            asks_book: market.asks,
+        },
+        ix_data::CancelSellOrder {
+            seed: market.seed,
+            order_id,
+        },
+    )
+}
+
+/// Market must have an oracle and `limit_price` must be non-zero
+#[allow(clippy::too_many_arguments)]
+pub fn place_pegged_order(
+    authority: &Pubkey,
+    maker: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    side: Side,
+    price_offset: i64,
+    limit_price: u64,
+    quantity: u64,
+) -> Instruction {
+    build(
+        ix_accounts::PlacePeggedOrder {
+            authority: *authority,
+            maker: *maker,
+            market: market.address,
+            user_open_orders: market.user_market_orders(maker, account_index),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            user_base_token_account: market.user_base_token_account(maker),
+            user_quote_token_account: market.user_quote_token_account(maker),
+            base_vault_account: market.base_vault,
+            quote_vault_account: market.quote_vault,
+            pegged_book: pegged_book_address(&market.address),
+            oracle: market.oracle.unwrap_or_default(),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::PlacePeggedOrder {
+            side,
+            price_offset,
+            limit_price,
+            quantity,
+        },
+    )
+}
+
+/// `authority` is the open orders owner or its delegate
+pub fn cancel_pegged_order(
+    authority: &Pubkey,
+    maker: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    order_id: u64,
+) -> Instruction {
+    build(
+        ix_accounts::CancelPeggedOrder {
+            authority: *authority,
+            maker: *maker,
+            market: market.address,
+            user_open_orders: market.user_market_orders(maker, account_index),
+            pegged_book: pegged_book_address(&market.address),
+        },
+        ix_data::CancelPeggedOrder {
+            seed: market.seed,
+            order_id,
+        },
+    )
+}
+
+/// `authority` is the open orders owner or its delegate, it pays the keeper bounty
+pub fn place_trigger_order(
+    authority: &Pubkey,
+    maker: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    params: TriggerOrderParams,
+) -> Instruction {
+    build(
+        ix_accounts::PlaceTriggerOrder {
+            authority: *authority,
+            maker: *maker,
+            market: market.address,
+            user_open_orders: market.user_market_orders(maker, account_index),
+            trigger_book: trigger_book_address(&market.address),
+            system_program: system_program::ID,
+        },
+        ix_data::PlaceTriggerOrder { params },
+    )
+}
+
+/// `authority` is the open orders owner or its delegate, it gets the keeper bounty back
+pub fn cancel_trigger_order(
+    authority: &Pubkey,
+    maker: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    order_id: u64,
+) -> Instruction {
+    build(
+        ix_accounts::CancelTriggerOrder {
+            authority: *authority,
+            maker: *maker,
+            market: market.address,
+            user_open_orders: market.user_market_orders(maker, account_index),
+            trigger_book: trigger_book_address(&market.address),
+        },
+        ix_data::CancelTriggerOrder {
+            seed: market.seed,
+            order_id,
+        },
+    )
+}
+
+/// Maker and taker open orders sub accounts taking part in a fill
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TakeAccounts {
+    pub taker: Pubkey,
+    pub taker_account_index: u8,
+    pub maker: Pubkey,
+    pub maker_account_index: u8,
+}
+
+fn take_buy_accounts(accounts: &TakeAccounts, market: &MarketKeys) -> ix_accounts::TakeBuyOrder {
+    ix_accounts::TakeBuyOrder {
+        taker: accounts.taker,
+        maker: accounts.maker,
+        market: market.address,
+        maker_open_orders: market.user_market_orders(&accounts.maker, accounts.maker_account_index),
+        taker_open_orders: market.user_market_orders(&accounts.taker, accounts.taker_account_index),
+        base_mint: market.base_mint,
+        quote_mint: market.quote_mint,
+        maker_base_token_account: market.user_base_token_account(&accounts.maker),
+        taker_base_token_account: market.user_base_token_account(&accounts.taker),
+        taker_quote_token_account: market.user_quote_token_account(&accounts.taker),
+        quote_vault_account: market.quote_vault,
+        bids_book: market.bids,
+        trade_history: market.trade_history,
+        market_stats: market.stats,
+        associated_token_program: associated_token::ID,
+        token_program: token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    }
+}
+
+fn take_sell_accounts(accounts: &TakeAccounts, market: &MarketKeys) -> ix_accounts::TakeSellOrder {
+    ix_accounts::TakeSellOrder {
+        taker: accounts.taker,
+        maker: accounts.maker,
+        market: market.address,
+        maker_open_orders: market.user_market_orders(&accounts.maker, accounts.maker_account_index),
+        taker_open_orders: market.user_market_orders(&accounts.taker, accounts.taker_account_index),
+        base_mint: market.base_mint,
+        quote_mint: market.quote_mint,
+        maker_quote_token_account: market.user_quote_token_account(&accounts.maker),
+        taker_base_token_account: market.user_base_token_account(&accounts.taker),
+        taker_quote_token_account: market.user_quote_token_account(&accounts.taker),
+        base_vault_account: market.base_vault,
+        asks_book: market.asks,
+        trade_history: market.trade_history,
+        market_stats: market.stats,
+        associated_token_program: associated_token::ID,
+        token_program: token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    }
+}
+
+pub fn take_buy_order(accounts: &TakeAccounts, market: &MarketKeys, order_id: u64) -> Instruction {
+    build(
+        take_buy_accounts(accounts, market),
+        ix_data::TakeBuyOrder {
+            seed: market.seed,
+            order_id,
+        },
+    )
+}
+
+pub fn take_sell_order(accounts: &TakeAccounts, market: &MarketKeys, order_id: u64) -> Instruction {
+    build(
+        take_sell_accounts(accounts, market),
+        ix_data::TakeSellOrder {
+            seed: market.seed,
+            order_id,
+        },
+    )
+}
+
+pub fn partial_take_buy_order(
+    accounts: &TakeAccounts,
+    market: &MarketKeys,
+    order_id: u64,
+    amount: u64,
+) -> Instruction {
+    let take = take_buy_accounts(accounts, market);
+
+    build(
+        ix_accounts::PartialTakeBuyOrder {
+            taker: take.taker,
+            maker: take.maker,
+            market: take.market,
+            maker_open_orders: take.maker_open_orders,
+            taker_open_orders: take.taker_open_orders,
+            base_mint: take.base_mint,
+            quote_mint: take.quote_mint,
+            maker_base_token_account: take.maker_base_token_account,
+            taker_base_token_account: take.taker_base_token_account,
+            taker_quote_token_account: take.taker_quote_token_account,
+            quote_vault_account: take.quote_vault_account,
+            bids_book: take.bids_book,
+            trade_history: take.trade_history,
+            market_stats: take.market_stats,
+            associated_token_program: take.associated_token_program,
+            token_program: take.token_program,
+            system_program: take.system_program,
+            rent: take.rent,
+        },
+        ix_data::PartialTakeBuyOrder {
+            seed: market.seed,
+            order_id,
+            amount,
+        },
+    )
+}
+
+pub fn partial_take_sell_order(
+    accounts: &TakeAccounts,
+    market: &MarketKeys,
+    order_id: u64,
+    amount: u64,
+) -> Instruction {
+    let take = take_sell_accounts(accounts, market);
+
+    build(
+        ix_accounts::PartialTakeSellOrder {
+            taker: take.taker,
+            maker: take.maker,
+            market: take.market,
+            maker_open_orders: take.maker_open_orders,
+            taker_open_orders: take.taker_open_orders,
+            base_mint: take.base_mint,
+            quote_mint: take.quote_mint,
+            maker_quote_token_account: take.maker_quote_token_account,
+            taker_base_token_account: take.taker_base_token_account,
+            taker_quote_token_account: take.taker_quote_token_account,
+            base_vault_account: take.base_vault_account,
+            asks_book: take.asks_book,
+            trade_history: take.trade_history,
+            market_stats: take.market_stats,
+            associated_token_program: take.associated_token_program,
+            token_program: take.token_program,
+            system_program: take.system_program,
+            rent: take.rent,
+        },
+        ix_data::PartialTakeSellOrder {
+            seed: market.seed,
+            order_id,
+            amount,
+        },
+    )
+}
+
+/// Non-zero `limit_price` is the lowest price selling into a pegged bid or the
+/// highest price buying from a pegged ask
+pub fn take_pegged_order(
+    accounts: &TakeAccounts,
+    market: &MarketKeys,
+    order_id: u64,
+    amount: u64,
+    limit_price: u64,
+) -> Instruction {
+    build(
+        ix_accounts::TakePeggedOrder {
+            taker: accounts.taker,
+            maker: accounts.maker,
+            market: market.address,
+            maker_open_orders: market
+                .user_market_orders(&accounts.maker, accounts.maker_account_index),
+            taker_open_orders: market
+                .user_market_orders(&accounts.taker, accounts.taker_account_index),
+            base_mint: market.base_mint,
+            quote_mint: market.quote_mint,
+            maker_base_token_account: market.user_base_token_account(&accounts.maker),
+            maker_quote_token_account: market.user_quote_token_account(&accounts.maker),
+            taker_base_token_account: market.user_base_token_account(&accounts.taker),
+            taker_quote_token_account: market.user_quote_token_account(&accounts.taker),
+            base_vault_account: market.base_vault,
+            quote_vault_account: market.quote_vault,
+            pegged_book: pegged_book_address(&market.address),
+            trade_history: market.trade_history,
+            market_stats: market.stats,
+            oracle: market.oracle.unwrap_or_default(),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        ix_data::TakePeggedOrder {
+            seed: market.seed,
+            order_id,
+            amount,
+            limit_price,
+        },
+    )
+}
+
+/// Open orders and token account of a resting maker a market trigger fills
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TriggerMaker {
+    pub open_orders: Pubkey,
+    /// Maker ATA receiving the escrowed token, base for bid makers and quote for ask makers
+    pub token_account: Pubkey,
+}
+
+/// Makers of the `book` orders a market trigger would fill in priority order, with their
+/// ATAs as token accounts, `book` is the side opposite to the trigger and orders of the
+/// trigger owner are skipped
+pub fn trigger_makers(market: &MarketKeys, book: &Book, order: &TriggerOrder) -> Vec<TriggerMaker> {
+    let mut makers = vec![];
+    let mut remaining = order.quantity;
+
+    for resting in book.orders.iter().take(book.orders_count as usize) {
+        let within_limit = match order.side {
+            Side::Bid => resting.price <= order.limit_price,
+            Side::Ask => resting.price >= order.limit_price,
+        };
+        if remaining == 0 || !within_limit {
+            break;
+        }
+        if resting.owner == order.owner {
+            continue;
+        }
+
+        makers.push(TriggerMaker {
+            open_orders: market.user_market_orders(&resting.owner, resting.account_index),
+            token_account: match order.side {
+                Side::Bid => market.user_quote_token_account(&resting.owner),
+                Side::Ask => market.user_base_token_account(&resting.owner),
+            },
+        });
+        remaining = remaining.saturating_sub(resting.quantity);
+    }
+
+    makers
+}
+
+/// Any `keeper` can execute a crossed trigger of `owner`, market triggers need the
+/// `makers` of the orders they fill, see `trigger_makers`
+pub fn execute_trigger(
+    keeper: &Pubkey,
+    owner: &Pubkey,
+    account_index: u8,
+    market: &MarketKeys,
+    order_id: u64,
+    makers: &[TriggerMaker],
+) -> Instruction {
+    let mut ix = build(
+        ix_accounts::ExecuteTrigger {
+            keeper: *keeper,
+            owner: *owner,
+            market: market.address,
+            owner_open_orders: market.user_market_orders(owner, account_index),
+            bids_book: market.bids,
+            asks_book: market.asks,
+            trigger_book: trigger_book_address(&market.address),
+            trade_history: market.trade_history,
+            market_stats: market.stats,
+            base_vault_account: market.base_vault,
+            quote_vault_account: market.quote_vault,
+            oracle: market.oracle,
+            token_program: token::ID,
+        },
+        ix_data::ExecuteTrigger {
+            seed: market.seed,
+            order_id,
+        },
+    );
+    ix.accounts.extend(makers.iter().flat_map(|maker| {
+        [
+            AccountMeta::new(maker.open_orders, false),
+            AccountMeta::new(maker.token_account, false),
+        ]
+    }));
+    ix
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::Discriminator;
+
+    use super::*;
+
+    #[test]
+    fn take_buy_order_accounts() {
+        let market = MarketKeys::new(0, Pubkey::new_unique(), Pubkey::new_unique());
+        let accounts = TakeAccounts {
+            taker: Pubkey::new_unique(),
+            taker_account_index: 0,
+            maker: Pubkey::new_unique(),
+            maker_account_index: 2,
+        };
+
+        let ix = take_buy_order(&accounts, &market, 7);
+
+        assert_eq!(ix.program_id, ID);
+        assert_eq!(&ix.data[..8], &ix_data::TakeBuyOrder::DISCRIMINATOR);
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            user_market_orders_address(&market.address, &accounts.maker, 2)
+        );
+        assert_eq!(ix.accounts[10].pubkey, market.quote_vault);
+        assert_eq!(ix.accounts[11].pubkey, market.bids);
+    }
+}
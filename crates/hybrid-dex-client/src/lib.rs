@@ -0,0 +1,25 @@
+pub mod accounts;
+pub mod amount;
+pub mod audit;
+pub mod error;
+pub mod instructions;
+pub mod orderbook;
+pub mod pda;
+pub mod rpc;
+pub mod twap;
+
+pub use accounts::*;
+pub use amount::*;
+pub use audit::*;
+pub use error::*;
+pub use orderbook::*;
+pub use pda::*;
+pub use rpc::*;
+pub use twap::*;
+
+pub use hybrid_dex::{
+    self, BestPrices, Book, Candle, GlobalPool, Market, MarketAudit, MarketOrderQuote, MarketStats,
+    OpenedOrder, OracleKind, PeggedBook, PeggedOrder, PriceLevel, PriceObservation, Side, Trade,
+    TradeHistory, TriggerBook, TriggerCondition, TriggerOrder, TriggerOrderParams,
+    TriggerOrderType, TriggerSource, UserMarketOrders, UserPosition, ID,
+};
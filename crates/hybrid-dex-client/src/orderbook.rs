@@ -0,0 +1,329 @@
+//! Off-chain mirror of market `Book` accounts with L2 depth aggregation
+
+use hybrid_dex::{Book, OpenedOrder, Side};
+
+/// Individual resting orders of both sides, best price first
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrderBook {
+    pub bids: Vec<OpenedOrder>,
+    pub asks: Vec<OpenedOrder>,
+}
+
+/// Aggregated price level, `cumulative_quantity` sums this and all better levels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Level {
+    pub price: u64,
+    pub quantity: u64,
+    pub cumulative_quantity: u64,
+    pub orders_count: u64,
+}
+
+/// Price level view of both sides, best price first
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct L2Depth {
+    pub bids: Vec<Level>,
+    pub asks: Vec<Level>,
+}
+
+/// New total quantity and order count of a price level, zero quantity means the level was removed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LevelChange {
+    pub side: Side,
+    pub price: u64,
+    pub quantity: u64,
+    pub orders_count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DepthMessage {
+    Snapshot(L2Depth),
+    Update(Vec<LevelChange>),
+}
+
+impl OrderBook {
+    pub fn new(bids: &Book, asks: &Book) -> OrderBook {
+        let mut book = OrderBook {
+            bids: bids
+                .orders
+                .iter()
+                .take(bids.orders_count as usize)
+                .copied()
+                .collect(),
+            asks: asks
+                .orders
+                .iter()
+                .take(asks.orders_count as usize)
+                .copied()
+                .collect(),
+        };
+
+        // price then time priority, independent of on-chain storage order
+        book.bids.sort_by(|a, b| {
+            b.price
+                .cmp(&a.price)
+                .then(a.created_at.cmp(&b.created_at))
+                .then(a.order_id.cmp(&b.order_id))
+        });
+        book.asks.sort_by(|a, b| {
+            a.price
+                .cmp(&b.price)
+                .then(a.created_at.cmp(&b.created_at))
+                .then(a.order_id.cmp(&b.order_id))
+        });
+
+        book
+    }
+
+    pub fn best_bid(&self) -> Option<u64> {
+        self.bids.first().map(|order| order.price)
+    }
+
+    pub fn best_ask(&self) -> Option<u64> {
+        self.asks.first().map(|order| order.price)
+    }
+
+    /// Best ask minus best bid, `None` when a side is empty or the book is crossed
+    pub fn spread(&self) -> Option<u64> {
+        self.best_ask()?.checked_sub(self.best_bid()?)
+    }
+
+    /// Mid price rounded down
+    pub fn mid_price(&self) -> Option<u64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        Some(((bid as u128 + ask as u128) / 2) as u64)
+    }
+
+    /// Aggregate orders by price, keeping at most `max_levels` per side
+    pub fn depth(&self, max_levels: Option<usize>) -> L2Depth {
+        L2Depth {
+            bids: aggregate(&self.bids, max_levels),
+            asks: aggregate(&self.asks, max_levels),
+        }
+    }
+}
+
+fn aggregate(orders: &[OpenedOrder], max_levels: Option<usize>) -> Vec<Level> {
+    let mut levels: Vec<Level> = vec![];
+
+    for order in orders {
+        match levels.last_mut() {
+            Some(level) if level.price == order.price => {
+                level.quantity = level.quantity.saturating_add(order.quantity);
+                level.orders_count += 1;
+            }
+            _ => {
+                if max_levels.is_some_and(|max| levels.len() >= max) {
+                    break;
+                }
+                levels.push(Level {
+                    price: order.price,
+                    quantity: order.quantity,
+                    cumulative_quantity: 0,
+                    orders_count: 1,
+                });
+            }
+        }
+    }
+
+    let mut cumulative_quantity = 0u64;
+    for level in levels.iter_mut() {
+        cumulative_quantity = cumulative_quantity.saturating_add(level.quantity);
+        level.cumulative_quantity = cumulative_quantity;
+    }
+
+    levels
+}
+
+fn diff_side(side: Side, old: &[Level], new: &[Level], changes: &mut Vec<LevelChange>) {
+    for level in new {
+        let previous = old.iter().find(|old_level| old_level.price == level.price);
+        if previous.map(|previous| (previous.quantity, previous.orders_count))
+            != Some((level.quantity, level.orders_count))
+        {
+            changes.push(LevelChange {
+                side,
+                price: level.price,
+                quantity: level.quantity,
+                orders_count: level.orders_count,
+            });
+        }
+    }
+
+    for level in old {
+        if !new.iter().any(|new_level| new_level.price == level.price) {
+            changes.push(LevelChange {
+                side,
+                price: level.price,
+                quantity: 0,
+                orders_count: 0,
+            });
+        }
+    }
+}
+
+impl L2Depth {
+    pub fn snapshot(&self) -> DepthMessage {
+        DepthMessage::Snapshot(self.clone())
+    }
+
+    /// Level changes turning `self` into `newer`, `None` when nothing changed
+    pub fn diff(&self, newer: &L2Depth) -> Option<DepthMessage> {
+        let mut changes = vec![];
+        diff_side(Side::Bid, &self.bids, &newer.bids, &mut changes);
+        diff_side(Side::Ask, &self.asks, &newer.asks, &mut changes);
+
+        (!changes.is_empty()).then_some(DepthMessage::Update(changes))
+    }
+
+    /// Apply a message received from `snapshot` or `diff`
+    pub fn apply(&mut self, message: &DepthMessage) {
+        match message {
+            DepthMessage::Snapshot(depth) => *self = depth.clone(),
+            DepthMessage::Update(changes) => {
+                for change in changes {
+                    let levels = match change.side {
+                        Side::Bid => &mut self.bids,
+                        Side::Ask => &mut self.asks,
+                    };
+                    levels.retain(|level| level.price != change.price);
+                    if change.quantity > 0 {
+                        levels.push(Level {
+                            price: change.price,
+                            quantity: change.quantity,
+                            cumulative_quantity: 0,
+                            orders_count: change.orders_count,
+                        });
+                    }
+                }
+
+                self.normalize();
+            }
+        }
+    }
+
+    /// Re-sort levels best first and recompute cumulative quantities
+    fn normalize(&mut self) {
+        self.bids
+            .sort_by_key(|level| std::cmp::Reverse(level.price));
+        self.asks.sort_by_key(|level| level.price);
+
+        for levels in [&mut self.bids, &mut self.asks] {
+            let mut cumulative_quantity = 0u64;
+            for level in levels.iter_mut() {
+                cumulative_quantity = cumulative_quantity.saturating_add(level.quantity);
+                level.cumulative_quantity = cumulative_quantity;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::Pubkey;
+
+    use super::*;
+
+    fn book(side: Side, orders: &[(u64, u64, u64)]) -> Book {
+        Book {
+            side,
+            market: Pubkey::default(),
+            orders_count: orders.len() as u64,
+            orders: orders
+                .iter()
+                .map(|&(order_id, price, quantity)| OpenedOrder {
+                    order_id,
+                    owner: Pubkey::default(),
+                    price,
+                    quantity,
+                    created_at: order_id as i64,
+                    account_index: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn depth_and_diff() {
+        let bids = book(Side::Bid, &[(0, 90, 5), (1, 100, 10), (2, 100, 20)]);
+        let asks = book(Side::Ask, &[(3, 120, 7), (4, 110, 3)]);
+        let order_book = OrderBook::new(&bids, &asks);
+
+        assert_eq!(order_book.best_bid(), Some(100));
+        assert_eq!(order_book.best_ask(), Some(110));
+        assert_eq!(order_book.spread(), Some(10));
+        assert_eq!(order_book.mid_price(), Some(105));
+        assert_eq!(order_book.bids[0].order_id, 1);
+
+        let depth = order_book.depth(None);
+        assert_eq!(
+            depth.bids,
+            vec![
+                Level {
+                    price: 100,
+                    quantity: 30,
+                    cumulative_quantity: 30,
+                    orders_count: 2
+                },
+                Level {
+                    price: 90,
+                    quantity: 5,
+                    cumulative_quantity: 35,
+                    orders_count: 1
+                },
+            ]
+        );
+        assert_eq!(order_book.depth(Some(1)).asks.len(), 1);
+
+        // order 2 filled, new ask level
+        let bids = book(Side::Bid, &[(0, 90, 5), (1, 100, 10)]);
+        let asks = book(Side::Ask, &[(3, 120, 7), (5, 115, 1)]);
+        let newer = OrderBook::new(&bids, &asks).depth(None);
+
+        let update = depth.diff(&newer).unwrap();
+        assert_eq!(
+            update,
+            DepthMessage::Update(vec![
+                LevelChange {
+                    side: Side::Bid,
+                    price: 100,
+                    quantity: 10,
+                    orders_count: 1
+                },
+                LevelChange {
+                    side: Side::Ask,
+                    price: 115,
+                    quantity: 1,
+                    orders_count: 1
+                },
+                LevelChange {
+                    side: Side::Ask,
+                    price: 110,
+                    quantity: 0,
+                    orders_count: 0
+                },
+            ])
+        );
+        assert_eq!(newer.diff(&newer), None);
+
+        let mut mirror = L2Depth::default();
+        mirror.apply(&depth.snapshot());
+        mirror.apply(&update);
+        assert_eq!(mirror, newer);
+
+        // a level keeping its quantity across more orders still updates
+        let bids = book(Side::Bid, &[(0, 90, 5), (1, 100, 4), (6, 100, 6)]);
+        let newest = OrderBook::new(&bids, &asks).depth(None);
+        let update = newer.diff(&newest).unwrap();
+        assert_eq!(
+            update,
+            DepthMessage::Update(vec![LevelChange {
+                side: Side::Bid,
+                price: 100,
+                quantity: 10,
+                orders_count: 2
+            }])
+        );
+        mirror.apply(&update);
+        assert_eq!(mirror, newest);
+    }
+}
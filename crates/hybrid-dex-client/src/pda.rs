@@ -0,0 +1,128 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use hybrid_dex::{
+    Market, OracleKind, UserMarketOrders, ASK_BOOK_SEED, BID_BOOK_SEED, GLOBAL_AUTHORITY_SEED, ID,
+    MARKET_SEED, MARKET_STATS_SEED, PEGGED_BOOK_SEED, TRADE_HISTORY_SEED, TRIGGER_BOOK_SEED,
+    USER_MARKET_ORDER_SEED,
+};
+
+pub fn global_pool_address() -> Pubkey {
+    Pubkey::find_program_address(&[GLOBAL_AUTHORITY_SEED.as_bytes()], &ID).0
+}
+
+pub fn market_address(seed: u64) -> Pubkey {
+    Pubkey::find_program_address(&[MARKET_SEED.as_bytes(), &seed.to_le_bytes()], &ID).0
+}
+
+pub fn bids_book_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[BID_BOOK_SEED.as_bytes(), market.as_ref()], &ID).0
+}
+
+pub fn asks_book_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ASK_BOOK_SEED.as_bytes(), market.as_ref()], &ID).0
+}
+
+pub fn trade_history_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TRADE_HISTORY_SEED.as_bytes(), market.as_ref()], &ID).0
+}
+
+pub fn market_stats_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[MARKET_STATS_SEED.as_bytes(), market.as_ref()], &ID).0
+}
+
+pub fn pegged_book_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PEGGED_BOOK_SEED.as_bytes(), market.as_ref()], &ID).0
+}
+
+pub fn trigger_book_address(market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TRIGGER_BOOK_SEED.as_bytes(), market.as_ref()], &ID).0
+}
+
+/// Open orders PDA of user sub account, index 0 is the legacy account
+pub fn user_market_orders_address(market: &Pubkey, user: &Pubkey, account_index: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.as_ref(),
+            user.as_ref(),
+            UserMarketOrders::index_seed(&account_index),
+        ],
+        &ID,
+    )
+    .0
+}
+
+/// Market token vault is the associated token account of market PDA
+pub fn vault_address(market: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(market, mint)
+}
+
+/// All addresses derived from a market seed and its mints
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarketKeys {
+    pub seed: u64,
+    pub address: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    /// `None` for markets created before trades were recorded, fills skip it
+    pub trade_history: Option<Pubkey>,
+    /// `None` for markets created before candles were recorded, fills skip it
+    pub stats: Option<Pubkey>,
+    /// `None` for markets created before pegged orders, close and audit skip it
+    pub pegged_book: Option<Pubkey>,
+    /// `None` for markets created before trigger orders, close and audit skip it
+    pub trigger_book: Option<Pubkey>,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    /// Price band oracle, order placement needs it once the market has one
+    pub oracle: Option<Pubkey>,
+}
+
+impl MarketKeys {
+    pub fn new(seed: u64, base_mint: Pubkey, quote_mint: Pubkey) -> MarketKeys {
+        let address = market_address(seed);
+
+        MarketKeys {
+            seed,
+            address,
+            base_mint,
+            quote_mint,
+            bids: bids_book_address(&address),
+            asks: asks_book_address(&address),
+            trade_history: Some(trade_history_address(&address)),
+            stats: Some(market_stats_address(&address)),
+            pegged_book: Some(pegged_book_address(&address)),
+            trigger_book: Some(trigger_book_address(&address)),
+            base_vault: vault_address(&address, &base_mint),
+            quote_vault: vault_address(&address, &quote_mint),
+            oracle: None,
+        }
+    }
+
+    pub fn from_market(market: &Market) -> MarketKeys {
+        let keys = MarketKeys::new(market.seed, market.base_mint, market.quote_mint);
+
+        MarketKeys {
+            trade_history: keys.trade_history.filter(|_| market.has_trade_history),
+            stats: keys.stats.filter(|_| market.has_market_stats),
+            pegged_book: keys.pegged_book.filter(|_| market.has_pegged_book),
+            trigger_book: keys.trigger_book.filter(|_| market.has_trigger_book),
+            oracle: (market.oracle_kind != OracleKind::None).then_some(market.oracle),
+            ..keys
+        }
+    }
+
+    pub fn user_market_orders(&self, user: &Pubkey, account_index: u8) -> Pubkey {
+        user_market_orders_address(&self.address, user, account_index)
+    }
+
+    pub fn user_base_token_account(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.base_mint)
+    }
+
+    pub fn user_quote_token_account(&self, user: &Pubkey) -> Pubkey {
+        get_associated_token_address(user, &self.quote_mint)
+    }
+}
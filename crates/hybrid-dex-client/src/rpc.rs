@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::error::Result;
+
+/// Account source used by the client, implemented for rpc nodes and local banks
+pub trait HybridDexRpc {
+    /// Raw account data, `None` when the account does not exist
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>>;
+
+    fn get_multiple_account_data(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        addresses
+            .iter()
+            .map(|address| self.get_account_data(address))
+            .collect()
+    }
+}
+
+/// In-memory account store, e.g. filled from a local bank or snapshots
+#[derive(Clone, Debug, Default)]
+pub struct AccountStore {
+    pub accounts: BTreeMap<Pubkey, Vec<u8>>,
+}
+
+impl AccountStore {
+    pub fn insert(&mut self, address: Pubkey, data: Vec<u8>) {
+        self.accounts.insert(address, data);
+    }
+}
+
+impl HybridDexRpc for AccountStore {
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self.accounts.get(address).cloned())
+    }
+}
+
+#[cfg(feature = "rpc-client")]
+impl HybridDexRpc for solana_client::rpc_client::RpcClient {
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .get_account_with_commitment(address, self.commitment())
+            .map_err(|err| crate::ClientError::Rpc(err.to_string()))?;
+
+        Ok(response.value.map(|account| account.data))
+    }
+
+    fn get_multiple_account_data(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let accounts = self
+            .get_multiple_accounts(addresses)
+            .map_err(|err| crate::ClientError::Rpc(err.to_string()))?;
+
+        Ok(accounts
+            .into_iter()
+            .map(|account| account.map(|account| account.data))
+            .collect())
+    }
+}
@@ -0,0 +1,72 @@
+//! Time weighted average price of a market from two price accumulator observations
+//!
+//! Take an observation, keep it, and compute the TWAP against a later one.
+//! Observations count the last trade price up to the observation time, so the
+//! window doesn't need a trade at either end.
+
+use anchor_lang::prelude::Pubkey;
+use hybrid_dex::PriceObservation;
+
+use crate::{
+    accounts::fetch_market_stats,
+    error::{ClientError, Result},
+    rpc::HybridDexRpc,
+};
+
+/// Price accumulator of `market` at unix timestamp `now`
+pub fn observe_price(
+    rpc: &impl HybridDexRpc,
+    market: &Pubkey,
+    now: i64,
+) -> Result<PriceObservation> {
+    Ok(fetch_market_stats(rpc, market)?.observe(now))
+}
+
+/// Time weighted average price between an earlier `start` and a later `end` observation
+pub fn twap(start: &PriceObservation, end: &PriceObservation) -> Result<u64> {
+    hybrid_dex::twap(start, end).map_err(ClientError::Twap)
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AccountSerialize;
+    use hybrid_dex::{MarketStats, DEFAULT_CANDLE_INTERVALS};
+
+    use super::*;
+    use crate::{pda::market_stats_address, rpc::AccountStore};
+
+    #[test]
+    fn twap_between_stored_observations() {
+        let market = Pubkey::new_unique();
+        let mut stats = MarketStats {
+            market,
+            last_price: 0,
+            last_trade_at: 0,
+            volume_buckets: vec![],
+            candle_series: vec![],
+            price_cumulative: 0,
+        };
+        stats
+            .set_candle_intervals(&DEFAULT_CANDLE_INTERVALS)
+            .unwrap();
+        stats.record_fill(30, 1, 30, 1_000).unwrap();
+
+        let mut store = AccountStore::default();
+        let mut data = vec![];
+        stats.try_serialize(&mut data).unwrap();
+        store.insert(market_stats_address(&market), data);
+
+        let start = observe_price(&store, &market, 1_000).unwrap();
+
+        stats.record_fill(60, 1, 60, 1_020).unwrap();
+        let mut data = vec![];
+        stats.try_serialize(&mut data).unwrap();
+        store.insert(market_stats_address(&market), data);
+
+        // 30 for 20s, then 60 for 40s
+        let end = observe_price(&store, &market, 1_060).unwrap();
+        assert_eq!(twap(&start, &end).unwrap(), 50);
+
+        assert!(matches!(twap(&end, &start), Err(ClientError::Twap(_))));
+    }
+}
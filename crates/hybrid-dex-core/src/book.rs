@@ -0,0 +1,348 @@
+//! Order book operations on the orders vector of a `Book` account
+
+use crate::{fill_amounts, CoreError, Side};
+
+/// Resting order fields used by book operations
+pub trait BookOrder: Copy {
+    fn order_id(&self) -> u64;
+    fn price(&self) -> u64;
+    fn quantity(&self) -> u64;
+    fn set_quantity(&mut self, quantity: u64);
+}
+
+/// Insert order in price-time priority, best price first and older orders
+/// ahead of newer ones at the same price
+pub fn insert_order<O: BookOrder>(
+    side: Side,
+    orders: &mut Vec<O>,
+    orders_count: u64,
+    new_order: O,
+) {
+    let count = (orders_count as usize).min(orders.len());
+    let idx = orders
+        .iter()
+        .take(count)
+        .position(|order| match side {
+            Side::Bid => order.price() < new_order.price(),
+            Side::Ask => order.price() > new_order.price(),
+        })
+        .unwrap_or(count);
+
+    orders.insert(idx, new_order);
+}
+
+/// Index of order among the first `orders_count` orders
+pub fn find_order<O: BookOrder>(
+    orders: &[O],
+    orders_count: u64,
+    order_id: u64,
+) -> Result<usize, CoreError> {
+    orders
+        .iter()
+        .take(orders_count as usize)
+        .position(|order| order.order_id() == order_id)
+        .ok_or(CoreError::OrderNotFound)
+}
+
+pub fn remove_order<O: BookOrder>(
+    orders: &mut Vec<O>,
+    orders_count: u64,
+    order_id: u64,
+) -> Result<O, CoreError> {
+    let idx = find_order(orders, orders_count, order_id)?;
+    Ok(orders.remove(idx))
+}
+
+/// Decrease order quantity by `amount` in place, which must stay below order quantity
+pub fn decrease_order<O: BookOrder>(
+    orders: &mut [O],
+    orders_count: u64,
+    order_id: u64,
+    amount: u64,
+) -> Result<O, CoreError> {
+    let idx = find_order(orders, orders_count, order_id)?;
+    if orders[idx].quantity() <= amount {
+        return Err(CoreError::PartialOrderAmountExceed);
+    }
+
+    let order = &mut orders[idx];
+    order.set_quantity(order.quantity() - amount);
+    Ok(*order)
+}
+
+/// Price, total quantity and order count of a price level
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BookLevel {
+    pub price: u64,
+    pub quantity: u64,
+    pub orders_count: u64,
+}
+
+/// Best price level among the first `orders_count` orders, `None` for an empty book
+pub fn best_level<O: BookOrder>(
+    orders: &[O],
+    orders_count: u64,
+) -> Result<Option<BookLevel>, CoreError> {
+    let mut orders = orders.iter().take(orders_count as usize);
+    let Some(best) = orders.next() else {
+        return Ok(None);
+    };
+
+    let mut level = BookLevel {
+        price: best.price(),
+        quantity: best.quantity(),
+        orders_count: 1,
+    };
+    for order in orders.take_while(|order| order.price() == level.price) {
+        level.quantity = level
+            .quantity
+            .checked_add(order.quantity())
+            .ok_or(CoreError::MathOverflow)?;
+        level.orders_count += 1;
+    }
+
+    Ok(Some(level))
+}
+
+/// Amounts of taking up to `amount` base from the best orders of a `maker_side` book
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MarketQuote {
+    pub base_amount: u64,
+    /// Quote the taker receives from bids or pays to asks
+    pub quote_amount: u64,
+    pub orders_filled: u64,
+    /// Price of the last order filled, zero when nothing fills
+    pub worst_price: u64,
+}
+
+/// Walk a `maker_side` book in priority order taking up to `amount` base, each order
+/// rounding as its own take would. Quoting stops at a fill worth less than one
+/// atomic quote unit, the base amount shows how much of `amount` the book covers.
+pub fn quote_market_order<O: BookOrder>(
+    maker_side: Side,
+    orders: &[O],
+    orders_count: u64,
+    amount: u64,
+    base_decimal: u8,
+) -> Result<MarketQuote, CoreError> {
+    let mut quote = MarketQuote::default();
+
+    for order in orders.iter().take(orders_count as usize) {
+        let remaining = amount - quote.base_amount;
+        if remaining == 0 {
+            break;
+        }
+
+        let fill = remaining.min(order.quantity());
+        let (base_amount, quote_amount) =
+            match fill_amounts(maker_side, fill, order.price(), base_decimal) {
+                Ok(amounts) => amounts,
+                Err(CoreError::ZeroFill) => break,
+                Err(err) => return Err(err),
+            };
+
+        quote.base_amount += base_amount;
+        quote.quote_amount = quote
+            .quote_amount
+            .checked_add(quote_amount)
+            .ok_or(CoreError::MathOverflow)?;
+        quote.orders_filled += 1;
+        quote.worst_price = order.price();
+    }
+
+    Ok(quote)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::simulator::SimOrder;
+
+    #[derive(Clone, Copy, Debug)]
+    enum Op {
+        Insert { price: u64, quantity: u64 },
+        Remove { pick: u64 },
+        Decrease { pick: u64, amount: u64 },
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            (1..20u64, 1..1_000u64).prop_map(|(price, quantity)| Op::Insert { price, quantity }),
+            any::<u64>().prop_map(|pick| Op::Remove { pick }),
+            (any::<u64>(), 0..1_200u64).prop_map(|(pick, amount)| Op::Decrease { pick, amount }),
+        ]
+    }
+
+    fn side() -> impl Strategy<Value = Side> {
+        prop_oneof![Just(Side::Bid), Just(Side::Ask)]
+    }
+
+    /// Live orders in price-time priority, ids grow with placement time
+    fn expected_orders(side: Side, model: &[SimOrder]) -> Vec<SimOrder> {
+        let mut orders = model.to_vec();
+        match side {
+            Side::Bid => {
+                orders.sort_by_key(|order| (std::cmp::Reverse(order.price), order.order_id))
+            }
+            Side::Ask => orders.sort_by_key(|order| (order.price, order.order_id)),
+        }
+        orders
+    }
+
+    proptest! {
+        #[test]
+        fn book_matches_reference_model(side in side(), ops in prop::collection::vec(op(), 1..64)) {
+            let mut orders: Vec<SimOrder> = vec![];
+            let mut orders_count = 0u64;
+
+            let mut model: Vec<SimOrder> = vec![];
+            let mut next_id = 0u64;
+            let mut inserted = 0u64;
+            let mut removed = 0u64;
+
+            for op in ops {
+                match op {
+                    Op::Insert { price, quantity } => {
+                        let order = SimOrder {
+                            order_id: next_id,
+                            owner: [0; 32],
+                            account_index: 0,
+                            price,
+                            quantity,
+                            created_at: next_id as i64,
+                        };
+                        insert_order(side, &mut orders, orders_count, order);
+                        orders_count += 1;
+
+                        model.push(order);
+                        next_id += 1;
+                        inserted += quantity;
+                    }
+                    Op::Remove { pick } => {
+                        // ids past the sequence or already removed must be rejected
+                        let order_id = pick % (next_id + 1);
+                        let result = remove_order(&mut orders, orders_count, order_id);
+
+                        match model.iter().position(|order| order.order_id == order_id) {
+                            Some(idx) => {
+                                let order = model.remove(idx);
+                                prop_assert_eq!(result, Ok(order));
+                                orders_count -= 1;
+                                removed += order.quantity;
+                            }
+                            None => prop_assert_eq!(result, Err(CoreError::OrderNotFound)),
+                        }
+                    }
+                    Op::Decrease { pick, amount } => {
+                        let order_id = pick % (next_id + 1);
+                        let result = decrease_order(&mut orders, orders_count, order_id, amount);
+
+                        match model.iter_mut().find(|order| order.order_id == order_id) {
+                            Some(order) if order.quantity > amount => {
+                                order.quantity -= amount;
+                                prop_assert_eq!(result, Ok(*order));
+                                removed += amount;
+                            }
+                            Some(_) => {
+                                prop_assert_eq!(result, Err(CoreError::PartialOrderAmountExceed))
+                            }
+                            None => prop_assert_eq!(result, Err(CoreError::OrderNotFound)),
+                        }
+                    }
+                }
+
+                prop_assert_eq!(orders_count as usize, orders.len());
+                prop_assert_eq!(&orders, &expected_orders(side, &model));
+                prop_assert_eq!(
+                    orders.iter().map(|order| order.quantity).sum::<u64>(),
+                    inserted - removed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn same_price_keeps_time_priority() {
+        let order = |order_id, price| SimOrder {
+            order_id,
+            owner: [0; 32],
+            account_index: 0,
+            price,
+            quantity: 1,
+            created_at: order_id as i64,
+        };
+
+        let mut bids = vec![];
+        for (count, (id, price)) in [(0, 10), (1, 12), (2, 10), (3, 12)].into_iter().enumerate() {
+            insert_order(Side::Bid, &mut bids, count as u64, order(id, price));
+        }
+        let ids: Vec<u64> = bids.iter().map(|order| order.order_id).collect();
+        assert_eq!(ids, vec![1, 3, 0, 2]);
+
+        let mut asks = vec![];
+        for (count, (id, price)) in [(0, 10), (1, 12), (2, 10), (3, 12)].into_iter().enumerate() {
+            insert_order(Side::Ask, &mut asks, count as u64, order(id, price));
+        }
+        let ids: Vec<u64> = asks.iter().map(|order| order.order_id).collect();
+        assert_eq!(ids, vec![0, 2, 1, 3]);
+    }
+
+    #[test]
+    fn best_level_and_market_quote() {
+        let order = |order_id, price, quantity| SimOrder {
+            order_id,
+            owner: [0; 32],
+            account_index: 0,
+            price,
+            quantity,
+            created_at: order_id as i64,
+        };
+        // asks of 6 decimals base, two at 1.5 quote and one at 3 quote
+        let asks = vec![
+            order(0, 1_500_000, 3),
+            order(1, 1_500_000, 4),
+            order(2, 3_000_000, 10),
+            order(3, 9_000_000, 1),
+        ];
+
+        assert_eq!(best_level::<SimOrder>(&[], 0), Ok(None));
+        assert_eq!(
+            best_level(&asks, 3),
+            Ok(Some(BookLevel {
+                price: 1_500_000,
+                quantity: 7,
+                orders_count: 2,
+            }))
+        );
+
+        // 3 at 1.5 rounds 4.5 up to 5, 4 at 1.5 is 6, 2 at 3 is 6
+        assert_eq!(
+            quote_market_order(Side::Ask, &asks, 3, 9, 6),
+            Ok(MarketQuote {
+                base_amount: 9,
+                quote_amount: 17,
+                orders_filled: 3,
+                worst_price: 3_000_000,
+            })
+        );
+        // book covers only part of the amount
+        assert_eq!(
+            quote_market_order(Side::Ask, &asks, 3, 100, 6).map(|quote| quote.base_amount),
+            Ok(17)
+        );
+
+        // takers of bids receive quote rounded down, dust fills stop the walk
+        let bids = vec![order(0, 1_500_000, 3), order(1, 500_000, 5)];
+        assert_eq!(
+            quote_market_order(Side::Bid, &bids, 2, 4, 6),
+            Ok(MarketQuote {
+                base_amount: 3,
+                quote_amount: 4,
+                orders_filled: 1,
+                worst_price: 1_500_000,
+            })
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Pure Rust order book and pricing logic of hybrid-dex.
+//!
+//! The on-chain program and the off-chain [`simulator`] share these functions,
+//! so replaying order flow yields the same fills as the chain.
+
+pub mod book;
+pub mod math;
+pub mod oracle;
+pub mod simulator;
+pub mod twap;
+
+pub use book::*;
+pub use math::*;
+pub use oracle::*;
+pub use twap::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CoreError {
+    OrderNotFound,
+    PartialOrderAmountExceed,
+    MathOverflow,
+    /// Fill converts to less than one atomic unit of the taker side
+    ZeroFill,
+    /// Converted amount does not fit `u64`
+    PriceOverflow,
+    /// TWAP end observation is not after its start
+    InvalidObservationWindow,
+    /// Oracle account data is not a price of the market
+    InvalidOracle,
+    /// Oracle price is not trading, older than the allowed staleness or lacks TWAP history
+    StaleOracle,
+    /// Oracle confidence interval is wider than allowed for its price
+    UncertainOracle,
+    /// Pegged order price is not positive at the current oracle price
+    InvalidPeggedPrice,
+}
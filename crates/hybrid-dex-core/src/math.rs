@@ -0,0 +1,160 @@
+//! Conversion between base and quote amounts, `price` is quote atomic units per whole base token
+//!
+//! Order quantities are base amounts on both sides. Bids lock their quote value
+//! rounded up, and fills round the quote side in the maker's favor so rounding
+//! dust never leaves the vault to the taker.
+
+use crate::{CoreError, Side};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+fn div_round(numerator: u128, denominator: u128, rounding: Rounding) -> Option<u64> {
+    let quotient = numerator.checked_div(denominator)?;
+    let value = match rounding {
+        Rounding::Up if quotient * denominator < numerator => quotient.checked_add(1)?,
+        _ => quotient,
+    };
+    u64::try_from(value).ok()
+}
+
+/// Base amount matching `quote_quantity` at `price`
+pub fn base_amount(
+    quote_quantity: u64,
+    price: u64,
+    base_decimal: u8,
+    rounding: Rounding,
+) -> Option<u64> {
+    let numerator =
+        (quote_quantity as u128).checked_mul(10u128.checked_pow(base_decimal as u32)?)?;
+    div_round(numerator, price as u128, rounding)
+}
+
+/// Quote amount matching `base_quantity` at `price`
+pub fn quote_amount(
+    base_quantity: u64,
+    price: u64,
+    base_decimal: u8,
+    rounding: Rounding,
+) -> Option<u64> {
+    let numerator = (base_quantity as u128).checked_mul(price as u128)?;
+    div_round(
+        numerator,
+        10u128.checked_pow(base_decimal as u32)?,
+        rounding,
+    )
+}
+
+/// Quote amount a bid of `base_quantity` at `price` locks, rounded up
+pub fn locked_quote_amount(base_quantity: u64, price: u64, base_decimal: u8) -> Option<u64> {
+    quote_amount(base_quantity, price, base_decimal, Rounding::Up)
+}
+
+/// Base and quote amounts of filling `amount` base of a resting `maker_side` order.
+///
+/// Takers of a bid receive the quote amount rounded down, takers of an ask pay
+/// it rounded up, fills worth less than one atomic quote unit fail with `ZeroFill`.
+pub fn fill_amounts(
+    maker_side: Side,
+    amount: u64,
+    price: u64,
+    base_decimal: u8,
+) -> Result<(u64, u64), CoreError> {
+    let exact = quote_amount(amount, price, base_decimal, Rounding::Down)
+        .ok_or(CoreError::PriceOverflow)?;
+    if exact == 0 {
+        return Err(CoreError::ZeroFill);
+    }
+
+    let quote = match maker_side {
+        Side::Bid => exact,
+        Side::Ask => {
+            locked_quote_amount(amount, price, base_decimal).ok_or(CoreError::PriceOverflow)?
+        }
+    };
+
+    Ok((amount, quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion() {
+        // 2.5 quote per base, 6 decimals base
+        assert_eq!(
+            base_amount(5_000_000, 2_500_000, 6, Rounding::Down),
+            Some(2_000_000)
+        );
+        assert_eq!(
+            quote_amount(2_000_000, 2_500_000, 6, Rounding::Down),
+            Some(5_000_000)
+        );
+
+        assert_eq!(base_amount(1, 3, 0, Rounding::Down), Some(0));
+        assert_eq!(base_amount(1, 3, 0, Rounding::Up), Some(1));
+        assert_eq!(quote_amount(3, 3, 1, Rounding::Up), Some(1));
+        assert_eq!(quote_amount(10, 3, 1, Rounding::Up), Some(3));
+
+        assert_eq!(base_amount(1, 0, 6, Rounding::Down), None);
+        assert_eq!(base_amount(u64::MAX, 1, 9, Rounding::Down), None);
+        assert_eq!(quote_amount(u64::MAX, u64::MAX, 0, Rounding::Down), None);
+    }
+
+    #[test]
+    fn fills_round_in_maker_favor() {
+        // 7 base at 1.5 quote is 10.5 quote, bid takers get 10 and ask takers pay 11
+        assert_eq!(fill_amounts(Side::Bid, 7, 1_500_000, 6), Ok((7, 10)));
+        assert_eq!(fill_amounts(Side::Ask, 7, 1_500_000, 6), Ok((7, 11)));
+        assert_eq!(locked_quote_amount(7, 1_500_000, 6), Some(11));
+        // exact fills round neither way
+        assert_eq!(
+            fill_amounts(Side::Bid, 2_000_000, 2_500_000, 6),
+            Ok((2_000_000, 5_000_000))
+        );
+
+        // fills worth less than one atomic quote unit
+        assert_eq!(
+            fill_amounts(Side::Bid, 1, 500_000, 6),
+            Err(CoreError::ZeroFill)
+        );
+        assert_eq!(
+            fill_amounts(Side::Ask, 1, 500_000, 6),
+            Err(CoreError::ZeroFill)
+        );
+        assert_eq!(
+            fill_amounts(Side::Ask, 0, 500_000, 6),
+            Err(CoreError::ZeroFill)
+        );
+        assert_eq!(
+            fill_amounts(Side::Ask, u64::MAX, u64::MAX, 0),
+            Err(CoreError::PriceOverflow)
+        );
+    }
+
+    #[test]
+    fn bid_fills_stay_within_locked_quote() {
+        // releasing the locked quote of each piece always covers what the taker gets
+        let (price, base_decimal) = (3_333_333, 6);
+        let mut quantity = 10_000u64;
+        let mut locked = locked_quote_amount(quantity, price, base_decimal).unwrap();
+        let mut paid = 0;
+
+        while quantity > 0 {
+            let amount = quantity.min(999);
+            let (_, quote) = fill_amounts(Side::Bid, amount, price, base_decimal).unwrap();
+            quantity -= amount;
+
+            let remaining = locked_quote_amount(quantity, price, base_decimal).unwrap();
+            assert!(locked - remaining >= quote);
+            locked = remaining;
+            paid += quote;
+        }
+
+        assert!(paid <= locked_quote_amount(10_000, price, base_decimal).unwrap());
+    }
+}
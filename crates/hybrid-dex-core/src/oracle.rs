@@ -0,0 +1,524 @@
+//! Reference prices read from oracle account data, and the band orders must rest in
+//!
+//! Layouts are read at fixed offsets so the program needs no oracle SDK, the
+//! program checks account owners before handing the data over. Prices come
+//! out in market units, quote atomic units per whole base token.
+
+use crate::{CoreError, Side};
+
+/// Pyth v2 price account layout
+pub mod pyth {
+    pub const MAGIC: u32 = 0xa1b2_c3d4;
+    pub const VERSION: u32 = 2;
+    pub const PRICE_ACCOUNT_TYPE: u32 = 3;
+    /// Aggregate status of a price currently trading
+    pub const STATUS_TRADING: u32 = 1;
+
+    pub const MAGIC_OFFSET: usize = 0;
+    pub const VERSION_OFFSET: usize = 4;
+    pub const ACCOUNT_TYPE_OFFSET: usize = 8;
+    pub const EXPONENT_OFFSET: usize = 20;
+    pub const PUBLISH_TIME_OFFSET: usize = 96;
+    pub const PRICE_OFFSET: usize = 208;
+    pub const CONFIDENCE_OFFSET: usize = 216;
+    pub const STATUS_OFFSET: usize = 224;
+    pub const ACCOUNT_SIZE: usize = 3312;
+}
+
+/// Raydium CLMM pool state and observation layouts, pools order their mints by key
+pub mod clmm {
+    /// Anchor discriminator of `PoolState`
+    pub const DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+
+    pub const MINT_0_OFFSET: usize = 73;
+    pub const MINT_1_OFFSET: usize = 105;
+    pub const OBSERVATION_KEY_OFFSET: usize = 201;
+    pub const ACCOUNT_SIZE: usize = 1544;
+
+    /// Anchor discriminator of `ObservationState`
+    pub const OBSERVATION_DISCRIMINATOR: [u8; 8] = [122, 174, 197, 53, 129, 9, 165, 132];
+    /// Index of the latest observation in the ring buffer
+    pub const OBSERVATION_INDEX_OFFSET: usize = 17;
+    pub const OBSERVATIONS_OFFSET: usize = 51;
+    /// Packed `block_timestamp: u32` and `tick_cumulative: i64` followed by padding
+    pub const OBSERVATION_SIZE: usize = 44;
+    pub const OBSERVATION_NUM: usize = 100;
+    pub const OBSERVATION_ACCOUNT_SIZE: usize = 4483;
+
+    /// Tick range of Raydium CLMM pools, a tick moves the price by 1.0001
+    pub const MIN_TICK: i32 = -443_636;
+    pub const MAX_TICK: i32 = -MIN_TICK;
+    /// Q64.64 square root prices at `MIN_TICK` and `MAX_TICK`
+    pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+    pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+    /// Q64.64 values of `1 / sqrt(1.0001)^(2^i)`, Raydium's `tick_math` factors
+    pub(crate) const SQRT_PRICE_FACTORS_X64: [u128; 19] = [
+        0xfffc_b933_bd6f_b800,
+        0xfff9_7272_373d_4000,
+        0xfff2_e50f_5f65_7000,
+        0xffe5_caca_7e10_f000,
+        0xffcb_9843_d60f_7000,
+        0xff97_3b41_fa98_e800,
+        0xff2e_a164_66c9_b000,
+        0xfe5d_ee04_6a9a_3800,
+        0xfcbe_86c7_900b_b000,
+        0xf987_a725_3ac6_5800,
+        0xf339_2b08_22bb_6000,
+        0xe715_9475_a2ca_f000,
+        0xd097_f3bd_fd2f_2000,
+        0xa9f7_4646_2d9f_8000,
+        0x70d8_69a1_56f3_1c00,
+        0x31be_135f_97ed_3200,
+        0x09aa_508b_5b85_a500,
+        0x005d_6af8_dedc_582c,
+        0x0000_2216_e584_f5fa,
+    ];
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], CoreError> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(CoreError::InvalidOracle)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, CoreError> {
+    read(data, offset).map(u32::from_le_bytes)
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Result<i64, CoreError> {
+    read(data, offset).map(i64::from_le_bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, CoreError> {
+    read(data, offset).map(u64::from_le_bytes)
+}
+
+/// Aggregate price of a Pyth price account published at most `max_staleness` seconds before `now`,
+/// with a confidence interval of at most `max_confidence_bps` basis points of the price
+pub fn pyth_price(
+    data: &[u8],
+    quote_decimal: u8,
+    now: i64,
+    max_staleness: i64,
+    max_confidence_bps: u16,
+) -> Result<u64, CoreError> {
+    if read_u32(data, pyth::MAGIC_OFFSET)? != pyth::MAGIC
+        || read_u32(data, pyth::VERSION_OFFSET)? != pyth::VERSION
+        || read_u32(data, pyth::ACCOUNT_TYPE_OFFSET)? != pyth::PRICE_ACCOUNT_TYPE
+    {
+        return Err(CoreError::InvalidOracle);
+    }
+
+    let publish_time = read_i64(data, pyth::PUBLISH_TIME_OFFSET)?;
+    if read_u32(data, pyth::STATUS_OFFSET)? != pyth::STATUS_TRADING
+        || now.saturating_sub(publish_time) > max_staleness
+    {
+        return Err(CoreError::StaleOracle);
+    }
+
+    let price = read_i64(data, pyth::PRICE_OFFSET)?;
+    let exponent = read(data, pyth::EXPONENT_OFFSET).map(i32::from_le_bytes)?;
+    let price = u64::try_from(price).map_err(|_| CoreError::InvalidOracle)? as u128;
+
+    // confidence shares the price exponent
+    let confidence = read_u64(data, pyth::CONFIDENCE_OFFSET)? as u128;
+    if confidence * 10_000 > price * max_confidence_bps as u128 {
+        return Err(CoreError::UncertainOracle);
+    }
+
+    // real price is price * 10^exponent, market price counts quote atomic units
+    let scale = exponent.saturating_add(quote_decimal as i32);
+    let scaled = if scale >= 0 {
+        10u128
+            .checked_pow(scale as u32)
+            .and_then(|factor| price.checked_mul(factor))
+    } else {
+        10u128
+            .checked_pow(scale.unsigned_abs())
+            .map(|factor| price / factor)
+    };
+
+    match scaled.map(u64::try_from) {
+        Some(Ok(0)) => Err(CoreError::InvalidOracle),
+        Some(Ok(price)) => Ok(price),
+        _ => Err(CoreError::PriceOverflow),
+    }
+}
+
+/// Observation account of a Raydium CLMM pool between the market mints
+pub fn clmm_pool_observation(
+    data: &[u8],
+    base_mint: &[u8; 32],
+    quote_mint: &[u8; 32],
+) -> Result<[u8; 32], CoreError> {
+    if read::<8>(data, 0)? != clmm::DISCRIMINATOR {
+        return Err(CoreError::InvalidOracle);
+    }
+
+    let mint_0: [u8; 32] = read(data, clmm::MINT_0_OFFSET)?;
+    let mint_1: [u8; 32] = read(data, clmm::MINT_1_OFFSET)?;
+    if (&mint_0, &mint_1) != (base_mint.min(quote_mint), base_mint.max(quote_mint)) {
+        return Err(CoreError::InvalidOracle);
+    }
+
+    read(data, clmm::OBSERVATION_KEY_OFFSET)
+}
+
+/// Time weighted price of a Raydium CLMM pool over at least the last `window` seconds of its
+/// observations, the spot price a swap moves within one transaction does not reach it
+pub fn clmm_twap_price(
+    data: &[u8],
+    base_mint: &[u8; 32],
+    quote_mint: &[u8; 32],
+    base_decimal: u8,
+    window: i64,
+) -> Result<u64, CoreError> {
+    if read::<8>(data, 0)? != clmm::OBSERVATION_DISCRIMINATOR {
+        return Err(CoreError::InvalidOracle);
+    }
+
+    let observation = |index: usize| -> Result<(i64, i64), CoreError> {
+        let offset = clmm::OBSERVATIONS_OFFSET + index * clmm::OBSERVATION_SIZE;
+        Ok((read_u32(data, offset)? as i64, read_i64(data, offset + 4)?))
+    };
+
+    let latest = read(data, clmm::OBSERVATION_INDEX_OFFSET).map(u16::from_le_bytes)? as usize;
+    if latest >= clmm::OBSERVATION_NUM {
+        return Err(CoreError::InvalidOracle);
+    }
+    let (end_time, end_cumulative) = observation(latest)?;
+
+    // newest observation at least `window` seconds before the latest one, walking back
+    // until an unwritten or wrapped slot
+    let mut start = None;
+    for back in 1..clmm::OBSERVATION_NUM {
+        let (time, cumulative) =
+            observation((latest + clmm::OBSERVATION_NUM - back) % clmm::OBSERVATION_NUM)?;
+        if time == 0 || time >= end_time {
+            break;
+        }
+        if end_time - time >= window {
+            start = Some((time, cumulative));
+            break;
+        }
+    }
+    let (start_time, start_cumulative) = start.ok_or(CoreError::StaleOracle)?;
+
+    // mean tick rounded down, mint 1 atomic units per mint 0 atomic unit at it, mint 0
+    // per mint 1 at its opposite
+    let tick = end_cumulative
+        .wrapping_sub(start_cumulative)
+        .div_euclid(end_time - start_time);
+    let tick = if base_mint < quote_mint { tick } else { -tick };
+    let tick = i32::try_from(tick).map_err(|_| CoreError::InvalidOracle)?;
+
+    // Q64.64 ratio of quote to base atomic units, times the atomic units of a whole base
+    let sqrt_price_x64 = clmm_sqrt_price_at_tick(tick)?;
+    let ratio_x64 = mul_shr_64(sqrt_price_x64, sqrt_price_x64).ok_or(CoreError::PriceOverflow)?;
+    let whole_base = 10u128.pow(base_decimal as u32);
+
+    match mul_shr_64(ratio_x64, whole_base).map(u64::try_from) {
+        Some(Ok(0)) => Err(CoreError::InvalidOracle),
+        Some(Ok(price)) => Ok(price),
+        _ => Err(CoreError::PriceOverflow),
+    }
+}
+
+/// Q64.64 square root of `1.0001^tick`, as Raydium's `get_sqrt_price_at_tick` computes it
+pub fn clmm_sqrt_price_at_tick(tick: i32) -> Result<u128, CoreError> {
+    let abs_tick = tick.unsigned_abs();
+    if abs_tick > clmm::MAX_TICK as u32 {
+        return Err(CoreError::InvalidOracle);
+    }
+
+    // product of the factors of the set bits gives the price at -|tick|, each factor is
+    // below 2^64 so every step fits in 128 bits
+    let mut ratio = 1u128 << 64;
+    for (bit, factor) in clmm::SQRT_PRICE_FACTORS_X64.iter().enumerate() {
+        if abs_tick & (1 << bit) != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+
+    if tick > 0 {
+        ratio = u128::MAX / ratio;
+    }
+    Ok(ratio)
+}
+
+/// `a * b >> 64` of the 256 bit product, none when it exceeds 128 bits
+fn mul_shr_64(a: u128, b: u128) -> Option<u128> {
+    const LOW: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & LOW);
+    let (b_high, b_low) = (b >> 64, b & LOW);
+
+    // products of the 64 bit halves, any partial sum past 128 bits overflows the result
+    let middle = (a_high * b_low)
+        .checked_add(a_low * b_high)?
+        .checked_add((a_low * b_low) >> 64)?;
+    let high = a_high * b_high;
+    if high >> 64 != 0 {
+        return None;
+    }
+    (high << 64).checked_add(middle)
+}
+
+/// Lowest and highest order price within `band_bps` basis points of `oracle_price`
+pub fn price_band(oracle_price: u64, band_bps: u16) -> (u64, u64) {
+    let price = oracle_price as u128;
+    let band = price * band_bps as u128;
+
+    let min = price.saturating_sub(band.div_ceil(10_000));
+    let max = price + band / 10_000;
+
+    (min as u64, u64::try_from(max).unwrap_or(u64::MAX))
+}
+
+/// Price of an order pegged `price_offset` from `oracle_price`, capped at `limit_price`:
+/// bids never pay above it, asks never sell below it
+pub fn pegged_price(
+    side: Side,
+    oracle_price: u64,
+    price_offset: i64,
+    limit_price: u64,
+) -> Result<u64, CoreError> {
+    let pegged = oracle_price as i128 + price_offset as i128;
+    let price = match side {
+        Side::Bid => pegged.min(limit_price as i128),
+        Side::Ask => pegged.max(limit_price as i128),
+    };
+
+    if price <= 0 {
+        return Err(CoreError::InvalidPeggedPrice);
+    }
+    u64::try_from(price).map_err(|_| CoreError::PriceOverflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pyth_account(
+        price: i64,
+        confidence: u64,
+        exponent: i32,
+        publish_time: i64,
+        status: u32,
+    ) -> Vec<u8> {
+        let mut data = vec![0; pyth::ACCOUNT_SIZE];
+        data[pyth::MAGIC_OFFSET..][..4].copy_from_slice(&pyth::MAGIC.to_le_bytes());
+        data[pyth::VERSION_OFFSET..][..4].copy_from_slice(&pyth::VERSION.to_le_bytes());
+        data[pyth::ACCOUNT_TYPE_OFFSET..][..4]
+            .copy_from_slice(&pyth::PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[pyth::EXPONENT_OFFSET..][..4].copy_from_slice(&exponent.to_le_bytes());
+        data[pyth::PUBLISH_TIME_OFFSET..][..8].copy_from_slice(&publish_time.to_le_bytes());
+        data[pyth::PRICE_OFFSET..][..8].copy_from_slice(&price.to_le_bytes());
+        data[pyth::CONFIDENCE_OFFSET..][..8].copy_from_slice(&confidence.to_le_bytes());
+        data[pyth::STATUS_OFFSET..][..4].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    fn clmm_pool(mint_0: [u8; 32], mint_1: [u8; 32], observation: [u8; 32]) -> Vec<u8> {
+        let mut data = vec![0; clmm::ACCOUNT_SIZE];
+        data[..8].copy_from_slice(&clmm::DISCRIMINATOR);
+        data[clmm::MINT_0_OFFSET..][..32].copy_from_slice(&mint_0);
+        data[clmm::MINT_1_OFFSET..][..32].copy_from_slice(&mint_1);
+        data[clmm::OBSERVATION_KEY_OFFSET..][..32].copy_from_slice(&observation);
+        data
+    }
+
+    /// Observations of `(block_timestamp, tick_cumulative)` written from the ring buffer start
+    fn clmm_observations(observations: &[(u32, i64)]) -> Vec<u8> {
+        let mut data = vec![0; clmm::OBSERVATION_ACCOUNT_SIZE];
+        data[..8].copy_from_slice(&clmm::OBSERVATION_DISCRIMINATOR);
+        let latest = observations.len() as u16 - 1;
+        data[clmm::OBSERVATION_INDEX_OFFSET..][..2].copy_from_slice(&latest.to_le_bytes());
+        for (index, (time, cumulative)) in observations.iter().enumerate() {
+            let offset = clmm::OBSERVATIONS_OFFSET + index * clmm::OBSERVATION_SIZE;
+            data[offset..][..4].copy_from_slice(&time.to_le_bytes());
+            data[offset + 4..][..8].copy_from_slice(&cumulative.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn pyth_price_in_market_units() {
+        let now = 1_000;
+        let trading = pyth::STATUS_TRADING;
+
+        // 25.50 with 8 decimals into a 6 decimal quote
+        let data = pyth_account(2_550_000_000, 2_550_000, -8, now - 10, trading);
+        assert_eq!(pyth_price(&data, 6, now, 60, 100), Ok(25_500_000));
+        assert_eq!(pyth_price(&data, 9, now, 60, 100), Ok(25_500_000_000));
+        assert_eq!(pyth_price(&data, 0, now, 60, 100), Ok(25));
+
+        assert_eq!(
+            pyth_price(&data, 6, now + 60, 60, 100),
+            Err(CoreError::StaleOracle)
+        );
+        let halted = pyth_account(2_550_000_000, 0, -8, now, 0);
+        assert_eq!(
+            pyth_price(&halted, 6, now, 60, 100),
+            Err(CoreError::StaleOracle)
+        );
+
+        let negative = pyth_account(-1, 0, -8, now, trading);
+        assert_eq!(
+            pyth_price(&negative, 6, now, 60, 100),
+            Err(CoreError::InvalidOracle)
+        );
+        assert_eq!(
+            pyth_price(&data[..100], 6, now, 60, 100),
+            Err(CoreError::InvalidOracle)
+        );
+        // 0.1% confidence is within 0.1% and beyond 0.05%
+        assert_eq!(
+            pyth_price(&data, 6, now, 60, 5),
+            Err(CoreError::UncertainOracle)
+        );
+        let mut wrong_type = data.clone();
+        wrong_type[pyth::ACCOUNT_TYPE_OFFSET] = 2;
+        assert_eq!(
+            pyth_price(&wrong_type, 6, now, 60, 100),
+            Err(CoreError::InvalidOracle)
+        );
+    }
+
+    #[test]
+    fn clmm_pool_observation_of_market_mints() {
+        let (low, high, observation) = ([1; 32], [2; 32], [9; 32]);
+        let pool = clmm_pool(low, high, observation);
+
+        assert_eq!(clmm_pool_observation(&pool, &low, &high), Ok(observation));
+        assert_eq!(clmm_pool_observation(&pool, &high, &low), Ok(observation));
+        assert_eq!(
+            clmm_pool_observation(&pool, &low, &[3; 32]),
+            Err(CoreError::InvalidOracle)
+        );
+        // pools order their mints by key
+        let unordered = clmm_pool(high, low, observation);
+        assert_eq!(
+            clmm_pool_observation(&unordered, &low, &high),
+            Err(CoreError::InvalidOracle)
+        );
+    }
+
+    #[test]
+    fn clmm_twap_price_over_window() {
+        let (low, high) = ([1; 32], [2; 32]);
+
+        // tick 6932 for 300 seconds then 13864 for the last 10, about 2 then 4
+        let data = clmm_observations(&[
+            (1_000, 0),
+            (1_300, 6_932 * 300),
+            (1_310, 6_932 * 300 + 13_864 * 10),
+        ]);
+        let twap = |base, quote, window| clmm_twap_price(&data, base, quote, 6, window);
+
+        // window reaches the first observation, the last 10 seconds move the mean tick
+        // to 7155 and the price by about 2%
+        assert_eq!(twap(&low, &high, 300), Ok(2_045_135));
+        // base of the higher key is mint 1, priced at the opposite tick
+        assert_eq!(twap(&high, &low, 300), Ok(488_965));
+        // a window within the last observations follows the recent ticks
+        assert_eq!(twap(&low, &high, 10), Ok(4_000_145));
+        assert_eq!(twap(&low, &high, 311), Err(CoreError::StaleOracle));
+
+        let mut not_observation = data.clone();
+        not_observation[0] = 0;
+        assert_eq!(
+            clmm_twap_price(&not_observation, &low, &high, 6, 300),
+            Err(CoreError::InvalidOracle)
+        );
+    }
+
+    #[test]
+    fn clmm_twap_price_at_extreme_ticks() {
+        let (low, high) = ([1; 32], [2; 32]);
+        let at_tick = |tick: i64| clmm_observations(&[(1_000, 0), (1_001, tick)]);
+        let twap = |data: &[u8], base, quote, base_decimal| {
+            clmm_twap_price(data, base, quote, base_decimal, 1)
+        };
+
+        // about 1.8e19 quote units per base unit, just within u64 for a base of no decimals
+        let max = at_tick(clmm::MAX_TICK as i64);
+        assert_eq!(twap(&max, &low, &high, 0), Ok(18_446_050_713_735_950_759));
+        assert_eq!(twap(&max, &low, &high, 6), Err(CoreError::PriceOverflow));
+        assert_eq!(twap(&max, &high, &low, 6), Err(CoreError::InvalidOracle));
+
+        let min = at_tick(clmm::MIN_TICK as i64);
+        assert_eq!(twap(&min, &low, &high, 6), Err(CoreError::InvalidOracle));
+        assert_eq!(twap(&min, &high, &low, 0), Ok(18_446_050_713_735_950_759));
+
+        // mean ticks round down, out of range ones are no pool price
+        let below_one = clmm_observations(&[(1_000, 0), (1_010, -6_932 * 10 - 5)]);
+        assert_eq!(twap(&below_one, &low, &high, 6), Ok(499_940));
+        let beyond = at_tick(clmm::MAX_TICK as i64 + 1);
+        assert_eq!(twap(&beyond, &low, &high, 0), Err(CoreError::InvalidOracle));
+    }
+
+    #[test]
+    fn clmm_sqrt_price_at_tick_matches_raydium() {
+        assert_eq!(
+            clmm_sqrt_price_at_tick(clmm::MIN_TICK),
+            Ok(clmm::MIN_SQRT_PRICE_X64)
+        );
+        assert_eq!(
+            clmm_sqrt_price_at_tick(clmm::MAX_TICK),
+            Ok(clmm::MAX_SQRT_PRICE_X64)
+        );
+        assert_eq!(clmm_sqrt_price_at_tick(0), Ok(1 << 64));
+        assert_eq!(clmm_sqrt_price_at_tick(1), Ok(18_447_666_387_855_957_090));
+        assert_eq!(clmm_sqrt_price_at_tick(-1), Ok(18_445_821_805_675_395_072));
+
+        assert_eq!(
+            clmm_sqrt_price_at_tick(clmm::MIN_TICK - 1),
+            Err(CoreError::InvalidOracle)
+        );
+        assert_eq!(
+            clmm_sqrt_price_at_tick(clmm::MAX_TICK + 1),
+            Err(CoreError::InvalidOracle)
+        );
+    }
+
+    #[test]
+    fn mul_shr_64_over_256_bit_products() {
+        assert_eq!(mul_shr_64(1 << 64, 1 << 64), Some(1 << 64));
+        assert_eq!(mul_shr_64(u128::MAX, 1 << 64), Some(u128::MAX));
+        assert_eq!(mul_shr_64(u128::MAX, (1 << 64) + 1), None);
+        assert_eq!(
+            mul_shr_64(clmm::MAX_SQRT_PRICE_X64, clmm::MAX_SQRT_PRICE_X64),
+            Some(340_269_576_686_954_494_453_065_731_097_211_981_386)
+        );
+    }
+
+    #[test]
+    fn pegged_price_follows_oracle_within_limit() {
+        assert_eq!(pegged_price(Side::Bid, 1_000, -20, 2_000), Ok(980));
+        assert_eq!(pegged_price(Side::Bid, 1_000, 50, 1_020), Ok(1_020));
+        assert_eq!(pegged_price(Side::Ask, 1_000, 20, 0), Ok(1_020));
+        assert_eq!(pegged_price(Side::Ask, 1_000, -50, 990), Ok(990));
+
+        assert_eq!(
+            pegged_price(Side::Bid, 1_000, -1_000, 2_000),
+            Err(CoreError::InvalidPeggedPrice)
+        );
+        assert_eq!(
+            pegged_price(Side::Ask, u64::MAX, 1, 0),
+            Err(CoreError::PriceOverflow)
+        );
+    }
+
+    #[test]
+    fn band_around_oracle_price() {
+        assert_eq!(price_band(1_000, 500), (950, 1_050));
+        // lower bound rounds up and upper bound down, staying inside the band
+        assert_eq!(price_band(999, 500), (949, 1_048));
+        assert_eq!(price_band(1_000, 10_000), (0, 2_000));
+        assert_eq!(
+            price_band(u64::MAX, 100),
+            (u64::MAX - u64::MAX / 100 - 1, u64::MAX)
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Deterministic replay of order flow through the same book and pricing
+//! functions as the on-chain take handlers, without a validator
+
+use crate::{
+    decrease_order, fill_amounts, find_order, insert_order, remove_order, BookOrder, CoreError,
+    Side,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimOrder {
+    pub order_id: u64,
+    pub owner: [u8; 32],
+    pub account_index: u8,
+    pub price: u64,
+    pub quantity: u64,
+    pub created_at: i64,
+}
+
+impl BookOrder for SimOrder {
+    fn order_id(&self) -> u64 {
+        self.order_id
+    }
+
+    fn price(&self) -> u64 {
+        self.price
+    }
+
+    fn quantity(&self) -> u64 {
+        self.quantity
+    }
+
+    fn set_quantity(&mut self, quantity: u64) {
+        self.quantity = quantity;
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimBook {
+    pub orders_count: u64,
+    pub orders: Vec<SimOrder>,
+}
+
+/// Order flow replayed against the simulated market
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Place {
+        side: Side,
+        owner: [u8; 32],
+        account_index: u8,
+        price: u64,
+        quantity: u64,
+        timestamp: i64,
+    },
+    Cancel {
+        side: Side,
+        order_id: u64,
+    },
+    Take {
+        side: Side,
+        order_id: u64,
+    },
+    PartialTake {
+        side: Side,
+        order_id: u64,
+        amount: u64,
+    },
+}
+
+/// Tokens exchanged by a take, `maker_side` is the side of the resting order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fill {
+    pub order_id: u64,
+    pub maker: [u8; 32],
+    pub maker_account_index: u8,
+    pub maker_side: Side,
+    pub price: u64,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    Placed(SimOrder),
+    Cancelled(SimOrder),
+    Filled(Fill),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Simulator {
+    pub base_decimal: u8,
+    pub bids: SimBook,
+    pub asks: SimBook,
+    pub order_seq_num: u64,
+    pub base_total_volume: u64,
+    pub quote_total_volume: u64,
+}
+
+impl Simulator {
+    pub fn new(base_decimal: u8) -> Simulator {
+        Simulator {
+            base_decimal,
+            ..Simulator::default()
+        }
+    }
+
+    fn book(&mut self, side: Side) -> &mut SimBook {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+
+    fn fill(&mut self, side: Side, order: &SimOrder, amount: u64) -> Result<Fill, CoreError> {
+        let (base, quote) = fill_amounts(side, amount, order.price, self.base_decimal)?;
+
+        self.base_total_volume = self
+            .base_total_volume
+            .checked_add(base)
+            .ok_or(CoreError::MathOverflow)?;
+        self.quote_total_volume = self
+            .quote_total_volume
+            .checked_add(quote)
+            .ok_or(CoreError::MathOverflow)?;
+
+        Ok(Fill {
+            order_id: order.order_id,
+            maker: order.owner,
+            maker_account_index: order.account_index,
+            maker_side: side,
+            price: order.price,
+            base_amount: base,
+            quote_amount: quote,
+        })
+    }
+
+    /// Apply one action, failed actions leave the state untouched like a reverted transaction
+    pub fn apply(&mut self, action: &Action) -> Result<Event, CoreError> {
+        match *action {
+            Action::Place {
+                side,
+                owner,
+                account_index,
+                price,
+                quantity,
+                timestamp,
+            } => {
+                let order = SimOrder {
+                    order_id: self.order_seq_num,
+                    owner,
+                    account_index,
+                    price,
+                    quantity,
+                    created_at: timestamp,
+                };
+
+                let book = self.book(side);
+                insert_order(side, &mut book.orders, book.orders_count, order);
+                book.orders_count += 1;
+                self.order_seq_num += 1;
+
+                Ok(Event::Placed(order))
+            }
+            Action::Cancel { side, order_id } => {
+                let book = self.book(side);
+                let order = remove_order(&mut book.orders, book.orders_count, order_id)?;
+                book.orders_count -= 1;
+
+                Ok(Event::Cancelled(order))
+            }
+            Action::Take { side, order_id } => {
+                let book = self.book(side);
+                let idx = find_order(&book.orders, book.orders_count, order_id)?;
+                let order = book.orders[idx];
+
+                let fill = self.fill(side, &order, order.quantity)?;
+
+                let book = self.book(side);
+                remove_order(&mut book.orders, book.orders_count, order_id)?;
+                book.orders_count -= 1;
+
+                Ok(Event::Filled(fill))
+            }
+            Action::PartialTake {
+                side,
+                order_id,
+                amount,
+            } => {
+                let book = self.book(side);
+                let order = decrease_order(&mut book.orders, book.orders_count, order_id, amount)?;
+
+                Ok(Event::Filled(self.fill(side, &order, amount)?))
+            }
+        }
+    }
+
+    /// Apply actions in order, collecting fills of the successful takes
+    pub fn replay<'a>(&mut self, actions: impl IntoIterator<Item = &'a Action>) -> Vec<Fill> {
+        actions
+            .into_iter()
+            .filter_map(|action| match self.apply(action) {
+                Ok(Event::Filled(fill)) => Some(fill),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_is_deterministic() {
+        let maker = [1; 32];
+        let actions = [
+            Action::Place {
+                side: Side::Bid,
+                owner: maker,
+                account_index: 0,
+                price: 2_000_000,
+                quantity: 2_500_000_000,
+                timestamp: 1,
+            },
+            Action::Place {
+                side: Side::Ask,
+                owner: maker,
+                account_index: 1,
+                price: 3_000_000,
+                quantity: 1_000_000_000,
+                timestamp: 2,
+            },
+            Action::Take {
+                side: Side::Bid,
+                order_id: 0,
+            },
+            Action::Cancel {
+                side: Side::Bid,
+                order_id: 0,
+            },
+            Action::PartialTake {
+                side: Side::Ask,
+                order_id: 1,
+                amount: 500_000_000,
+            },
+        ];
+
+        let mut simulator = Simulator::new(9);
+        let fills = simulator.replay(&actions);
+
+        assert_eq!(
+            fills,
+            vec![
+                Fill {
+                    order_id: 0,
+                    maker,
+                    maker_account_index: 0,
+                    maker_side: Side::Bid,
+                    price: 2_000_000,
+                    base_amount: 2_500_000_000,
+                    quote_amount: 5_000_000,
+                },
+                Fill {
+                    order_id: 1,
+                    maker,
+                    maker_account_index: 1,
+                    maker_side: Side::Ask,
+                    price: 3_000_000,
+                    base_amount: 500_000_000,
+                    quote_amount: 1_500_000,
+                },
+            ]
+        );
+        assert_eq!(simulator.bids.orders_count, 0);
+        assert_eq!(simulator.base_total_volume, 3_000_000_000);
+
+        let mut again = Simulator::new(9);
+        assert_eq!(again.replay(&actions), fills);
+        assert_eq!(again, simulator);
+    }
+}
@@ -0,0 +1,84 @@
+//! Price-time accumulator of market fills, in the style of a Uniswap v2 oracle
+//!
+//! Each fill adds the previous trade price times the seconds it stood to a
+//! running sum. Two observations of that sum give the time weighted average
+//! price between them, the sum wraps on overflow so only differences matter.
+
+use crate::CoreError;
+
+/// Cumulative price-time sum of a market at `timestamp`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PriceObservation {
+    pub timestamp: i64,
+    pub price_cumulative: u128,
+}
+
+impl PriceObservation {
+    /// Observation at `timestamp` while `price` stood since this one,
+    /// timestamps before this observation leave it unchanged
+    pub fn advance(&self, price: u64, timestamp: i64) -> PriceObservation {
+        if timestamp <= self.timestamp {
+            return *self;
+        }
+
+        let elapsed = timestamp.abs_diff(self.timestamp) as u128;
+        PriceObservation {
+            timestamp,
+            price_cumulative: self
+                .price_cumulative
+                .wrapping_add((price as u128).wrapping_mul(elapsed)),
+        }
+    }
+}
+
+/// Time weighted average price between an earlier `start` and a later `end` observation
+pub fn twap(start: &PriceObservation, end: &PriceObservation) -> Result<u64, CoreError> {
+    if end.timestamp <= start.timestamp {
+        return Err(CoreError::InvalidObservationWindow);
+    }
+
+    let elapsed = end.timestamp.abs_diff(start.timestamp) as u128;
+    let price = end.price_cumulative.wrapping_sub(start.price_cumulative) / elapsed;
+
+    u64::try_from(price).map_err(|_| CoreError::PriceOverflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn twap_weights_prices_by_time() {
+        let start = PriceObservation {
+            timestamp: 1_000,
+            price_cumulative: 0,
+        };
+
+        // 10 for 30s, then 40 for 10s
+        let middle = start.advance(10, 1_030);
+        let end = middle.advance(40, 1_040);
+        assert_eq!(end.price_cumulative, 700);
+
+        assert_eq!(twap(&start, &middle), Ok(10));
+        assert_eq!(twap(&start, &end), Ok(17));
+        assert_eq!(twap(&middle, &end), Ok(40));
+
+        // earlier timestamps don't move the accumulator
+        assert_eq!(end.advance(99, 1_000), end);
+
+        assert_eq!(twap(&end, &end), Err(CoreError::InvalidObservationWindow));
+        assert_eq!(twap(&end, &start), Err(CoreError::InvalidObservationWindow));
+    }
+
+    #[test]
+    fn twap_survives_accumulator_wrap() {
+        let start = PriceObservation {
+            timestamp: 0,
+            price_cumulative: u128::MAX - 5,
+        };
+        let end = start.advance(u64::MAX, 100);
+
+        assert!(end.price_cumulative < start.price_cumulative);
+        assert_eq!(twap(&start, &end), Ok(u64::MAX));
+    }
+}
@@ -14,26 +14,21 @@ export type HybridDex = {
   },
   "instructions": [
     {
-      "name": "cancelBuyOrder",
+      "name": "auditMarket",
       "docs": [
-        "Cancel buy order as owner"
+        "* Check market vaults cover resting orders and free balances of open orders\n     * passed as remaining accounts, returns the audited totals"
       ],
       "discriminator": [
-        168,
-        199,
-        175,
-        242,
-        230,
-        250,
-        27,
-        100
+        141,
+        179,
+        62,
+        148,
+        25,
+        10,
+        23,
+        63
       ],
       "accounts": [
-        {
-          "name": "maker",
-          "writable": true,
-          "signer": true
-        },
         {
           "name": "market",
           "pda": {
@@ -56,28 +51,20 @@ export type HybridDex = {
             ]
           },
           "relations": [
-            "userOpenOrders"
+            "peggedBook",
+            "triggerBook"
           ]
         },
         {
-          "name": "userOpenOrders",
-          "writable": true,
+          "name": "bidsBook",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
-                  114,
-                  107,
-                  101,
-                  116,
+                  98,
+                  105,
+                  100,
                   45,
                   98,
                   111,
@@ -88,217 +75,83 @@ export type HybridDex = {
               {
                 "kind": "account",
                 "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "maker"
               }
             ]
           }
         },
         {
-          "name": "baseMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "quoteMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "userQuoteTokenAccount",
-          "writable": true,
+          "name": "asksBook",
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "maker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  97,
+                  115,
+                  107,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
                 ]
               },
               {
                 "kind": "account",
-                "path": "quoteMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "quoteVaultAccount",
-          "writable": true,
+          "name": "peggedBook",
+          "docs": [
+            "required once the market has one, markets created before pegged orders have none"
+          ],
+          "optional": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "market"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  112,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  103,
+                  103,
+                  101,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
                 ]
               },
               {
                 "kind": "account",
-                "path": "quoteMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "bidsBook",
-          "writable": true,
+          "name": "triggerBook",
+          "docs": [
+            "required once the market has one, markets created before trigger orders have none"
+          ],
+          "optional": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  98,
+                  116,
+                  114,
                   105,
-                  100,
+                  103,
+                  103,
+                  101,
+                  114,
                   45,
                   98,
                   111,
@@ -314,54 +167,51 @@ export type HybridDex = {
           }
         },
         {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
-        },
-        {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "baseVaultAccount"
         },
         {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
+          "name": "quoteVaultAccount"
         }
       ],
       "args": [
         {
           "name": "seed",
           "type": "u64"
-        },
-        {
-          "name": "orderId",
-          "type": "u64"
         }
-      ]
+      ],
+      "returns": {
+        "defined": {
+          "name": "marketAudit"
+        }
+      }
     },
     {
-      "name": "cancelSellOrder",
+      "name": "cancelBuyOrder",
       "docs": [
-        "Cancel sell order as owner"
+        "Cancel buy order as owner or its delegate, releasing funds to free balance"
       ],
       "discriminator": [
-        35,
-        49,
-        106,
-        38,
-        91,
-        127,
-        157,
-        20
+        168,
+        199,
+        175,
+        242,
+        230,
+        250,
+        27,
+        100
       ],
       "accounts": [
         {
-          "name": "maker",
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate"
+          ],
           "writable": true,
           "signer": true
         },
+        {
+          "name": "maker"
+        },
         {
           "name": "market",
           "pda": {
@@ -389,23 +239,19 @@ export type HybridDex = {
         },
         {
           "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "bidsBook",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
-                  114,
-                  107,
-                  101,
-                  116,
+                  98,
+                  105,
+                  100,
                   45,
                   98,
                   111,
@@ -416,206 +262,175 @@ export type HybridDex = {
               {
                 "kind": "account",
                 "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "maker"
               }
             ]
           }
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
         },
         {
-          "name": "baseMint",
-          "relations": [
-            "market"
-          ]
+          "name": "orderId",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "cancelPeggedOrder",
+      "docs": [
+        "Cancel pegged order as owner or its delegate, releasing funds to free balance"
+      ],
+      "discriminator": [
+        204,
+        74,
+        228,
+        17,
+        11,
+        99,
+        195,
+        111
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate"
+          ],
+          "writable": true,
+          "signer": true
         },
         {
-          "name": "quoteMint",
-          "relations": [
-            "market"
-          ]
+          "name": "maker"
         },
         {
-          "name": "userBaseTokenAccount",
-          "writable": true,
+          "name": "market",
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "maker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  109,
+                  97,
+                  114,
+                  107,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  116
                 ]
               },
               {
-                "kind": "account",
-                "path": "baseMint"
+                "kind": "arg",
+                "path": "seed"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
-          }
+            ]
+          },
+          "relations": [
+            "userOpenOrders",
+            "peggedBook"
+          ]
         },
         {
-          "name": "baseVaultAccount",
+          "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "peggedBook",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "market"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  112,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  103,
+                  103,
+                  101,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
                 ]
               },
               {
                 "kind": "account",
-                "path": "baseMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
-        },
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "orderId",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "cancelSellOrder",
+      "docs": [
+        "Cancel sell order as owner or its delegate, releasing funds to free balance"
+      ],
+      "discriminator": [
+        35,
+        49,
+        106,
+        38,
+        91,
+        127,
+        157,
+        20
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "maker"
+        },
+        {
+          "name": "market",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          },
+          "relations": [
+            "userOpenOrders"
+          ]
+        },
+        {
+          "name": "userOpenOrders",
+          "writable": true
+        },
         {
           "name": "asksBook",
           "writable": true,
@@ -640,22 +455,104 @@ export type HybridDex = {
               }
             ]
           }
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
         },
         {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+          "name": "orderId",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "cancelTriggerOrder",
+      "docs": [
+        "* Cancel trigger order as owner or its delegate, releasing funds to free balance\n     * and the keeper bounty to the signer"
+      ],
+      "discriminator": [
+        144,
+        84,
+        67,
+        39,
+        27,
+        25,
+        202,
+        141
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate, gets the keeper bounty back"
+          ],
+          "writable": true,
+          "signer": true
         },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "name": "maker"
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "market",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          },
+          "relations": [
+            "userOpenOrders",
+            "triggerBook"
+          ]
         },
         {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
+          "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "triggerBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  105,
+                  103,
+                  103,
+                  101,
+                  114,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
         }
       ],
       "args": [
@@ -812,7 +709,11 @@ export type HybridDex = {
           },
           "relations": [
             "bidsBook",
-            "asksBook"
+            "asksBook",
+            "tradeHistory",
+            "marketStats",
+            "peggedBook",
+            "triggerBook"
           ]
         },
         {
@@ -878,74 +779,46 @@ export type HybridDex = {
           }
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
-        }
-      ],
-      "args": [
-        {
-          "name": "seed",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "createMarket",
-      "docs": [
-        "Create market"
-      ],
-      "discriminator": [
-        103,
-        226,
-        97,
-        235,
-        200,
-        188,
-        251,
-        254
-      ],
-      "accounts": [
-        {
-          "name": "authority",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "globalPool",
+          "name": "tradeHistory",
+          "docs": [
+            "required once the market has one, markets created before trades were recorded have none"
+          ],
           "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  103,
-                  108,
-                  111,
-                  98,
+                  116,
+                  114,
                   97,
-                  108,
+                  100,
+                  101,
                   45,
-                  97,
-                  117,
-                  116,
                   104,
-                  111,
-                  114,
                   105,
+                  115,
                   116,
+                  111,
+                  114,
                   121
                 ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
               }
             ]
           }
         },
         {
-          "name": "market",
+          "name": "marketStats",
+          "docs": [
+            "required once the market has them, markets created before candles were recorded have none"
+          ],
           "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
               {
@@ -956,33 +829,39 @@ export type HybridDex = {
                   114,
                   107,
                   101,
-                  116
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
                 ]
               },
               {
                 "kind": "account",
-                "path": "global_pool.market_seq_num",
-                "account": "globalPool"
+                "path": "market"
               }
             ]
           }
         },
         {
-          "name": "baseMint"
-        },
-        {
-          "name": "quoteMint"
-        },
-        {
-          "name": "bidsBook",
+          "name": "peggedBook",
+          "docs": [
+            "required once the market has one, markets created before pegged orders have none"
+          ],
           "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  98,
-                  105,
+                  112,
+                  101,
+                  103,
+                  103,
+                  101,
                   100,
                   45,
                   98,
@@ -999,16 +878,24 @@ export type HybridDex = {
           }
         },
         {
-          "name": "asksBook",
+          "name": "triggerBook",
+          "docs": [
+            "required once the market has one, markets created before trigger orders have none"
+          ],
           "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  97,
-                  115,
-                  107,
+                  116,
+                  114,
+                  105,
+                  103,
+                  103,
+                  101,
+                  114,
                   45,
                   98,
                   111,
@@ -1023,6 +910,12 @@ export type HybridDex = {
             ]
           }
         },
+        {
+          "name": "baseVaultAccount"
+        },
+        {
+          "name": "quoteVaultAccount"
+        },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
@@ -1034,25 +927,25 @@ export type HybridDex = {
       ],
       "args": [
         {
-          "name": "name",
-          "type": "string"
+          "name": "seed",
+          "type": "u64"
         }
       ]
     },
     {
-      "name": "createOpenOrders",
+      "name": "closeOpenOrders",
       "docs": [
-        "Create open orders PDA for user in certain market"
+        "Close empty open orders PDA and reclaim rent, also after market is closed"
       ],
       "discriminator": [
-        229,
-        194,
-        212,
-        172,
-        8,
-        10,
-        134,
-        147
+        200,
+        216,
+        63,
+        239,
+        7,
+        230,
+        255,
+        20
       ],
       "accounts": [
         {
@@ -1061,74 +954,40 @@ export type HybridDex = {
           "signer": true
         },
         {
-          "name": "market"
+          "name": "market",
+          "relations": [
+            "userOpenOrders"
+          ]
         },
         {
           "name": "userOpenOrders",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
-                  114,
-                  107,
-                  101,
-                  116,
-                  45,
-                  98,
-                  111,
-                  111,
-                  107
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "user"
-              }
-            ]
-          }
+          "writable": true
         },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
         }
       ],
       "args": []
     },
     {
-      "name": "initialize",
+      "name": "createMarket",
       "docs": [
-        "* Initialize global pool\n     * admin initialize global configs"
+        "Create market with its own order limits bounded by global config"
       ],
       "discriminator": [
-        175,
-        175,
-        109,
-        31,
-        13,
-        152,
-        155,
-        237
+        103,
+        226,
+        97,
+        235,
+        200,
+        188,
+        251,
+        254
       ],
       "accounts": [
         {
-          "name": "admin",
+          "name": "authority",
           "writable": true,
           "signer": true
         },
@@ -1161,50 +1020,6 @@ export type HybridDex = {
             ]
           }
         },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
-        }
-      ],
-      "args": [
-        {
-          "name": "maxOrdersPerUser",
-          "type": "u64"
-        },
-        {
-          "name": "maxOrdersPerBook",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "partialTakeBuyOrder",
-      "docs": [
-        "Take buy order partially as taker"
-      ],
-      "discriminator": [
-        129,
-        233,
-        245,
-        100,
-        123,
-        126,
-        173,
-        137
-      ],
-      "accounts": [
-        {
-          "name": "taker",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "maker"
-        },
         {
           "name": "market",
           "writable": true,
@@ -1222,35 +1037,55 @@ export type HybridDex = {
                 ]
               },
               {
-                "kind": "arg",
-                "path": "seed"
+                "kind": "account",
+                "path": "global_pool.market_seq_num",
+                "account": "globalPool"
               }
             ]
-          },
-          "relations": [
-            "makerOpenOrders",
-            "takerOpenOrders"
-          ]
+          }
         },
         {
-          "name": "makerOpenOrders",
+          "name": "baseMint"
+        },
+        {
+          "name": "quoteMint"
+        },
+        {
+          "name": "bidsBook",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
-                  101,
-                  114,
+                  98,
+                  105,
+                  100,
                   45,
-                  109,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "asksBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
                   97,
-                  114,
+                  115,
                   107,
-                  101,
-                  116,
                   45,
                   98,
                   111,
@@ -1261,27 +1096,48 @@ export type HybridDex = {
               {
                 "kind": "account",
                 "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tradeHistory",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  97,
+                  100,
+                  101,
+                  45,
+                  104,
+                  105,
+                  115,
+                  116,
+                  111,
+                  114,
+                  121
+                ]
               },
               {
                 "kind": "account",
-                "path": "maker"
+                "path": "market"
               }
             ]
           }
         },
         {
-          "name": "takerOpenOrders",
+          "name": "marketStats",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
                   109,
                   97,
                   114,
@@ -1289,6 +1145,35 @@ export type HybridDex = {
                   101,
                   116,
                   45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "peggedBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  101,
+                  103,
+                  103,
+                  101,
+                  100,
+                  45,
                   98,
                   111,
                   111,
@@ -1298,396 +1183,255 @@ export type HybridDex = {
               {
                 "kind": "account",
                 "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "taker"
               }
             ]
           }
         },
         {
-          "name": "baseMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "quoteMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "makerBaseTokenAccount",
+          "name": "triggerBook",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "maker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  116,
+                  114,
+                  105,
+                  103,
+                  103,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  114,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
                 ]
               },
               {
                 "kind": "account",
-                "path": "baseMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "takerBaseTokenAccount",
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "maxOrdersPerUser",
+          "type": "u64"
+        },
+        {
+          "name": "maxOrdersPerBook",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createMarketStats",
+      "docs": [
+        "Create stats of a market created before candles were recorded"
+      ],
+      "discriminator": [
+        186,
+        181,
+        8,
+        9,
+        73,
+        59,
+        226,
+        104
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "docs": [
+            "anyone can pay for the stats of a market created before they existed"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "market",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "taker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  109,
+                  97,
+                  114,
+                  107,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  116
                 ]
               },
               {
-                "kind": "account",
-                "path": "baseMint"
+                "kind": "arg",
+                "path": "seed"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "takerQuoteTokenAccount",
+          "name": "marketStats",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "taker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  109,
+                  97,
+                  114,
+                  107,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
                 ]
               },
               {
                 "kind": "account",
-                "path": "quoteMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "quoteVaultAccount",
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createOpenOrders",
+      "docs": [
+        "Create open orders PDA for user sub account in certain market"
+      ],
+      "discriminator": [
+        229,
+        194,
+        212,
+        172,
+        8,
+        10,
+        134,
+        147
+      ],
+      "accounts": [
+        {
+          "name": "user",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "market"
+        },
+        {
+          "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "accountIndex",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "createPeggedBook",
+      "docs": [
+        "Create pegged book of a market created before pegged orders existed"
+      ],
+      "discriminator": [
+        51,
+        26,
+        0,
+        128,
+        79,
+        106,
+        109,
+        180
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "docs": [
+            "anyone can pay for the pegged book of a market created before it existed"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "market",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "market"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  109,
+                  97,
+                  114,
+                  107,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  116
                 ]
               },
               {
-                "kind": "account",
-                "path": "quoteMint"
+                "kind": "arg",
+                "path": "seed"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "bidsBook",
+          "name": "peggedBook",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  98,
-                  105,
+                  112,
+                  101,
+                  103,
+                  103,
+                  101,
                   100,
                   45,
                   98,
@@ -1703,62 +1447,42 @@ export type HybridDex = {
             ]
           }
         },
-        {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
-        },
-        {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-        },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
         }
       ],
       "args": [
         {
           "name": "seed",
           "type": "u64"
-        },
-        {
-          "name": "orderId",
-          "type": "u64"
-        },
-        {
-          "name": "amount",
-          "type": "u64"
         }
       ]
     },
     {
-      "name": "partialTakeSellOrder",
+      "name": "createTradeHistory",
       "docs": [
-        "Take sell order partially as taker"
+        "Create trade history of a market created before trades were recorded"
       ],
       "discriminator": [
-        229,
-        207,
-        14,
-        109,
-        78,
+        191,
+        152,
+        47,
+        18,
+        113,
         166,
-        99,
-        192
+        91,
+        202
       ],
       "accounts": [
         {
-          "name": "taker",
+          "name": "payer",
+          "docs": [
+            "anyone can pay for the trade history of a market created before it existed"
+          ],
           "writable": true,
           "signer": true
         },
-        {
-          "name": "maker"
-        },
         {
           "name": "market",
           "writable": true,
@@ -1780,68 +1504,112 @@ export type HybridDex = {
                 "path": "seed"
               }
             ]
-          },
-          "relations": [
-            "makerOpenOrders",
-            "takerOpenOrders"
-          ]
+          }
         },
         {
-          "name": "makerOpenOrders",
+          "name": "tradeHistory",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
-                  101,
+                  116,
                   114,
-                  45,
-                  109,
                   97,
-                  114,
-                  107,
+                  100,
                   101,
-                  116,
                   45,
-                  98,
-                  111,
+                  104,
+                  105,
+                  115,
+                  116,
                   111,
-                  107
+                  114,
+                  121
                 ]
               },
               {
                 "kind": "account",
                 "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "maker"
               }
             ]
           }
         },
         {
-          "name": "takerOpenOrders",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createTriggerBook",
+      "docs": [
+        "Create trigger book of a market created before trigger orders existed"
+      ],
+      "discriminator": [
+        39,
+        130,
+        26,
+        133,
+        22,
+        138,
+        117,
+        54
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "docs": [
+            "anyone can pay for the trigger book of a market created before it existed"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
                   114,
                   107,
                   101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          }
+        },
+        {
+          "name": "triggerBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
                   116,
+                  114,
+                  105,
+                  103,
+                  103,
+                  101,
+                  114,
                   45,
                   98,
                   111,
@@ -1852,14 +1620,59 @@ export type HybridDex = {
               {
                 "kind": "account",
                 "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "taker"
               }
             ]
           }
         },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "deposit",
+      "docs": [
+        "Deposit tokens into open orders free balance as owner or its delegate"
+      ],
+      "discriminator": [
+        242,
+        35,
+        198,
+        137,
+        82,
+        225,
+        242,
+        182
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "user"
+        },
+        {
+          "name": "market",
+          "relations": [
+            "userOpenOrders"
+          ]
+        },
+        {
+          "name": "userOpenOrders",
+          "writable": true
+        },
         {
           "name": "baseMint",
           "relations": [
@@ -1873,13 +1686,21 @@ export type HybridDex = {
           ]
         },
         {
-          "name": "makerQuoteTokenAccount",
+          "name": "userBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "userQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "baseVaultAccount",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "account",
-                "path": "maker"
+                "path": "market"
               },
               {
                 "kind": "const",
@@ -1920,7 +1741,7 @@ export type HybridDex = {
               },
               {
                 "kind": "account",
-                "path": "quoteMint"
+                "path": "baseMint"
               }
             ],
             "program": {
@@ -1963,13 +1784,13 @@ export type HybridDex = {
           }
         },
         {
-          "name": "takerBaseTokenAccount",
+          "name": "quoteVaultAccount",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "account",
-                "path": "taker"
+                "path": "market"
               },
               {
                 "kind": "const",
@@ -2010,7 +1831,7 @@ export type HybridDex = {
               },
               {
                 "kind": "account",
-                "path": "baseMint"
+                "path": "quoteMint"
               }
             ],
             "program": {
@@ -2053,183 +1874,116 @@ export type HybridDex = {
           }
         },
         {
-          "name": "takerQuoteTokenAccount",
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "baseAmount",
+          "type": "u64"
+        },
+        {
+          "name": "quoteAmount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "executeTrigger",
+      "docs": [
+        "* Execute trigger order whose price has crossed as any keeper, resting it in the book or\n     * filling it against the book orders of other owners, and pay the keeper its bounty"
+      ],
+      "discriminator": [
+        158,
+        99,
+        201,
+        137,
+        192,
+        20,
+        236,
+        136
+      ],
+      "accounts": [
+        {
+          "name": "keeper",
+          "docs": [
+            "anyone can execute a crossed trigger and earn its bounty"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "owner"
+        },
+        {
+          "name": "market",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "taker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  109,
+                  97,
+                  114,
+                  107,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  116
                 ]
               },
               {
-                "kind": "account",
-                "path": "quoteMint"
+                "kind": "arg",
+                "path": "seed"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
-          }
+            ]
+          },
+          "relations": [
+            "ownerOpenOrders",
+            "triggerBook",
+            "tradeHistory",
+            "marketStats"
+          ]
         },
         {
-          "name": "baseVaultAccount",
+          "name": "ownerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "bidsBook",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "market"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  98,
+                  105,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
                 ]
               },
               {
                 "kind": "account",
-                "path": "baseMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
@@ -2258,20 +2012,116 @@ export type HybridDex = {
           }
         },
         {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+          "name": "triggerBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  105,
+                  103,
+                  103,
+                  101,
+                  114,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
         },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "name": "tradeHistory",
+          "docs": [
+            "required once the market has one, markets created before trades were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  97,
+                  100,
+                  101,
+                  45,
+                  104,
+                  105,
+                  115,
+                  116,
+                  111,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "marketStats",
+          "docs": [
+            "required once the market has them, markets created before candles were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
         },
         {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
+          "name": "baseVaultAccount",
+          "writable": true
+        },
+        {
+          "name": "quoteVaultAccount",
+          "writable": true
+        },
+        {
+          "name": "oracle",
+          "optional": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
         }
       ],
       "args": [
@@ -2282,88 +2132,57 @@ export type HybridDex = {
         {
           "name": "orderId",
           "type": "u64"
-        },
-        {
-          "name": "amount",
-          "type": "u64"
         }
       ]
     },
     {
-      "name": "placeBuyOrder",
+      "name": "getBestPrices",
       "docs": [
-        "Place buy order as maker"
+        "View best bid and ask levels, returned for CPI callers and simulation"
       ],
       "discriminator": [
-        47,
-        253,
-        241,
-        214,
-        167,
-        204,
-        11,
-        39
+        93,
+        2,
+        233,
+        124,
+        255,
+        213,
+        130,
+        206
       ],
       "accounts": [
         {
-          "name": "maker",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "globalPool",
+          "name": "market",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  103,
-                  108,
-                  111,
-                  98,
-                  97,
-                  108,
-                  45,
+                  109,
                   97,
-                  117,
-                  116,
-                  104,
-                  111,
                   114,
-                  105,
-                  116,
-                  121
+                  107,
+                  101,
+                  116
                 ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
               }
             ]
           }
         },
         {
-          "name": "market",
-          "writable": true,
-          "relations": [
-            "userOpenOrders"
-          ]
-        },
-        {
-          "name": "userOpenOrders",
-          "writable": true,
+          "name": "bidsBook",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
-                  114,
-                  107,
-                  101,
-                  116,
+                  98,
+                  105,
+                  100,
                   45,
                   98,
                   111,
@@ -2374,217 +2193,20 @@ export type HybridDex = {
               {
                 "kind": "account",
                 "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "maker"
               }
             ]
           }
         },
         {
-          "name": "baseMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "quoteMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "userQuoteTokenAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "account",
-                "path": "maker"
-              },
-              {
-                "kind": "const",
-                "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "quoteMint"
-              }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
-          }
-        },
-        {
-          "name": "quoteVaultAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "account",
-                "path": "market"
-              },
-              {
-                "kind": "const",
-                "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "quoteMint"
-              }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
-          }
-        },
-        {
-          "name": "bidsBook",
-          "writable": true,
+          "name": "asksBook",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  98,
-                  105,
-                  100,
+                  97,
+                  115,
+                  107,
                   45,
                   98,
                   111,
@@ -2598,110 +2220,205 @@ export type HybridDex = {
               }
             ]
           }
-        },
-        {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
-        },
-        {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
         }
       ],
       "args": [
         {
-          "name": "price",
-          "type": "u64"
-        },
-        {
-          "name": "quantity",
+          "name": "seed",
           "type": "u64"
         }
-      ]
+      ],
+      "returns": {
+        "defined": {
+          "name": "bestPrices"
+        }
+      }
     },
     {
-      "name": "placeSellOrder",
+      "name": "getUserPosition",
       "docs": [
-        "Place sell order as maker"
+        "View balances of an open orders account, returned for CPI callers and simulation"
       ],
       "discriminator": [
-        254,
-        177,
-        180,
-        104,
-        171,
-        194,
-        79,
-        86
+        134,
+        165,
+        10,
+        74,
+        46,
+        176,
+        252,
+        181
       ],
       "accounts": [
         {
-          "name": "maker",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "globalPool",
+          "name": "market",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  103,
-                  108,
-                  111,
-                  98,
-                  97,
-                  108,
-                  45,
+                  109,
                   97,
-                  117,
-                  116,
-                  104,
-                  111,
                   114,
-                  105,
-                  116,
-                  121
+                  107,
+                  101,
+                  116
                 ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
               }
             ]
-          }
-        },
-        {
-          "name": "market",
-          "writable": true,
+          },
           "relations": [
             "userOpenOrders"
           ]
         },
         {
-          "name": "userOpenOrders",
+          "name": "userOpenOrders"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        }
+      ],
+      "returns": {
+        "defined": {
+          "name": "userPosition"
+        }
+      }
+    },
+    {
+      "name": "initialize",
+      "docs": [
+        "* Initialize global pool\n     * admin initialize global configs"
+      ],
+      "discriminator": [
+        175,
+        175,
+        109,
+        31,
+        13,
+        152,
+        155,
+        237
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "globalPool",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  45,
+                  97,
                   117,
-                  115,
-                  101,
+                  116,
+                  104,
+                  111,
                   114,
-                  45,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "maxOrdersPerUser",
+          "type": "u64"
+        },
+        {
+          "name": "maxOrdersPerBook",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "migrateBooks",
+      "docs": [
+        "Convert legacy books to the current format best first with market owner authority"
+      ],
+      "discriminator": [
+        125,
+        68,
+        99,
+        209,
+        242,
+        195,
+        147,
+        108
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "market authority pays for the wider orders"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
                   109,
                   97,
                   114,
                   107,
                   101,
-                  116,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bidsBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  105,
+                  100,
                   45,
                   98,
                   111,
@@ -2712,217 +2429,192 @@ export type HybridDex = {
               {
                 "kind": "account",
                 "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "maker"
               }
             ]
           }
         },
         {
-          "name": "baseMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "quoteMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "userBaseTokenAccount",
+          "name": "asksBook",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "maker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  97,
+                  115,
+                  107,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
                 ]
               },
               {
                 "kind": "account",
-                "path": "baseMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "baseVaultAccount",
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "migrateGlobalPool",
+      "docs": [
+        "Admin can grow global pool into the current account layout"
+      ],
+      "discriminator": [
+        113,
+        61,
+        140,
+        78,
+        202,
+        51,
+        136,
+        245
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "globalPool",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "market"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  45,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
                 ]
-              },
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "migrateMarket",
+      "docs": [
+        "Grow market into the current account layout, paid by anyone"
+      ],
+      "discriminator": [
+        201,
+        113,
+        181,
+        120,
+        217,
+        60,
+        109,
+        203
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "docs": [
+            "anyone can pay to migrate, the layout change doesn't touch market state"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "globalPool",
+          "docs": [
+            "global pool must be migrated first, its limits backfill the market limits"
+          ],
+          "pda": {
+            "seeds": [
               {
-                "kind": "account",
-                "path": "baseMint"
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  45,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "asksBook",
+          "name": "market",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
+                  109,
                   97,
-                  115,
+                  114,
                   107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bidsBook",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  105,
+                  100,
                   45,
                   98,
                   111,
@@ -2937,48 +2629,70 @@ export type HybridDex = {
             ]
           }
         },
-        {
-          "name": "associatedTokenProgram",
-          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
-        },
-        {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-        },
         {
           "name": "systemProgram",
           "address": "11111111111111111111111111111111"
-        },
-        {
-          "name": "rent",
-          "address": "SysvarRent111111111111111111111111111111111"
         }
       ],
       "args": [
         {
-          "name": "price",
+          "name": "seed",
           "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "migrateOpenOrders",
+      "docs": [
+        "Grow open orders PDA into the current account layout, paid by anyone"
+      ],
+      "discriminator": [
+        173,
+        187,
+        90,
+        171,
+        68,
+        71,
+        136,
+        147
+      ],
+      "accounts": [
+        {
+          "name": "payer",
+          "docs": [
+            "anyone can pay to migrate, the layout change doesn't touch balances"
+          ],
+          "writable": true,
+          "signer": true
         },
         {
-          "name": "quantity",
-          "type": "u64"
+          "name": "userOpenOrders",
+          "docs": [
+            "owner and discriminator are checked while decoding"
+          ],
+          "writable": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
         }
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "takeBuyOrder",
+      "name": "partialTakeBuyOrder",
       "docs": [
-        "Take buy order as taker"
+        "Take buy order partially as taker, returns the fill"
       ],
       "discriminator": [
-        51,
-        18,
-        45,
-        16,
-        46,
-        147,
-        43,
-        45
+        129,
+        233,
+        245,
+        100,
+        123,
+        126,
+        173,
+        137
       ],
       "accounts": [
         {
@@ -3013,112 +2727,51 @@ export type HybridDex = {
           },
           "relations": [
             "makerOpenOrders",
-            "takerOpenOrders"
+            "takerOpenOrders",
+            "tradeHistory",
+            "marketStats"
           ]
         },
         {
           "name": "makerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "takerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "baseMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "quoteMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "makerBaseTokenAccount",
+          "docs": [
+            "maker proceeds always go to its ATA, the taker can't pick the destination"
+          ],
           "writable": true,
           "pda": {
             "seeds": [
+              {
+                "kind": "account",
+                "path": "maker"
+              },
               {
                 "kind": "const",
                 "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
-                  114,
-                  107,
-                  101,
-                  116,
-                  45,
-                  98,
-                  111,
-                  111,
-                  107
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "maker"
-              }
-            ]
-          }
-        },
-        {
-          "name": "takerOpenOrders",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
-                  114,
-                  107,
-                  101,
-                  116,
-                  45,
-                  98,
-                  111,
-                  111,
-                  107
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "taker"
-              }
-            ]
-          }
-        },
-        {
-          "name": "baseMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "quoteMint",
-          "relations": [
-            "market"
-          ]
-        },
-        {
-          "name": "makerBaseTokenAccount",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "account",
-                "path": "maker"
-              },
-              {
-                "kind": "const",
-                "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
                   101,
                   161,
                   147,
@@ -3194,12 +2847,20 @@ export type HybridDex = {
         },
         {
           "name": "takerBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "takerQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "quoteVaultAccount",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "account",
-                "path": "taker"
+                "path": "market"
               },
               {
                 "kind": "const",
@@ -3240,7 +2901,7 @@ export type HybridDex = {
               },
               {
                 "kind": "account",
-                "path": "baseMint"
+                "path": "quoteMint"
               }
             ],
             "program": {
@@ -3283,206 +2944,93 @@ export type HybridDex = {
           }
         },
         {
-          "name": "takerQuoteTokenAccount",
+          "name": "bidsBook",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "taker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  98,
+                  105,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
                 ]
               },
               {
                 "kind": "account",
-                "path": "quoteMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "quoteVaultAccount",
+          "name": "tradeHistory",
+          "docs": [
+            "required once the market has one, markets created before trades were recorded have none"
+          ],
           "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  97,
+                  100,
+                  101,
+                  45,
+                  104,
+                  105,
+                  115,
+                  116,
+                  111,
+                  114,
+                  121
+                ]
+              },
               {
                 "kind": "account",
                 "path": "market"
-              },
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketStats",
+          "docs": [
+            "required once the market has them, markets created before candles were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
+                  109,
+                  97,
+                  114,
+                  107,
                   101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
                 ]
               },
               {
                 "kind": "account",
-                "path": "quoteMint"
-              }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
-          }
-        },
-        {
-          "name": "bidsBook",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  98,
-                  105,
-                  100,
-                  45,
-                  98,
-                  111,
-                  111,
-                  107
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "market"
+                "path": "market"
               }
             ]
           }
@@ -3512,23 +3060,32 @@ export type HybridDex = {
         {
           "name": "orderId",
           "type": "u64"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
         }
-      ]
+      ],
+      "returns": {
+        "defined": {
+          "name": "trade"
+        }
+      }
     },
     {
-      "name": "takeSellOrder",
+      "name": "partialTakeSellOrder",
       "docs": [
-        "Take sell order as taker"
+        "Take sell order partially as taker, returns the fill"
       ],
       "discriminator": [
-        168,
-        69,
-        47,
-        247,
-        196,
-        211,
-        61,
-        120
+        229,
+        207,
+        14,
+        109,
+        78,
+        166,
+        99,
+        192
       ],
       "accounts": [
         {
@@ -3563,82 +3120,18 @@ export type HybridDex = {
           },
           "relations": [
             "makerOpenOrders",
-            "takerOpenOrders"
+            "takerOpenOrders",
+            "tradeHistory",
+            "marketStats"
           ]
         },
         {
           "name": "makerOpenOrders",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
-                  114,
-                  107,
-                  101,
-                  116,
-                  45,
-                  98,
-                  111,
-                  111,
-                  107
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "maker"
-              }
-            ]
-          }
+          "writable": true
         },
         {
           "name": "takerOpenOrders",
-          "writable": true,
-          "pda": {
-            "seeds": [
-              {
-                "kind": "const",
-                "value": [
-                  117,
-                  115,
-                  101,
-                  114,
-                  45,
-                  109,
-                  97,
-                  114,
-                  107,
-                  101,
-                  116,
-                  45,
-                  98,
-                  111,
-                  111,
-                  107
-                ]
-              },
-              {
-                "kind": "account",
-                "path": "market"
-              },
-              {
-                "kind": "account",
-                "path": "taker"
-              }
-            ]
-          }
+          "writable": true
         },
         {
           "name": "baseMint",
@@ -3654,6 +3147,9 @@ export type HybridDex = {
         },
         {
           "name": "makerQuoteTokenAccount",
+          "docs": [
+            "maker proceeds always go to its ATA, the taker can't pick the destination"
+          ],
           "writable": true,
           "pda": {
             "seeds": [
@@ -3744,12 +3240,20 @@ export type HybridDex = {
         },
         {
           "name": "takerBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "takerQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "baseVaultAccount",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "account",
-                "path": "taker"
+                "path": "market"
               },
               {
                 "kind": "const",
@@ -3833,113 +3337,206 @@ export type HybridDex = {
           }
         },
         {
-          "name": "takerQuoteTokenAccount",
+          "name": "asksBook",
           "writable": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "taker"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
-                  161,
-                  147,
-                  217,
-                  203,
-                  225,
-                  70,
-                  206,
-                  235,
-                  121,
-                  172,
-                  28,
-                  180,
-                  133,
-                  237,
-                  95,
-                  91,
-                  55,
-                  145,
-                  58,
-                  140,
-                  245,
-                  133,
-                  126,
-                  255,
-                  0,
-                  169
+                  97,
+                  115,
+                  107,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
                 ]
               },
               {
                 "kind": "account",
-                "path": "quoteMint"
+                "path": "market"
               }
-            ],
-            "program": {
-              "kind": "const",
-              "value": [
-                140,
-                151,
-                37,
-                143,
-                78,
-                36,
-                137,
-                241,
-                187,
-                61,
-                16,
-                41,
-                20,
-                142,
-                13,
-                131,
-                11,
-                90,
-                19,
-                153,
-                218,
-                255,
-                16,
-                132,
-                4,
-                142,
-                123,
-                216,
-                219,
-                233,
-                248,
-                89
-              ]
-            }
+            ]
           }
         },
         {
-          "name": "baseVaultAccount",
+          "name": "tradeHistory",
+          "docs": [
+            "required once the market has one, markets created before trades were recorded have none"
+          ],
           "writable": true,
+          "optional": true,
           "pda": {
             "seeds": [
-              {
-                "kind": "account",
-                "path": "market"
-              },
               {
                 "kind": "const",
                 "value": [
-                  6,
-                  221,
-                  246,
-                  225,
-                  215,
-                  101,
+                  116,
+                  114,
+                  97,
+                  100,
+                  101,
+                  45,
+                  104,
+                  105,
+                  115,
+                  116,
+                  111,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketStats",
+          "docs": [
+            "required once the market has them, markets created before candles were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "orderId",
+          "type": "u64"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ],
+      "returns": {
+        "defined": {
+          "name": "trade"
+        }
+      }
+    },
+    {
+      "name": "placeBuyOrder",
+      "docs": [
+        "Place buy order as maker or its delegate, locking free balance first, returns the order id"
+      ],
+      "discriminator": [
+        47,
+        253,
+        241,
+        214,
+        167,
+        204,
+        11,
+        39
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "maker"
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "relations": [
+            "userOpenOrders"
+          ]
+        },
+        {
+          "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "baseMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "quoteMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "userQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "quoteVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
                   161,
                   147,
                   217,
@@ -3970,7 +3567,7 @@ export type HybridDex = {
               },
               {
                 "kind": "account",
-                "path": "baseMint"
+                "path": "quoteMint"
               }
             ],
             "program": {
@@ -4013,16 +3610,16 @@ export type HybridDex = {
           }
         },
         {
-          "name": "asksBook",
+          "name": "bidsBook",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  97,
-                  115,
-                  107,
+                  98,
+                  105,
+                  100,
                   45,
                   98,
                   111,
@@ -4037,6 +3634,10 @@ export type HybridDex = {
             ]
           }
         },
+        {
+          "name": "oracle",
+          "optional": true
+        },
         {
           "name": "associatedTokenProgram",
           "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
@@ -4056,216 +3657,3913 @@ export type HybridDex = {
       ],
       "args": [
         {
-          "name": "seed",
+          "name": "price",
           "type": "u64"
         },
         {
-          "name": "orderId",
+          "name": "quantity",
           "type": "u64"
         }
-      ]
+      ],
+      "returns": "u64"
     },
     {
-      "name": "transferAdmin",
+      "name": "placePeggedOrder",
       "docs": [
-        "Admin can transfer admin authority"
+        "* Place order pegged `price_offset` from the market oracle price as maker or its delegate,\n     * bids lock quote at a non-zero `limit_price` and never pay above it, asks never sell below\n     * it, returns the order id"
       ],
       "discriminator": [
-        42,
-        242,
-        66,
-        106,
-        228,
-        10,
-        111,
-        156
+        39,
+        90,
+        41,
+        60,
+        244,
+        27,
+        134,
+        39
       ],
       "accounts": [
         {
-          "name": "admin",
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "maker"
+        },
+        {
+          "name": "market",
           "writable": true,
-          "signer": true,
           "relations": [
-            "globalPool"
+            "userOpenOrders",
+            "peggedBook"
           ]
         },
         {
-          "name": "globalPool",
+          "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "baseMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "quoteMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "userBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "userQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "baseVaultAccount",
+          "docs": [
+            "both vaults exist once a pegged order rests, takes and cancels rely on it"
+          ],
           "writable": true,
           "pda": {
             "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
               {
                 "kind": "const",
                 "value": [
-                  103,
-                  108,
-                  111,
-                  98,
-                  97,
-                  108,
-                  45,
-                  97,
-                  117,
-                  116,
-                  104,
-                  111,
-                  114,
-                  105,
-                  116,
-                  121
-                ]
-              }
-            ]
-          }
-        }
-      ],
-      "args": [
-        {
-          "name": "newAdmin",
-          "type": "pubkey"
-        }
-      ]
-    }
-  ],
-  "accounts": [
-    {
-      "name": "book",
-      "discriminator": [
-        121,
-        34,
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "baseMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "quoteVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "quoteMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "peggedBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  101,
+                  103,
+                  103,
+                  101,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "oracle"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "side",
+          "type": {
+            "defined": {
+              "name": "side"
+            }
+          }
+        },
+        {
+          "name": "priceOffset",
+          "type": "i64"
+        },
+        {
+          "name": "limitPrice",
+          "type": "u64"
+        },
+        {
+          "name": "quantity",
+          "type": "u64"
+        }
+      ],
+      "returns": "u64"
+    },
+    {
+      "name": "placeSellOrder",
+      "docs": [
+        "Place sell order as maker or its delegate, locking free balance first, returns the order id"
+      ],
+      "discriminator": [
+        254,
+        177,
+        180,
+        104,
+        171,
+        194,
+        79,
+        86
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "maker"
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "relations": [
+            "userOpenOrders"
+          ]
+        },
+        {
+          "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "baseMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "quoteMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "userBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "baseVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "baseMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "asksBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  97,
+                  115,
+                  107,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "oracle",
+          "optional": true
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "price",
+          "type": "u64"
+        },
+        {
+          "name": "quantity",
+          "type": "u64"
+        }
+      ],
+      "returns": "u64"
+    },
+    {
+      "name": "placeTriggerOrder",
+      "docs": [
+        "* Place stop or take profit order as maker or its delegate, escrowing its funds from free\n     * balance and a keeper bounty from the signer, returns the order id"
+      ],
+      "discriminator": [
+        32,
+        156,
+        50,
+        188,
+        232,
+        159,
+        112,
+        236
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "open orders owner or its delegate, pays the keeper bounty"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "maker"
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "relations": [
+            "userOpenOrders",
+            "triggerBook"
+          ]
+        },
+        {
+          "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "triggerBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  105,
+                  103,
+                  103,
+                  101,
+                  114,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "params",
+          "type": {
+            "defined": {
+              "name": "triggerOrderParams"
+            }
+          }
+        }
+      ],
+      "returns": "u64"
+    },
+    {
+      "name": "quoteMarketOrder",
+      "docs": [
+        "* View amounts of a market order taking up to `amount` base on `side`,\n     * returned for CPI callers and simulation"
+      ],
+      "discriminator": [
+        100,
+        131,
+        81,
+        108,
+        80,
+        215,
+        73,
+        241
+      ],
+      "accounts": [
+        {
+          "name": "market",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          }
+        },
+        {
+          "name": "bidsBook",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  105,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "asksBook",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  97,
+                  115,
+                  107,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "side",
+          "type": {
+            "defined": {
+              "name": "side"
+            }
+          }
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ],
+      "returns": {
+        "defined": {
+          "name": "marketOrderQuote"
+        }
+      }
+    },
+    {
+      "name": "resizeBook",
+      "docs": [
+        "Grow bids and asks book capacity with market owner authority"
+      ],
+      "discriminator": [
+        10,
+        17,
+        222,
+        209,
+        3,
+        211,
+        139,
+        117
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "globalPool",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  45,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          },
+          "relations": [
+            "bidsBook",
+            "asksBook"
+          ]
+        },
+        {
+          "name": "bidsBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  105,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "asksBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  97,
+                  115,
+                  107,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "maxOrdersPerBook",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "setCandleIntervals",
+      "docs": [
+        "Replace market candle series with market owner authority"
+      ],
+      "discriminator": [
+        37,
+        15,
+        128,
+        193,
+        162,
+        130,
+        10,
+        84
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "market",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          },
+          "relations": [
+            "marketStats"
+          ]
+        },
+        {
+          "name": "marketStats",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "intervals",
+          "type": {
+            "vec": "i64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "setDelegate",
+      "docs": [
+        "Set or clear delegate trading authority of open orders PDA as owner"
+      ],
+      "discriminator": [
+        242,
+        30,
+        46,
+        76,
+        108,
+        235,
+        128,
+        181
+      ],
+      "accounts": [
+        {
+          "name": "user",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "market",
+          "relations": [
+            "userOpenOrders"
+          ]
+        },
+        {
+          "name": "userOpenOrders",
+          "writable": true
+        }
+      ],
+      "args": [
+        {
+          "name": "delegate",
+          "type": {
+            "option": "pubkey"
+          }
+        }
+      ]
+    },
+    {
+      "name": "setOracle",
+      "docs": [
+        "* Attach a Pyth price account or the observations of a Raydium CLMM pool as oracle with\n     * market owner authority, orders must then be placed within `price_band_bps` of its\n     * price, `OracleKind::None` detaches it"
+      ],
+      "discriminator": [
+        186,
+        128,
+        81,
+        104,
+        74,
+        79,
+        18,
+        224
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          }
+        },
+        {
+          "name": "oracle",
+          "optional": true
+        },
+        {
+          "name": "oraclePool",
+          "optional": true
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "oracleKind",
+          "type": {
+            "defined": {
+              "name": "oracleKind"
+            }
+          }
+        },
+        {
+          "name": "priceBandBps",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "setOrderMinimums",
+      "docs": [
+        "* Set the smallest quote value of pegged and trigger orders and of the trades moving last\n     * price triggers with market owner authority"
+      ],
+      "discriminator": [
+        72,
+        192,
+        147,
+        182,
+        109,
+        91,
+        64,
+        75
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "signer": true
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "minOrderValue",
+          "type": "u64"
+        },
+        {
+          "name": "minTriggerTradeValue",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "takeBuyOrder",
+      "docs": [
+        "Take buy order as taker, returns the fill"
+      ],
+      "discriminator": [
+        51,
+        18,
+        45,
+        16,
+        46,
+        147,
+        43,
+        45
+      ],
+      "accounts": [
+        {
+          "name": "taker",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "maker"
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          },
+          "relations": [
+            "makerOpenOrders",
+            "takerOpenOrders",
+            "tradeHistory",
+            "marketStats"
+          ]
+        },
+        {
+          "name": "makerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "takerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "baseMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "quoteMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "makerBaseTokenAccount",
+          "docs": [
+            "maker proceeds always go to its ATA, the taker can't pick the destination"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "maker"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "baseMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "takerBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "takerQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "quoteVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "quoteMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "bidsBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  105,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tradeHistory",
+          "docs": [
+            "required once the market has one, markets created before trades were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  97,
+                  100,
+                  101,
+                  45,
+                  104,
+                  105,
+                  115,
+                  116,
+                  111,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketStats",
+          "docs": [
+            "required once the market has them, markets created before candles were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "orderId",
+          "type": "u64"
+        }
+      ],
+      "returns": {
+        "defined": {
+          "name": "trade"
+        }
+      }
+    },
+    {
+      "name": "takePeggedOrder",
+      "docs": [
+        "* Take `amount` base of pegged order as taker at the current oracle price, failing when\n     * it is worse than a non-zero `limit_price` for the taker, returns the fill"
+      ],
+      "discriminator": [
+        44,
+        55,
+        90,
+        131,
+        32,
+        12,
+        92,
+        201
+      ],
+      "accounts": [
+        {
+          "name": "taker",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "maker"
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          },
+          "relations": [
+            "makerOpenOrders",
+            "takerOpenOrders",
+            "peggedBook",
+            "tradeHistory",
+            "marketStats"
+          ]
+        },
+        {
+          "name": "makerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "takerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "baseMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "quoteMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "makerBaseTokenAccount",
+          "docs": [
+            "maker proceeds always go to its ATA, the taker can't pick the destination"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "maker"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "baseMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "makerQuoteTokenAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "maker"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "quoteMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "takerBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "takerQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "baseVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "baseMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "quoteVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "quoteMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "peggedBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  101,
+                  103,
+                  103,
+                  101,
+                  100,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tradeHistory",
+          "docs": [
+            "required once the market has one, markets created before trades were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  97,
+                  100,
+                  101,
+                  45,
+                  104,
+                  105,
+                  115,
+                  116,
+                  111,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketStats",
+          "docs": [
+            "required once the market has them, markets created before candles were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "oracle"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "orderId",
+          "type": "u64"
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "limitPrice",
+          "type": "u64"
+        }
+      ],
+      "returns": {
+        "defined": {
+          "name": "trade"
+        }
+      }
+    },
+    {
+      "name": "takeSellOrder",
+      "docs": [
+        "Take sell order as taker, returns the fill"
+      ],
+      "discriminator": [
+        168,
+        69,
+        47,
+        247,
+        196,
+        211,
+        61,
+        120
+      ],
+      "accounts": [
+        {
+          "name": "taker",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "maker"
+        },
+        {
+          "name": "market",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          },
+          "relations": [
+            "makerOpenOrders",
+            "takerOpenOrders",
+            "tradeHistory",
+            "marketStats"
+          ]
+        },
+        {
+          "name": "makerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "takerOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "baseMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "quoteMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "makerQuoteTokenAccount",
+          "docs": [
+            "maker proceeds always go to its ATA, the taker can't pick the destination"
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "maker"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "quoteMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "takerBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "takerQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "baseVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "baseMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "asksBook",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  97,
+                  115,
+                  107,
+                  45,
+                  98,
+                  111,
+                  111,
+                  107
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tradeHistory",
+          "docs": [
+            "required once the market has one, markets created before trades were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  97,
+                  100,
+                  101,
+                  45,
+                  104,
+                  105,
+                  115,
+                  116,
+                  111,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketStats",
+          "docs": [
+            "required once the market has them, markets created before candles were recorded have none"
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  45,
+                  115,
+                  116,
+                  97,
+                  116,
+                  115
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "market"
+              }
+            ]
+          }
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "orderId",
+          "type": "u64"
+        }
+      ],
+      "returns": {
+        "defined": {
+          "name": "trade"
+        }
+      }
+    },
+    {
+      "name": "transferAdmin",
+      "docs": [
+        "Admin can transfer admin authority"
+      ],
+      "discriminator": [
+        42,
+        242,
+        66,
+        106,
+        228,
+        10,
+        111,
+        156
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "globalPool"
+          ]
+        },
+        {
+          "name": "globalPool",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  108,
+                  111,
+                  98,
+                  97,
+                  108,
+                  45,
+                  97,
+                  117,
+                  116,
+                  104,
+                  111,
+                  114,
+                  105,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "newAdmin",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "withdraw",
+      "docs": [
+        "Withdraw open orders free balance to owner token accounts, delegates can't withdraw"
+      ],
+      "discriminator": [
+        183,
+        18,
+        70,
+        156,
+        148,
+        109,
+        161,
+        34
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "open orders owner, delegates trade but never withdraw"
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "user"
+        },
+        {
+          "name": "market",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seed"
+              }
+            ]
+          },
+          "relations": [
+            "userOpenOrders"
+          ]
+        },
+        {
+          "name": "userOpenOrders",
+          "writable": true
+        },
+        {
+          "name": "baseMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "quoteMint",
+          "relations": [
+            "market"
+          ]
+        },
+        {
+          "name": "userBaseTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "userQuoteTokenAccount",
+          "writable": true
+        },
+        {
+          "name": "baseVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "baseMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "quoteVaultAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "market"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "quoteMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "rent",
+          "address": "SysvarRent111111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seed",
+          "type": "u64"
+        },
+        {
+          "name": "baseAmount",
+          "type": "u64"
+        },
+        {
+          "name": "quoteAmount",
+          "type": "u64"
+        }
+      ]
+    }
+  ],
+  "accounts": [
+    {
+      "name": "book",
+      "discriminator": [
+        121,
+        34,
         121,
         35,
         91,
         62,
-        85,
-        222
+        85,
+        222
+      ]
+    },
+    {
+      "name": "globalPool",
+      "discriminator": [
+        162,
+        244,
+        124,
+        37,
+        148,
+        94,
+        28,
+        50
+      ]
+    },
+    {
+      "name": "market",
+      "discriminator": [
+        219,
+        190,
+        213,
+        55,
+        0,
+        227,
+        198,
+        154
+      ]
+    },
+    {
+      "name": "marketStats",
+      "discriminator": [
+        240,
+        45,
+        182,
+        233,
+        92,
+        118,
+        209,
+        83
+      ]
+    },
+    {
+      "name": "peggedBook",
+      "discriminator": [
+        116,
+        242,
+        186,
+        62,
+        221,
+        91,
+        31,
+        129
+      ]
+    },
+    {
+      "name": "tradeHistory",
+      "discriminator": [
+        190,
+        117,
+        218,
+        114,
+        66,
+        112,
+        56,
+        41
       ]
     },
     {
-      "name": "globalPool",
-      "discriminator": [
-        162,
-        244,
-        124,
-        37,
-        148,
-        94,
-        28,
-        50
-      ]
+      "name": "triggerBook",
+      "discriminator": [
+        111,
+        187,
+        236,
+        215,
+        51,
+        133,
+        44,
+        220
+      ]
+    },
+    {
+      "name": "userMarketOrders",
+      "discriminator": [
+        177,
+        198,
+        190,
+        183,
+        1,
+        137,
+        172,
+        178
+      ]
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "invalidAdmin",
+      "msg": "Admin address dismatch"
+    },
+    {
+      "code": 6001,
+      "name": "invalidInputNameLength",
+      "msg": "Name lenght above limit"
+    },
+    {
+      "code": 6002,
+      "name": "invalidCloseMarketAdmin",
+      "msg": "The signer of this transaction is not this market's authority or admin."
+    },
+    {
+      "code": 6003,
+      "name": "nonEmptyMarket",
+      "msg": "Cannot close a non-empty market"
+    },
+    {
+      "code": 6004,
+      "name": "invalidAccountOwner",
+      "msg": "Account owner does not match with passed address"
+    },
+    {
+      "code": 6005,
+      "name": "openOrdersFull",
+      "msg": "No free order index in user open orders account"
+    },
+    {
+      "code": 6006,
+      "name": "insufficientDepositBalance",
+      "msg": "Deposit token account balance insufficient"
+    },
+    {
+      "code": 6007,
+      "name": "insufficientWithdrawBalance",
+      "msg": "Market token vault balance insufficient"
+    },
+    {
+      "code": 6008,
+      "name": "orderNotFound",
+      "msg": "Not found order id from market order book"
+    },
+    {
+      "code": 6009,
+      "name": "incorrectMakerAddress",
+      "msg": "Order maker address not matched with passed maker"
+    },
+    {
+      "code": 6010,
+      "name": "partialOrderAmountExceed",
+      "msg": "Partial take order amount exceed order quantity"
+    },
+    {
+      "code": 6011,
+      "name": "invalidOrderLimit",
+      "msg": "Market order limit is zero or above global limit"
+    },
+    {
+      "code": 6012,
+      "name": "invalidMarketAuthority",
+      "msg": "The signer of this transaction is not this market's authority"
+    },
+    {
+      "code": 6013,
+      "name": "nonEmptyOpenOrders",
+      "msg": "Cannot close open orders account with resting orders or balances"
+    },
+    {
+      "code": 6014,
+      "name": "invalidDelegate",
+      "msg": "Signer is neither open orders owner nor its delegate"
+    },
+    {
+      "code": 6015,
+      "name": "insufficientFreeBalance",
+      "msg": "Open orders free balance insufficient"
+    },
+    {
+      "code": 6016,
+      "name": "mathOverflow",
+      "msg": "Math operation overflow"
+    },
+    {
+      "code": 6017,
+      "name": "invalidAuditAccount",
+      "msg": "Audit account is not a unique open orders account of this market"
+    },
+    {
+      "code": 6018,
+      "name": "vaultInsolvent",
+      "msg": "Market vault balance below resting orders and free balances"
+    },
+    {
+      "code": 6019,
+      "name": "zeroAmount",
+      "msg": "Amount or price must be greater than zero"
+    },
+    {
+      "code": 6020,
+      "name": "priceOverflow",
+      "msg": "Price conversion result does not fit in u64"
+    },
+    {
+      "code": 6021,
+      "name": "zeroFill",
+      "msg": "Fill is worth less than one atomic unit of the paid token"
+    },
+    {
+      "code": 6022,
+      "name": "bookMigrationRequired",
+      "msg": "Market books use an outdated format, migrate them first"
+    },
+    {
+      "code": 6023,
+      "name": "bookAlreadyMigrated",
+      "msg": "Market books already use the current format"
+    },
+    {
+      "code": 6024,
+      "name": "invalidMigrationAccount",
+      "msg": "Migration account is not a unique open orders account of a resting bid maker"
+    },
+    {
+      "code": 6025,
+      "name": "accountAlreadyMigrated",
+      "msg": "Account already uses the current layout version"
+    },
+    {
+      "code": 6026,
+      "name": "invalidCandleIntervals",
+      "msg": "Candle intervals must be positive, increasing and at most MAX_CANDLE_SERIES"
+    },
+    {
+      "code": 6027,
+      "name": "invalidObservationWindow",
+      "msg": "TWAP end observation must be later than its start"
+    },
+    {
+      "code": 6028,
+      "name": "invalidOracleAccount",
+      "msg": "Oracle account is not the price source of this market"
+    },
+    {
+      "code": 6029,
+      "name": "staleOraclePrice",
+      "msg": "Oracle price is not trading, too old or lacks TWAP history"
+    },
+    {
+      "code": 6030,
+      "name": "invalidPriceBand",
+      "msg": "Price band must be between 1 and MAX_PRICE_BAND_BPS basis points"
+    },
+    {
+      "code": 6031,
+      "name": "priceOutsideBand",
+      "msg": "Order price is outside the oracle price band"
+    },
+    {
+      "code": 6032,
+      "name": "invalidPeggedPrice",
+      "msg": "Pegged order price is not positive at the current oracle price"
+    },
+    {
+      "code": 6033,
+      "name": "peggedLimitRequired",
+      "msg": "Pegged orders and their takes need a non-zero limit price"
+    },
+    {
+      "code": 6034,
+      "name": "peggedPriceBeyondLimit",
+      "msg": "Pegged order price is worse than the taker limit"
+    },
+    {
+      "code": 6035,
+      "name": "triggerLimitRequired",
+      "msg": "Limit triggers and market buy triggers need a limit price"
+    },
+    {
+      "code": 6036,
+      "name": "triggerNotReached",
+      "msg": "Trigger price has not been crossed"
+    },
+    {
+      "code": 6037,
+      "name": "invalidTriggerMakerAccount",
+      "msg": "Trigger fill account is not the open orders or ATA of the resting maker"
+    },
+    {
+      "code": 6038,
+      "name": "bookGrowthExceeded",
+      "msg": "Book can grow by at most MAX_BOOK_GROWTH orders per resize"
+    },
+    {
+      "code": 6039,
+      "name": "tradeHistoryRequired",
+      "msg": "Trade history account of the market is required"
+    },
+    {
+      "code": 6040,
+      "name": "oracleConfidenceTooWide",
+      "msg": "Oracle confidence interval is wider than MAX_ORACLE_CONFIDENCE_BPS of its price"
+    },
+    {
+      "code": 6041,
+      "name": "ownerBookOrdersFull",
+      "msg": "Owner already rests the most pegged or trigger orders allowed per user"
+    },
+    {
+      "code": 6042,
+      "name": "orderValueTooSmall",
+      "msg": "Pegged or trigger order is worth less than its minimum quote value"
+    },
+    {
+      "code": 6043,
+      "name": "marketStatsRequired",
+      "msg": "Market stats account of the market is required"
+    },
+    {
+      "code": 6044,
+      "name": "peggedBookRequired",
+      "msg": "Pegged book of the market is required"
+    },
+    {
+      "code": 6045,
+      "name": "triggerBookRequired",
+      "msg": "Trigger book of the market is required"
+    },
+    {
+      "code": 6046,
+      "name": "invalidOrderMinimum",
+      "msg": "Minimum order and trigger trade values must be positive"
+    },
+    {
+      "code": 6047,
+      "name": "nonEmptyMarketVault",
+      "msg": "Cannot close a market whose vaults still hold tokens"
+    }
+  ],
+  "types": [
+    {
+      "name": "bestPrices",
+      "docs": [
+        "Top of the book, `None` for an empty side"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "bestBid",
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "priceLevel"
+                }
+              }
+            }
+          },
+          {
+            "name": "bestAsk",
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "priceLevel"
+                }
+              }
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "book",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "side",
+            "type": {
+              "defined": {
+                "name": "side"
+              }
+            }
+          },
+          {
+            "name": "market",
+            "type": "pubkey"
+          },
+          {
+            "name": "ordersCount",
+            "type": "u64"
+          },
+          {
+            "name": "orders",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "openedOrder"
+                }
+              }
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "candle",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "openTime",
+            "docs": [
+              "Start of the candle interval, zero for a slot never traded in"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "open",
+            "type": "u64"
+          },
+          {
+            "name": "high",
+            "type": "u64"
+          },
+          {
+            "name": "low",
+            "type": "u64"
+          },
+          {
+            "name": "close",
+            "type": "u64"
+          },
+          {
+            "name": "baseVolume",
+            "type": "u64"
+          },
+          {
+            "name": "quoteVolume",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "candleSeries",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "interval",
+            "docs": [
+              "Candle length in seconds"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "candles",
+            "docs": [
+              "Ring buffer indexed by `open_time / interval % CANDLES_PER_SERIES`"
+            ],
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "candle"
+                }
+              }
+            }
+          }
+        ]
+      }
     },
     {
-      "name": "market",
-      "discriminator": [
-        219,
-        190,
-        213,
-        55,
-        0,
-        227,
-        198,
-        154
-      ]
+      "name": "globalPool",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "admin",
+            "type": "pubkey"
+          },
+          {
+            "name": "maxOrdersPerUser",
+            "type": "u64"
+          },
+          {
+            "name": "maxOrdersPerBook",
+            "type": "u64"
+          },
+          {
+            "name": "totalMarketCount",
+            "type": "u64"
+          },
+          {
+            "name": "marketSeqNum",
+            "type": "u64"
+          },
+          {
+            "name": "extra",
+            "type": "u128"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                128
+              ]
+            }
+          }
+        ]
+      }
     },
     {
-      "name": "userMarketOrders",
-      "discriminator": [
-        177,
-        198,
-        190,
-        183,
-        1,
-        137,
-        172,
-        178
-      ]
-    }
-  ],
-  "errors": [
-    {
-      "code": 6000,
-      "name": "invalidAdmin",
-      "msg": "Admin address dismatch"
+      "name": "market",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "seed",
+            "type": "u64"
+          },
+          {
+            "name": "name",
+            "type": {
+              "array": [
+                "u8",
+                16
+              ]
+            }
+          },
+          {
+            "name": "marketAuthority",
+            "type": "pubkey"
+          },
+          {
+            "name": "baseMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "quoteMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "baseDecimal",
+            "type": "u8"
+          },
+          {
+            "name": "quoteDecimal",
+            "type": "u8"
+          },
+          {
+            "name": "bids",
+            "type": "pubkey"
+          },
+          {
+            "name": "asks",
+            "type": "pubkey"
+          },
+          {
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "baseTotalVolume",
+            "type": "u64"
+          },
+          {
+            "name": "quoteTotalVolume",
+            "type": "u64"
+          },
+          {
+            "name": "orderSeqNum",
+            "type": "u64"
+          },
+          {
+            "name": "bookVersion",
+            "docs": [
+              "Format of `bids` and `asks`, carved from the reserved bytes so legacy markets read 0"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "extra",
+            "type": {
+              "array": [
+                "u8",
+                15
+              ]
+            }
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "maxOrdersPerUser",
+            "docs": [
+              "Order limits of this market, carved from the reserved bytes and backfilled by `migrate_market`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "maxOrdersPerBook",
+            "type": "u64"
+          },
+          {
+            "name": "oracleKind",
+            "docs": [
+              "Price source bounding order prices, carved from the reserved bytes so legacy markets have none"
+            ],
+            "type": {
+              "defined": {
+                "name": "oracleKind"
+              }
+            }
+          },
+          {
+            "name": "oracle",
+            "type": "pubkey"
+          },
+          {
+            "name": "priceBandBps",
+            "docs": [
+              "Orders must rest within this many basis points of the oracle price"
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "hasTradeHistory",
+            "docs": [
+              "Trade history account was created, fills must record in it, carved from the reserved bytes"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "hasMarketStats",
+            "docs": [
+              "Market stats account was created, fills must record in it, carved from the reserved bytes"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "hasPeggedBook",
+            "docs": [
+              "Pegged book was created, close and audit must count it, carved from the reserved bytes"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "hasTriggerBook",
+            "docs": [
+              "Trigger book was created, close and audit must count it, carved from the reserved bytes"
+            ],
+            "type": "bool"
+          },
+          {
+            "name": "minOrderValue",
+            "docs": [
+              "Smallest quote value of a pegged order at its placement price or of a trigger order at",
+              "its limit, in quote atomic units"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "minTriggerTradeValue",
+            "docs": [
+              "Smallest quote value of a trade moving last price triggers, in quote atomic units"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                57
+              ]
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6001,
-      "name": "invalidInputNameLength",
-      "msg": "Name lenght above limit"
+      "name": "marketAudit",
+      "docs": [
+        "Funds the market vaults owe to users against what they hold"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "baseLocked",
+            "docs": [
+              "Base quantity of resting asks, pegged and trigger asks included"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "quoteLocked",
+            "docs": [
+              "Quote locked by resting bids, pegged and trigger bids at their limit price included"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "baseFree",
+            "docs": [
+              "Base free balance of the passed open orders accounts"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "quoteFree",
+            "docs": [
+              "Quote free balance of the passed open orders accounts"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "baseVault",
+            "type": "u64"
+          },
+          {
+            "name": "quoteVault",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6002,
-      "name": "invalidCloseMarketAdmin",
-      "msg": "The signer of this transaction is not this market's authority or admin."
+      "name": "marketOrderQuote",
+      "docs": [
+        "Amounts a market order would trade taking resting orders one by one"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "baseAmount",
+            "docs": [
+              "Base the book covers, below the requested amount on a thin book"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "quoteAmount",
+            "docs": [
+              "Quote the taker receives selling to bids or pays buying from asks"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "ordersFilled",
+            "type": "u64"
+          },
+          {
+            "name": "worstPrice",
+            "docs": [
+              "Price of the last order filled, zero when nothing fills"
+            ],
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6003,
-      "name": "nonEmptyMarket",
-      "msg": "Cannot close a non-empty market"
+      "name": "marketStats",
+      "docs": [
+        "Last trade price, rolling day volume and OHLCV candles of a market, updated on each fill"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "market",
+            "type": "pubkey"
+          },
+          {
+            "name": "lastPrice",
+            "type": "u64"
+          },
+          {
+            "name": "lastTradeAt",
+            "type": "i64"
+          },
+          {
+            "name": "volumeBuckets",
+            "docs": [
+              "Hourly volume ring buffer of the last day"
+            ],
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "volumeBucket"
+                }
+              }
+            }
+          },
+          {
+            "name": "candleSeries",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "candleSeries"
+                }
+              }
+            }
+          },
+          {
+            "name": "priceCumulative",
+            "docs": [
+              "Sum of each trade price times the seconds it stood, wraps on overflow"
+            ],
+            "type": "u128"
+          }
+        ]
+      }
     },
     {
-      "code": 6004,
-      "name": "invalidAccountOwner",
-      "msg": "Account owner does not match with passed address"
+      "name": "openedOrder",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "orderId",
+            "type": "u64"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "price",
+            "type": "u64"
+          },
+          {
+            "name": "quantity",
+            "type": "u64"
+          },
+          {
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "accountIndex",
+            "type": "u8"
+          }
+        ]
+      }
     },
     {
-      "code": 6005,
-      "name": "openOrdersFull",
-      "msg": "No free order index in user open orders account"
+      "name": "oracleKind",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "none"
+          },
+          {
+            "name": "pyth"
+          },
+          {
+            "name": "raydiumClmm"
+          }
+        ]
+      }
     },
     {
-      "code": 6006,
-      "name": "insufficientDepositBalance",
-      "msg": "Deposit token account balance insufficient"
+      "name": "peggedBook",
+      "docs": [
+        "Oracle pegged orders of a market, both sides in placement order"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "market",
+            "type": "pubkey"
+          },
+          {
+            "name": "orders",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "peggedOrder"
+                }
+              }
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6007,
-      "name": "insufficientWithdrawBalance",
-      "msg": "Market token vault balance insufficient"
+      "name": "peggedOrder",
+      "docs": [
+        "Resting order priced from the market oracle when it is taken"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "orderId",
+            "type": "u64"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "accountIndex",
+            "type": "u8"
+          },
+          {
+            "name": "side",
+            "type": {
+              "defined": {
+                "name": "side"
+              }
+            }
+          },
+          {
+            "name": "priceOffset",
+            "docs": [
+              "Added to the oracle price, negative offsets rest below it"
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "limitPrice",
+            "docs": [
+              "Highest bid or lowest ask price, bids lock their quote value at it"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "quantity",
+            "type": "u64"
+          },
+          {
+            "name": "createdAt",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6008,
-      "name": "orderNotFound",
-      "msg": "Not found order id from market order book"
+      "name": "priceLevel",
+      "docs": [
+        "Price, total base quantity and order count of a book price level"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "price",
+            "type": "u64"
+          },
+          {
+            "name": "quantity",
+            "type": "u64"
+          },
+          {
+            "name": "ordersCount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6009,
-      "name": "incorrectMakerAddress",
-      "msg": "Order maker address not matched with passed maker"
+      "name": "side",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "bid"
+          },
+          {
+            "name": "ask"
+          }
+        ]
+      }
     },
     {
-      "code": 6010,
-      "name": "partialOrderAmountExceed",
-      "msg": "Partial take order amount exceed order quantity"
-    }
-  ],
-  "types": [
-    {
-      "name": "book",
+      "name": "trade",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "side",
+            "name": "seqNum",
+            "docs": [
+              "Position in the market trade sequence, orders trades across ring buffer wraps"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "orderId",
+            "type": "u64"
+          },
+          {
+            "name": "price",
+            "type": "u64"
+          },
+          {
+            "name": "baseAmount",
+            "type": "u64"
+          },
+          {
+            "name": "quoteAmount",
+            "type": "u64"
+          },
+          {
+            "name": "takerSide",
             "type": {
               "defined": {
                 "name": "side"
               }
             }
           },
+          {
+            "name": "maker",
+            "type": "pubkey"
+          },
+          {
+            "name": "taker",
+            "type": "pubkey"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "tradeHistory",
+      "docs": [
+        "Ring buffer of the last `TRADE_HISTORY_CAPACITY` trades of a market"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
             "name": "market",
             "type": "pubkey"
           },
           {
-            "name": "ordersCount",
+            "name": "tradeSeqNum",
+            "docs": [
+              "Trades recorded since creation, the next trade overwrites `seq_num % capacity`"
+            ],
             "type": "u64"
           },
           {
-            "name": "orders",
+            "name": "trades",
             "type": {
               "vec": {
                 "defined": {
-                  "name": "openedOrder"
+                  "name": "trade"
                 }
               }
             }
@@ -4274,144 +7572,215 @@ export type HybridDex = {
       }
     },
     {
-      "name": "globalPool",
+      "name": "triggerBook",
+      "docs": [
+        "Trigger orders of a market waiting for their price, both sides in placement order,",
+        "the account holds the keeper bounties on top of its rent"
+      ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "admin",
+            "name": "market",
             "type": "pubkey"
           },
           {
-            "name": "maxOrdersPerUser",
-            "type": "u64"
-          },
-          {
-            "name": "maxOrdersPerBook",
-            "type": "u64"
-          },
-          {
-            "name": "totalMarketCount",
-            "type": "u64"
-          },
+            "name": "orders",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "triggerOrder"
+                }
+              }
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "triggerCondition",
+      "docs": [
+        "Direction of the watched price move"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
           {
-            "name": "marketSeqNum",
-            "type": "u64"
+            "name": "stop"
           },
           {
-            "name": "extra",
-            "type": "u128"
+            "name": "takeProfit"
           }
         ]
       }
     },
     {
-      "name": "market",
+      "name": "triggerOrder",
+      "docs": [
+        "Conditional order holding its funds until a keeper executes it"
+      ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "seed",
+            "name": "orderId",
             "type": "u64"
           },
           {
-            "name": "name",
-            "type": {
-              "array": [
-                "u8",
-                16
-              ]
-            }
-          },
-          {
-            "name": "marketAuthority",
-            "type": "pubkey"
-          },
-          {
-            "name": "baseMint",
+            "name": "owner",
             "type": "pubkey"
           },
           {
-            "name": "quoteMint",
-            "type": "pubkey"
+            "name": "accountIndex",
+            "type": "u8"
           },
           {
-            "name": "baseDecimal",
-            "type": "u8"
+            "name": "side",
+            "type": {
+              "defined": {
+                "name": "side"
+              }
+            }
           },
           {
-            "name": "quoteDecimal",
-            "type": "u8"
+            "name": "source",
+            "type": {
+              "defined": {
+                "name": "triggerSource"
+              }
+            }
           },
           {
-            "name": "bids",
-            "type": "pubkey"
+            "name": "condition",
+            "type": {
+              "defined": {
+                "name": "triggerCondition"
+              }
+            }
           },
           {
-            "name": "asks",
-            "type": "pubkey"
+            "name": "orderType",
+            "type": {
+              "defined": {
+                "name": "triggerOrderType"
+              }
+            }
           },
           {
-            "name": "createdAt",
-            "type": "i64"
+            "name": "triggerPrice",
+            "type": "u64"
           },
           {
-            "name": "baseTotalVolume",
+            "name": "limitPrice",
+            "docs": [
+              "Price of the resulting limit order or worst fill price of a market order,",
+              "bids lock their quote value at it, zero leaves a market ask uncapped"
+            ],
             "type": "u64"
           },
           {
-            "name": "quoteTotalVolume",
+            "name": "quantity",
             "type": "u64"
           },
           {
-            "name": "orderSeqNum",
+            "name": "bounty",
+            "docs": [
+              "Lamports the executing keeper receives"
+            ],
             "type": "u64"
           },
           {
-            "name": "extra",
-            "type": "u128"
+            "name": "createdAt",
+            "type": "i64"
           }
         ]
       }
     },
     {
-      "name": "openedOrder",
+      "name": "triggerOrderParams",
+      "docs": [
+        "Trigger order terms chosen by its owner"
+      ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "orderId",
-            "type": "u64"
+            "name": "side",
+            "type": {
+              "defined": {
+                "name": "side"
+              }
+            }
           },
           {
-            "name": "owner",
-            "type": "pubkey"
+            "name": "source",
+            "type": {
+              "defined": {
+                "name": "triggerSource"
+              }
+            }
           },
           {
-            "name": "price",
+            "name": "condition",
+            "type": {
+              "defined": {
+                "name": "triggerCondition"
+              }
+            }
+          },
+          {
+            "name": "orderType",
+            "type": {
+              "defined": {
+                "name": "triggerOrderType"
+              }
+            }
+          },
+          {
+            "name": "triggerPrice",
+            "type": "u64"
+          },
+          {
+            "name": "limitPrice",
             "type": "u64"
           },
           {
             "name": "quantity",
             "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "triggerOrderType",
+      "docs": [
+        "Order a trigger turns into once it fires"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "limit"
           },
           {
-            "name": "createdAt",
-            "type": "i64"
+            "name": "market"
           }
         ]
       }
     },
     {
-      "name": "side",
+      "name": "triggerSource",
+      "docs": [
+        "Price a trigger order watches"
+      ],
       "type": {
         "kind": "enum",
         "variants": [
           {
-            "name": "bid"
+            "name": "lastPrice"
           },
           {
-            "name": "ask"
+            "name": "oracle"
           }
         ]
       }
@@ -4452,9 +7821,114 @@ export type HybridDex = {
           {
             "name": "extra",
             "type": "u128"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "delegate",
+            "docs": [
+              "Trading authority besides the owner, carved from the reserved bytes so legacy accounts have none"
+            ],
+            "type": "pubkey"
+          },
+          {
+            "name": "accountIndex",
+            "docs": [
+              "Sub account of the owner in this market, legacy accounts are index 0"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "baseFree",
+            "docs": [
+              "Deposited funds not locked by orders, withdrawable by the owner"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "quoteFree",
+            "type": "u64"
+          },
+          {
+            "name": "reserved",
+            "type": {
+              "array": [
+                "u8",
+                79
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "userPosition",
+      "docs": [
+        "Balances of an open orders account, locked amounts back its resting orders"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "accountIndex",
+            "type": "u8"
+          },
+          {
+            "name": "openedOrdersCount",
+            "type": "u64"
+          },
+          {
+            "name": "baseLocked",
+            "type": "u64"
+          },
+          {
+            "name": "quoteLocked",
+            "type": "u64"
+          },
+          {
+            "name": "baseFree",
+            "type": "u64"
+          },
+          {
+            "name": "quoteFree",
+            "type": "u64"
+          },
+          {
+            "name": "baseTotalVolume",
+            "type": "u64"
+          },
+          {
+            "name": "quoteTotalVolume",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "volumeBucket",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "start",
+            "type": "i64"
+          },
+          {
+            "name": "baseVolume",
+            "type": "u64"
+          },
+          {
+            "name": "quoteVolume",
+            "type": "u64"
           }
         ]
       }
     }
   ]
-};
+};
\ No newline at end of file
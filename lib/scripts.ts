@@ -1,4 +1,5 @@
 import * as anchor from '@coral-xyz/anchor';
+import { getAssociatedTokenAddressSync } from '@solana/spl-token';
 import { PublicKey, Transaction } from '@solana/web3.js';
 
 import {
@@ -9,7 +10,29 @@ import {
   USER_MARKET_ORDER_SEED,
 } from './constant';
 import { HybridDex } from './hybrid_dex';
-import { Market, MARKET_SIZE, Side } from './types';
+import { Market, Side } from './types';
+
+/**
+ * Open orders PDA of user sub account, index 0 keeps the legacy address
+ */
+export const getUserMarketOrdersKey = (
+  market: PublicKey,
+  user: PublicKey,
+  accountIndex: number,
+  programId: PublicKey
+) => {
+  const seeds = [
+    Buffer.from(USER_MARKET_ORDER_SEED),
+    market.toBuffer(),
+    user.toBuffer(),
+  ];
+  if (accountIndex !== 0) {
+    seeds.push(Buffer.from([accountIndex]));
+  }
+
+  const [userOrders] = PublicKey.findProgramAddressSync(seeds, programId);
+  return userOrders;
+};
 
 export const createInitializeTx = async (
   admin: PublicKey,
@@ -94,13 +117,15 @@ export const createChangeConfigTx = async (
 };
 
 /**
- * Create market
+ * Create market with its own order limits, bounded by global config
  */
 export const createMarketTx = async (
   authority: PublicKey,
   baseMint: PublicKey,
   quoteMint: PublicKey,
   name: string,
+  maxOrdersPerUser: number,
+  maxOrdersPerBook: number,
   program: anchor.Program<HybridDex>
 ) => {
   const { data } = await getGlobalState(program);
@@ -114,7 +139,11 @@ export const createMarketTx = async (
   const tx = new Transaction();
 
   const txId = await program.methods
-    .createMarket(name)
+    .createMarket(
+      name,
+      new anchor.BN(maxOrdersPerUser),
+      new anchor.BN(maxOrdersPerBook)
+    )
     .accounts({
       authority,
       baseMint,
@@ -142,6 +171,8 @@ export const closeMarketTx = async (
     .closeMarket(new anchor.BN(data.seed))
     .accounts({
       authority,
+      baseVaultAccount: getAssociatedTokenAddressSync(data.baseMint, market, true),
+      quoteVaultAccount: getAssociatedTokenAddressSync(data.quoteMint, market, true),
     })
     .transaction();
 
@@ -156,10 +187,11 @@ export const createOpenOrdersTx = async (
   const tx = new Transaction();
 
   const txId = await program.methods
-    .createOpenOrders()
-    .accounts({
+    .createOpenOrders(0)
+    .accountsPartial({
       user,
       market,
+      userOpenOrders: getUserMarketOrdersKey(market, user, 0, program.programId),
     })
     .transaction();
 
@@ -176,14 +208,30 @@ export const placeOrderTx = async (
   quantity: number, // should have quote / base decimal
   program: anchor.Program<HybridDex>
 ) => {
+  const { data } = await getMarketState(market, program);
+  const userOpenOrders = getUserMarketOrdersKey(
+    market,
+    maker,
+    0,
+    program.programId
+  );
+  // markets without price band take no oracle account
+  const oracle = data.oracle.equals(PublicKey.default) ? null : data.oracle;
+
   if (side === Side.Bid) {
     const tx = new Transaction();
     const txId = await program.methods
       .placeBuyOrder(new anchor.BN(price), new anchor.BN(quantity))
-      .accounts({
+      .accountsPartial({
+        authority: maker,
         maker,
-        // @ts-ignore
         market,
+        userOpenOrders,
+        userQuoteTokenAccount: getAssociatedTokenAddressSync(
+          data.quoteMint,
+          maker
+        ),
+        oracle,
       })
       .transaction();
 
@@ -194,10 +242,16 @@ export const placeOrderTx = async (
     const tx = new Transaction();
     const txId = await program.methods
       .placeSellOrder(new anchor.BN(price), new anchor.BN(quantity))
-      .accounts({
+      .accountsPartial({
+        authority: maker,
         maker,
-        // @ts-ignore
         market,
+        userOpenOrders,
+        userBaseTokenAccount: getAssociatedTokenAddressSync(
+          data.baseMint,
+          maker
+        ),
+        oracle,
       })
       .transaction();
 
@@ -215,13 +269,19 @@ export const cancelOrderTx = async (
   program: anchor.Program<HybridDex>
 ) => {
   const { data } = await getMarketState(market, program);
+  const userOpenOrders = getUserMarketOrdersKey(
+    market,
+    maker,
+    0,
+    program.programId
+  );
 
   if (side === Side.Bid) {
     const tx = new Transaction();
 
     const txId = await program.methods
       .cancelBuyOrder(new anchor.BN(data.seed), new anchor.BN(orderId))
-      .accounts({ maker })
+      .accountsPartial({ authority: maker, maker, userOpenOrders })
       .transaction();
 
     tx.add(txId);
@@ -232,7 +292,7 @@ export const cancelOrderTx = async (
 
     const txId = await program.methods
       .cancelSellOrder(new anchor.BN(data.seed), new anchor.BN(orderId))
-      .accounts({ maker })
+      .accountsPartial({ authority: maker, maker, userOpenOrders })
       .transaction();
 
     tx.add(txId);
@@ -241,6 +301,24 @@ export const cancelOrderTx = async (
   }
 };
 
+/**
+ * Open orders and taker token accounts of a take, both sides use sub account 0
+ */
+const getTakeAccounts = (
+  taker: PublicKey,
+  maker: PublicKey,
+  market: PublicKey,
+  data: { baseMint: PublicKey; quoteMint: PublicKey },
+  program: anchor.Program<HybridDex>
+) => ({
+  taker,
+  maker,
+  makerOpenOrders: getUserMarketOrdersKey(market, maker, 0, program.programId),
+  takerOpenOrders: getUserMarketOrdersKey(market, taker, 0, program.programId),
+  takerBaseTokenAccount: getAssociatedTokenAddressSync(data.baseMint, taker),
+  takerQuoteTokenAccount: getAssociatedTokenAddressSync(data.quoteMint, taker),
+});
+
 export const takeOrderTx = async (
   taker: PublicKey,
   maker: PublicKey,
@@ -250,16 +328,14 @@ export const takeOrderTx = async (
   program: anchor.Program<HybridDex>
 ) => {
   const { data } = await getMarketState(market, program);
+  const takeAccounts = getTakeAccounts(taker, maker, market, data, program);
 
   if (side === Side.Bid) {
     const tx = new Transaction();
 
     const txId = await program.methods
       .takeBuyOrder(new anchor.BN(data.seed), new anchor.BN(orderId))
-      .accounts({
-        taker,
-        maker,
-      })
+      .accountsPartial(takeAccounts)
       .transaction();
 
     tx.add(txId);
@@ -270,10 +346,7 @@ export const takeOrderTx = async (
 
     const txId = await program.methods
       .takeSellOrder(new anchor.BN(data.seed), new anchor.BN(orderId))
-      .accounts({
-        taker,
-        maker,
-      })
+      .accountsPartial(takeAccounts)
       .transaction();
 
     tx.add(txId);
@@ -292,6 +365,7 @@ export const partialTakeOrderTx = async (
   program: anchor.Program<HybridDex>
 ) => {
   const { data } = await getMarketState(market, program);
+  const takeAccounts = getTakeAccounts(taker, maker, market, data, program);
 
   if (side === Side.Bid) {
     const tx = new Transaction();
@@ -302,10 +376,7 @@ export const partialTakeOrderTx = async (
         new anchor.BN(orderId),
         new anchor.BN(amount)
       )
-      .accounts({
-        taker,
-        maker,
-      })
+      .accountsPartial(takeAccounts)
       .transaction();
 
     tx.add(txId);
@@ -320,10 +391,7 @@ export const partialTakeOrderTx = async (
         new anchor.BN(orderId),
         new anchor.BN(amount)
       )
-      .accounts({
-        taker,
-        maker,
-      })
+      .accountsPartial(takeAccounts)
       .transaction();
 
     tx.add(txId);
@@ -380,7 +448,7 @@ export const findAllMarkets = async (
 ) => {
   let filters: anchor.web3.GetProgramAccountsFilter[] = [
     {
-      dataSize: MARKET_SIZE,
+      memcmp: program.coder.accounts.memcmp('market'),
     },
   ];
 
@@ -421,6 +489,8 @@ export const findAllMarkets = async (
     data.baseTotalVolume = data.baseTotalVolume.toNumber();
     data.quoteTotalVolume = data.quoteTotalVolume.toNumber();
     data.orderSeqNum = data.orderSeqNum.toNumber();
+    data.maxOrdersPerUser = data.maxOrdersPerUser.toNumber();
+    data.maxOrdersPerBook = data.maxOrdersPerBook.toNumber();
     return {
       key: marketAcc.pubkey.toBase58(),
       data,
@@ -436,10 +506,7 @@ export const getUserMarketOrdersState = async (
   user: PublicKey,
   program: anchor.Program<HybridDex>
 ) => {
-  const [userOrders] = PublicKey.findProgramAddressSync(
-    [Buffer.from(USER_MARKET_ORDER_SEED), market.toBuffer(), user.toBuffer()],
-    program.programId
-  );
+  const userOrders = getUserMarketOrdersKey(market, user, 0, program.programId);
   try {
     let userOrdersData = await program.account.userMarketOrders.fetch(
       userOrders
@@ -33,10 +33,10 @@ export interface Market {
   baseTotalVolume: anchor.BN;
   quoteTotalVolume: anchor.BN;
   orderSeqNum: anchor.BN;
+  maxOrdersPerUser: anchor.BN;
+  maxOrdersPerBook: anchor.BN;
 }
 
-export const MARKET_SIZE = 8 + 8 + 16 + 32 * 5 + 8 + 8 * 4 + 16;
-
 export enum Side {
   Bid = 0,
   Ask = 1,
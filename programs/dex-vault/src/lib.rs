@@ -0,0 +1,320 @@
+//! Example hybrid-dex integration: a vault whose funds and open orders belong
+//! to a PDA, trading through CPI with token accounts that aren't ATAs.
+//!
+//! The vault authority is a system owned PDA funded with lamports, so it can
+//! pay rent for its open orders account and for maker ATAs created by takes.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use hybrid_dex::{
+    cpi::{
+        accounts::{CreateOpenOrders, PlaceBuyOrder, TakeSellOrder},
+        create_open_orders, place_buy_order, take_sell_order,
+    },
+    program::HybridDex,
+    Trade,
+};
+
+declare_id!("DpivpsNaKDQgQrHzLv4Tce55Tj3hAU8weKcsSkNwKR6W");
+
+pub const VAULT_AUTHORITY_SEED: &str = "vault-authority";
+
+/// Vault authority of `owner`, the hybrid-dex trader of the vault
+pub fn vault_authority_address(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED.as_bytes(), owner.as_ref()], &ID).0
+}
+
+#[program]
+pub mod dex_vault {
+    use super::*;
+
+    /** Create the vault open orders account, paid by the vault authority */
+    pub fn create_vault_open_orders(
+        ctx: Context<CreateVaultOpenOrders>,
+        account_index: u8,
+    ) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let seeds = &[
+            VAULT_AUTHORITY_SEED.as_bytes(),
+            owner.as_ref(),
+            &[ctx.bumps.vault_authority],
+        ];
+        let signers_seeds = &[&seeds[..]];
+
+        let cpi_accounts = CreateOpenOrders {
+            user: ctx.accounts.vault_authority.to_account_info(),
+            market: ctx.accounts.market.to_account_info(),
+            user_open_orders: ctx.accounts.vault_open_orders.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+
+        create_open_orders(
+            CpiContext::new_with_signer(
+                ctx.accounts.hybrid_dex_program.to_account_info(),
+                cpi_accounts,
+                signers_seeds,
+            ),
+            account_index,
+        )
+    }
+
+    /** Bid from the vault quote account, returns the hybrid-dex order id */
+    pub fn place_vault_bid(ctx: Context<PlaceVaultBid>, price: u64, quantity: u64) -> Result<u64> {
+        let owner = ctx.accounts.owner.key();
+        let seeds = &[
+            VAULT_AUTHORITY_SEED.as_bytes(),
+            owner.as_ref(),
+            &[ctx.bumps.vault_authority],
+        ];
+        let signers_seeds = &[&seeds[..]];
+
+        let cpi_accounts = PlaceBuyOrder {
+            authority: ctx.accounts.vault_authority.to_account_info(),
+            maker: ctx.accounts.vault_authority.to_account_info(),
+            market: ctx.accounts.market.to_account_info(),
+            user_open_orders: ctx.accounts.vault_open_orders.to_account_info(),
+            base_mint: ctx.accounts.base_mint.to_account_info(),
+            quote_mint: ctx.accounts.quote_mint.to_account_info(),
+            user_quote_token_account: ctx.accounts.vault_quote_token_account.to_account_info(),
+            quote_vault_account: ctx.accounts.quote_vault_account.to_account_info(),
+            bids_book: ctx.accounts.bids_book.to_account_info(),
+            oracle: ctx
+                .accounts
+                .oracle
+                .as_ref()
+                .map(|oracle| oracle.to_account_info()),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+
+        let order_id = place_buy_order(
+            CpiContext::new_with_signer(
+                ctx.accounts.hybrid_dex_program.to_account_info(),
+                cpi_accounts,
+                signers_seeds,
+            ),
+            price,
+            quantity,
+        )?
+        .get();
+
+        Ok(order_id)
+    }
+
+    /** Take an ask into the vault accounts, failing above `max_quote_amount`, returns the fill */
+    pub fn take_vault_ask(
+        ctx: Context<TakeVaultAsk>,
+        seed: u64,
+        order_id: u64,
+        max_quote_amount: u64,
+    ) -> Result<Trade> {
+        let owner = ctx.accounts.owner.key();
+        let seeds = &[
+            VAULT_AUTHORITY_SEED.as_bytes(),
+            owner.as_ref(),
+            &[ctx.bumps.vault_authority],
+        ];
+        let signers_seeds = &[&seeds[..]];
+
+        let cpi_accounts = TakeSellOrder {
+            taker: ctx.accounts.vault_authority.to_account_info(),
+            maker: ctx.accounts.maker.to_account_info(),
+            market: ctx.accounts.market.to_account_info(),
+            maker_open_orders: ctx.accounts.maker_open_orders.to_account_info(),
+            taker_open_orders: ctx.accounts.vault_open_orders.to_account_info(),
+            base_mint: ctx.accounts.base_mint.to_account_info(),
+            quote_mint: ctx.accounts.quote_mint.to_account_info(),
+            maker_quote_token_account: ctx.accounts.maker_quote_token_account.to_account_info(),
+            taker_base_token_account: ctx.accounts.vault_base_token_account.to_account_info(),
+            taker_quote_token_account: ctx.accounts.vault_quote_token_account.to_account_info(),
+            base_vault_account: ctx.accounts.base_vault_account.to_account_info(),
+            asks_book: ctx.accounts.asks_book.to_account_info(),
+            trade_history: ctx
+                .accounts
+                .trade_history
+                .as_ref()
+                .map(|trade_history| trade_history.to_account_info()),
+            market_stats: ctx
+                .accounts
+                .market_stats
+                .as_ref()
+                .map(|market_stats| market_stats.to_account_info()),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+
+        let trade = take_sell_order(
+            CpiContext::new_with_signer(
+                ctx.accounts.hybrid_dex_program.to_account_info(),
+                cpi_accounts,
+                signers_seeds,
+            ),
+            seed,
+            order_id,
+        )?
+        .get();
+
+        // check fill cost against the vault limit
+        require!(
+            trade.quote_amount <= max_quote_amount,
+            VaultError::SlippageExceeded
+        );
+
+        Ok(trade)
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateVaultOpenOrders<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes(), owner.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: SystemAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: created by hybrid-dex
+    #[account(mut)]
+    pub vault_open_orders: UncheckedAccount<'info>,
+
+    pub hybrid_dex_program: Program<'info, HybridDex>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceVaultBid<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes(), owner.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: SystemAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub vault_open_orders: UncheckedAccount<'info>,
+
+    pub base_mint: Account<'info, Mint>,
+
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_quote_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub quote_vault_account: UncheckedAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub bids_book: UncheckedAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex, markets without price band take none
+    pub oracle: Option<UncheckedAccount<'info>>,
+
+    pub hybrid_dex_program: Program<'info, HybridDex>,
+    /// CHECK: validated by hybrid-dex
+    pub associated_token_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct TakeVaultAsk<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_AUTHORITY_SEED.as_bytes(), owner.key().as_ref()],
+        bump,
+    )]
+    pub vault_authority: SystemAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    pub maker: UncheckedAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub maker_open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub vault_open_orders: UncheckedAccount<'info>,
+
+    pub base_mint: Account<'info, Mint>,
+
+    pub quote_mint: Account<'info, Mint>,
+
+    /// CHECK: maker ATA, created by hybrid-dex on the first fill
+    #[account(mut)]
+    pub maker_quote_token_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_base_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = vault_authority,
+    )]
+    pub vault_quote_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub base_vault_account: UncheckedAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub asks_book: UncheckedAccount<'info>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub trade_history: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: validated by hybrid-dex
+    #[account(mut)]
+    pub market_stats: Option<UncheckedAccount<'info>>,
+
+    pub hybrid_dex_program: Program<'info, HybridDex>,
+    /// CHECK: validated by hybrid-dex
+    pub associated_token_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Fill costs more than the vault limit")]
+    SlippageExceeded,
+}
@@ -0,0 +1,427 @@
+//! Vault PDA trading on hybrid-dex through CPI, both programs run natively
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{entrypoint::ProgramResult, instruction::Instruction, program_pack::Pack},
+    AnchorDeserialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, spl_associated_token_account},
+    token::{spl_token, ID as TOKEN_PROGRAM_ID},
+};
+use dex_vault::{vault_authority_address, VaultError};
+use hybrid_dex::{Side, Trade};
+use hybrid_dex_client::{instructions, MarketKeys};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+};
+
+const UNIT: u64 = 1_000_000;
+const DECIMALS: u8 = 6;
+
+// anchor entry ties account infos to a single lifetime the runtime can't name
+fn process_hybrid_dex(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    hybrid_dex::entry(program_id, accounts, data)
+}
+
+fn process_dex_vault(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    dex_vault::entry(program_id, accounts, data)
+}
+
+struct TestEnv {
+    ctx: ProgramTestContext,
+    admin: Keypair,
+    market: MarketKeys,
+    /// Vault owner and its authority PDA, trading with plain token accounts
+    owner: Keypair,
+    vault: Pubkey,
+    vault_base: Pubkey,
+    vault_quote: Pubkey,
+}
+
+impl TestEnv {
+    /// Market over new mints and a vault funded with `quote_amount`
+    async fn new(quote_amount: u64) -> TestEnv {
+        let mut program_test =
+            ProgramTest::new("hybrid_dex", hybrid_dex::ID, processor!(process_hybrid_dex));
+        program_test.add_program("dex_vault", dex_vault::ID, processor!(process_dex_vault));
+        let ctx = program_test.start_with_context().await;
+        let admin = Keypair::from_bytes(&ctx.payer.to_bytes()).unwrap();
+        let owner = Keypair::new();
+
+        let mut env = TestEnv {
+            ctx,
+            admin,
+            market: MarketKeys::new(0, Pubkey::default(), Pubkey::default()),
+            vault: vault_authority_address(&owner.pubkey()),
+            owner,
+            vault_base: Pubkey::default(),
+            vault_quote: Pubkey::default(),
+        };
+
+        let base_mint = env.create_mint().await;
+        let quote_mint = env.create_mint().await;
+        env.market = MarketKeys::new(0, base_mint, quote_mint);
+
+        let admin = env.admin.pubkey();
+        env.send(
+            &[
+                instructions::initialize(&admin, 8, 16),
+                instructions::create_market(&admin, 0, &base_mint, &quote_mint, "SOL/USDC", 8, 16),
+                system_instruction::transfer(&admin, &env.vault, 1_000_000_000),
+            ],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        env.vault_base = env
+            .create_token_account(&base_mint, &env.vault.clone())
+            .await;
+        env.vault_quote = env
+            .create_token_account(&quote_mint, &env.vault.clone())
+            .await;
+        env.mint_to(&quote_mint, &env.vault_quote.clone(), quote_amount)
+            .await;
+
+        let ix = env.vault_instruction(
+            dex_vault::accounts::CreateVaultOpenOrders {
+                owner: env.owner.pubkey(),
+                vault_authority: env.vault,
+                market: env.market.address,
+                vault_open_orders: env.market.user_market_orders(&env.vault, 0),
+                hybrid_dex_program: hybrid_dex::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            dex_vault::instruction::CreateVaultOpenOrders { account_index: 0 },
+        );
+        let owner = Keypair::from_bytes(&env.owner.to_bytes()).unwrap();
+        env.send(&[ix], &[&owner]).await.unwrap();
+
+        env
+    }
+
+    fn vault_instruction(
+        &self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+    ) -> Instruction {
+        Instruction {
+            program_id: dex_vault::ID,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    /// Send instructions paid by admin, returning the transaction return data
+    async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<u8>, BanksClientError> {
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await.unwrap();
+
+        let mut all_signers = vec![&self.admin];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.admin.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        let processed = self
+            .ctx
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await?;
+        processed.result?;
+
+        Ok(processed
+            .metadata
+            .and_then(|metadata| metadata.return_data)
+            .map(|return_data| return_data.data)
+            .unwrap_or_default())
+    }
+
+    async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let admin = self.admin.pubkey();
+
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &admin,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &admin,
+                    None,
+                    DECIMALS,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+
+        mint.pubkey()
+    }
+
+    /// Keypair token account of `owner`, not its ATA
+    async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let admin = self.admin.pubkey();
+
+        self.send(
+            &[
+                system_instruction::create_account(
+                    &admin,
+                    &account.pubkey(),
+                    rent.minimum_balance(spl_token::state::Account::LEN),
+                    spl_token::state::Account::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_account(
+                    &spl_token::ID,
+                    &account.pubkey(),
+                    mint,
+                    owner,
+                )
+                .unwrap(),
+            ],
+            &[&account],
+        )
+        .await
+        .unwrap();
+
+        account.pubkey()
+    }
+
+    async fn mint_to(&mut self, mint: &Pubkey, account: &Pubkey, amount: u64) {
+        let admin = self.admin.pubkey();
+        self.send(
+            &[
+                spl_token::instruction::mint_to(&spl_token::ID, mint, account, &admin, &[], amount)
+                    .unwrap(),
+            ],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Funded wallet with an open orders account and ATAs holding `amount` of `mint`
+    async fn create_trader(&mut self, mint: &Pubkey, amount: u64) -> Keypair {
+        let trader = Keypair::new();
+        let admin = self.admin.pubkey();
+        let market = self.market;
+
+        let mut ixs = vec![system_instruction::transfer(
+            &admin,
+            &trader.pubkey(),
+            1_000_000_000,
+        )];
+        ixs.extend(instructions::create_user_token_accounts(
+            &admin,
+            &trader.pubkey(),
+            &market,
+        ));
+        ixs.push(instructions::create_open_orders(
+            &trader.pubkey(),
+            &market.address,
+            0,
+        ));
+        self.send(&ixs, &[&trader]).await.unwrap();
+
+        let ata = get_associated_token_address(&trader.pubkey(), mint);
+        self.mint_to(mint, &ata, amount).await;
+
+        trader
+    }
+
+    async fn balance(&mut self, account: &Pubkey) -> u64 {
+        match self.ctx.banks_client.get_account(*account).await.unwrap() {
+            Some(account) => {
+                spl_token::state::Account::unpack(&account.data)
+                    .unwrap()
+                    .amount
+            }
+            None => 0,
+        }
+    }
+
+    fn place_vault_bid(&self, price: u64, quantity: u64) -> Instruction {
+        self.vault_instruction(
+            dex_vault::accounts::PlaceVaultBid {
+                owner: self.owner.pubkey(),
+                vault_authority: self.vault,
+                market: self.market.address,
+                vault_open_orders: self.market.user_market_orders(&self.vault, 0),
+                base_mint: self.market.base_mint,
+                quote_mint: self.market.quote_mint,
+                vault_quote_token_account: self.vault_quote,
+                quote_vault_account: self.market.quote_vault,
+                bids_book: self.market.bids,
+                oracle: self.market.oracle,
+                hybrid_dex_program: hybrid_dex::ID,
+                associated_token_program: associated_token::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            dex_vault::instruction::PlaceVaultBid { price, quantity },
+        )
+    }
+
+    fn take_vault_ask(&self, maker: &Pubkey, order_id: u64, max_quote_amount: u64) -> Instruction {
+        self.vault_instruction(
+            dex_vault::accounts::TakeVaultAsk {
+                owner: self.owner.pubkey(),
+                vault_authority: self.vault,
+                maker: *maker,
+                market: self.market.address,
+                maker_open_orders: self.market.user_market_orders(maker, 0),
+                vault_open_orders: self.market.user_market_orders(&self.vault, 0),
+                base_mint: self.market.base_mint,
+                quote_mint: self.market.quote_mint,
+                maker_quote_token_account: self.market.user_quote_token_account(maker),
+                vault_base_token_account: self.vault_base,
+                vault_quote_token_account: self.vault_quote,
+                base_vault_account: self.market.base_vault,
+                asks_book: self.market.asks,
+                trade_history: self.market.trade_history,
+                market_stats: self.market.stats,
+                hybrid_dex_program: hybrid_dex::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: TOKEN_PROGRAM_ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            dex_vault::instruction::TakeVaultAsk {
+                seed: self.market.seed,
+                order_id,
+                max_quote_amount,
+            },
+        )
+    }
+}
+
+#[tokio::test]
+async fn vault_bid_is_taken() {
+    let mut env = TestEnv::new(10 * UNIT).await;
+    let owner = Keypair::from_bytes(&env.owner.to_bytes()).unwrap();
+
+    // order id comes back through the vault return data
+    let return_data = env
+        .send(&[env.place_vault_bid(2 * UNIT, UNIT)], &[&owner])
+        .await
+        .unwrap();
+    let order_id = u64::try_from_slice(&return_data).unwrap();
+    assert_eq!(order_id, 0);
+    assert_eq!(env.balance(&env.vault_quote.clone()).await, 8 * UNIT);
+
+    let base_mint = env.market.base_mint;
+    let taker = env.create_trader(&base_mint, UNIT).await;
+    let accounts = instructions::TakeAccounts {
+        taker: taker.pubkey(),
+        taker_account_index: 0,
+        maker: env.vault,
+        maker_account_index: 0,
+    };
+    let return_data = env
+        .send(
+            &[instructions::take_buy_order(
+                &accounts,
+                &env.market,
+                order_id,
+            )],
+            &[&taker],
+        )
+        .await
+        .unwrap();
+
+    let trade = Trade::try_from_slice(&return_data).unwrap();
+    assert_eq!(trade.order_id, order_id);
+    assert_eq!(trade.maker, env.vault);
+    assert_eq!(trade.taker_side, Side::Ask);
+    assert_eq!((trade.base_amount, trade.quote_amount), (UNIT, 2 * UNIT));
+
+    // maker proceeds land in the vault ATA created by the take
+    let vault_ata = get_associated_token_address(&env.vault, &base_mint);
+    assert_eq!(env.balance(&vault_ata).await, UNIT);
+    let taker_quote = env.market.user_quote_token_account(&taker.pubkey());
+    assert_eq!(env.balance(&taker_quote).await, 2 * UNIT);
+}
+
+#[tokio::test]
+async fn vault_takes_ask_within_limit() {
+    let mut env = TestEnv::new(10 * UNIT).await;
+    let owner = Keypair::from_bytes(&env.owner.to_bytes()).unwrap();
+
+    let base_mint = env.market.base_mint;
+    let maker = env.create_trader(&base_mint, UNIT).await;
+    env.send(
+        &[instructions::place_sell_order(
+            &maker.pubkey(),
+            &maker.pubkey(),
+            0,
+            &env.market,
+            2 * UNIT,
+            UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // fill costs 2 quote, above the vault limit
+    let err = env
+        .send(
+            &[env.take_vault_ask(&maker.pubkey(), 0, 2 * UNIT - 1)],
+            &[&owner],
+        )
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(VaultError::SlippageExceeded.into())
+        ),
+    );
+
+    let return_data = env
+        .send(
+            &[env.take_vault_ask(&maker.pubkey(), 0, 2 * UNIT)],
+            &[&owner],
+        )
+        .await
+        .unwrap();
+    let trade = Trade::try_from_slice(&return_data).unwrap();
+    assert_eq!(trade.taker, env.vault);
+    assert_eq!(trade.maker, maker.pubkey());
+    assert_eq!(trade.taker_side, Side::Bid);
+    assert_eq!((trade.base_amount, trade.quote_amount), (UNIT, 2 * UNIT));
+
+    assert_eq!(env.balance(&env.vault_base.clone()).await, UNIT);
+    assert_eq!(env.balance(&env.vault_quote.clone()).await, 8 * UNIT);
+    let maker_quote = env.market.user_quote_token_account(&maker.pubkey());
+    assert_eq!(env.balance(&maker_quote).await, 2 * UNIT);
+}
@@ -1,6 +1,87 @@
+use anchor_lang::prelude::*;
+
 pub const GLOBAL_AUTHORITY_SEED: &str = "global-authority";
 pub const MARKET_SEED: &str = "market";
 pub const USER_MARKET_ORDER_SEED: &str = "user-market-book";
 
 pub const BID_BOOK_SEED: &str = "bid-book";
 pub const ASK_BOOK_SEED: &str = "ask-book";
+pub const TRADE_HISTORY_SEED: &str = "trade-history";
+pub const MARKET_STATS_SEED: &str = "market-stats";
+pub const PEGGED_BOOK_SEED: &str = "pegged-book";
+pub const TRIGGER_BOOK_SEED: &str = "trigger-book";
+
+/// Orders a resize adds to each book, realloc grows an account by at most
+/// `MAX_PERMITTED_DATA_INCREASE` bytes per instruction
+pub const MAX_BOOK_GROWTH: u64 =
+    (anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE
+        / std::mem::size_of::<crate::OpenedOrder>()) as u64;
+
+/// Trades kept by the per market trade history ring buffer
+pub const TRADE_HISTORY_CAPACITY: usize = 64;
+
+/// Resting oracle pegged orders of a market, both sides together
+pub const PEGGED_BOOK_CAPACITY: usize = 32;
+/// Pegged orders one owner can rest in a market, so a few owners can't fill the book
+pub const MAX_PEGGED_ORDERS_PER_USER: usize = 4;
+/// Smallest quote value of a pegged or trigger order of new markets, in whole quote tokens,
+/// market authorities set their own with `set_order_minimums`
+pub const DEFAULT_MIN_ORDER_VALUE: u64 = 1;
+
+/// Trigger orders of a market waiting for their price, both sides together
+pub const TRIGGER_BOOK_CAPACITY: usize = 32;
+/// Trigger orders one owner can wait with in a market
+pub const MAX_TRIGGER_ORDERS_PER_USER: usize = 4;
+/// Smallest quote value of a trade moving last price triggers of new markets, in whole quote
+/// tokens, so a dust self trade can't fire them, market authorities set their own with
+/// `set_order_minimums`
+pub const DEFAULT_MIN_TRIGGER_TRADE_VALUE: u64 = 1;
+/// Lamports a trigger order escrows for the keeper executing it, two signature fees
+pub const TRIGGER_KEEPER_BOUNTY: u64 = 10_000;
+
+/// Hourly buckets summed into the rolling day volume of market stats
+pub const VOLUME_BUCKETS: usize = 24;
+pub const VOLUME_BUCKET_INTERVAL: i64 = 3600;
+
+/// Candle series a market keeps, each holds the last `CANDLES_PER_SERIES` intervals
+pub const MAX_CANDLE_SERIES: usize = 4;
+pub const CANDLES_PER_SERIES: usize = 24;
+/// Candle intervals in seconds of new market stats
+pub const DEFAULT_CANDLE_INTERVALS: [i64; 3] = [60, 3600, 86400];
+
+/// Layout versions of new accounts, `migrate_*` instructions bring older ones up to date
+pub const GLOBAL_POOL_VERSION: u8 = 1;
+pub const MARKET_VERSION: u8 = 1;
+pub const OPEN_ORDERS_VERSION: u8 = 1;
+
+/// Reserved bytes at the end of versioned accounts for fields of later layouts
+pub const RESERVED_SIZE: usize = 128;
+/// Market reserved bytes left after the order limits, oracle band, account flag and minimum
+/// value fields
+pub const MARKET_RESERVED_SIZE: usize = 57;
+/// Open orders reserved bytes left after the delegate, account index and free balances
+pub const OPEN_ORDERS_RESERVED_SIZE: usize = 79;
+
+/// Book format of new markets, version 0 books hold orders without account index worst first
+/// and bids of quote quantities
+pub const BOOK_VERSION: u8 = 1;
+
+/// Oracle programs owning the price accounts a market band can follow, of the cluster
+/// selected by the `devnet` feature
+#[cfg(feature = "devnet")]
+pub const PYTH_ORACLE_PROGRAM_ID: Pubkey = pubkey!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+#[cfg(feature = "devnet")]
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = pubkey!("devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH");
+#[cfg(not(feature = "devnet"))]
+pub const PYTH_ORACLE_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi3epH");
+#[cfg(not(feature = "devnet"))]
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+/// Seconds a Pyth price stays usable after its publish time
+pub const MAX_ORACLE_STALENESS: i64 = 60;
+/// Widest Pyth confidence interval accepted, in basis points of the price
+pub const MAX_ORACLE_CONFIDENCE_BPS: u16 = 200;
+/// Seconds of Raydium CLMM observations averaged into the oracle price, a swap within
+/// one transaction moves it by at most its share of the window
+pub const ORACLE_TWAP_WINDOW: i64 = 300;
+/// Widest price band, the lower bound reaches zero
+pub const MAX_PRICE_BAND_BPS: u16 = 10_000;
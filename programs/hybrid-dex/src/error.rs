@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use hybrid_dex_core::CoreError;
 
 #[error_code]
 pub enum HybridDexError {
@@ -24,4 +25,95 @@ pub enum HybridDexError {
     IncorrectMakerAddress,
     #[msg("Partial take order amount exceed order quantity")]
     PartialOrderAmountExceed,
+    #[msg("Market order limit is zero or above global limit")]
+    InvalidOrderLimit,
+    #[msg("The signer of this transaction is not this market's authority")]
+    InvalidMarketAuthority,
+    #[msg("Cannot close open orders account with resting orders or balances")]
+    NonEmptyOpenOrders,
+    #[msg("Signer is neither open orders owner nor its delegate")]
+    InvalidDelegate,
+    #[msg("Open orders free balance insufficient")]
+    InsufficientFreeBalance,
+    #[msg("Math operation overflow")]
+    MathOverflow,
+    #[msg("Audit account is not a unique open orders account of this market")]
+    InvalidAuditAccount,
+    #[msg("Market vault balance below resting orders and free balances")]
+    VaultInsolvent,
+    #[msg("Amount or price must be greater than zero")]
+    ZeroAmount,
+    #[msg("Price conversion result does not fit in u64")]
+    PriceOverflow,
+    #[msg("Fill is worth less than one atomic unit of the paid token")]
+    ZeroFill,
+    #[msg("Market books use an outdated format, migrate them first")]
+    BookMigrationRequired,
+    #[msg("Market books already use the current format")]
+    BookAlreadyMigrated,
+    #[msg("Migration account is not a unique open orders account of a resting bid maker")]
+    InvalidMigrationAccount,
+    #[msg("Account already uses the current layout version")]
+    AccountAlreadyMigrated,
+    #[msg("Candle intervals must be positive, increasing and at most MAX_CANDLE_SERIES")]
+    InvalidCandleIntervals,
+    #[msg("TWAP end observation must be later than its start")]
+    InvalidObservationWindow,
+    #[msg("Oracle account is not the price source of this market")]
+    InvalidOracleAccount,
+    #[msg("Oracle price is not trading, too old or lacks TWAP history")]
+    StaleOraclePrice,
+    #[msg("Price band must be between 1 and MAX_PRICE_BAND_BPS basis points")]
+    InvalidPriceBand,
+    #[msg("Order price is outside the oracle price band")]
+    PriceOutsideBand,
+    #[msg("Pegged order price is not positive at the current oracle price")]
+    InvalidPeggedPrice,
+    #[msg("Pegged orders and their takes need a non-zero limit price")]
+    PeggedLimitRequired,
+    #[msg("Pegged order price is worse than the taker limit")]
+    PeggedPriceBeyondLimit,
+    #[msg("Limit triggers and market buy triggers need a limit price")]
+    TriggerLimitRequired,
+    #[msg("Trigger price has not been crossed")]
+    TriggerNotReached,
+    #[msg("Trigger fill account is not the open orders or ATA of the resting maker")]
+    InvalidTriggerMakerAccount,
+    #[msg("Book can grow by at most MAX_BOOK_GROWTH orders per resize")]
+    BookGrowthExceeded,
+    #[msg("Trade history account of the market is required")]
+    TradeHistoryRequired,
+    #[msg("Oracle confidence interval is wider than MAX_ORACLE_CONFIDENCE_BPS of its price")]
+    OracleConfidenceTooWide,
+    #[msg("Owner already rests the most pegged or trigger orders allowed per user")]
+    OwnerBookOrdersFull,
+    #[msg("Pegged or trigger order is worth less than its minimum quote value")]
+    OrderValueTooSmall,
+    #[msg("Market stats account of the market is required")]
+    MarketStatsRequired,
+    #[msg("Pegged book of the market is required")]
+    PeggedBookRequired,
+    #[msg("Trigger book of the market is required")]
+    TriggerBookRequired,
+    #[msg("Minimum order and trigger trade values must be positive")]
+    InvalidOrderMinimum,
+    #[msg("Cannot close a market whose vaults still hold tokens")]
+    NonEmptyMarketVault,
+}
+
+impl From<CoreError> for HybridDexError {
+    fn from(err: CoreError) -> HybridDexError {
+        match err {
+            CoreError::OrderNotFound => HybridDexError::OrderNotFound,
+            CoreError::PartialOrderAmountExceed => HybridDexError::PartialOrderAmountExceed,
+            CoreError::MathOverflow => HybridDexError::MathOverflow,
+            CoreError::ZeroFill => HybridDexError::ZeroFill,
+            CoreError::PriceOverflow => HybridDexError::PriceOverflow,
+            CoreError::InvalidObservationWindow => HybridDexError::InvalidObservationWindow,
+            CoreError::InvalidOracle => HybridDexError::InvalidOracleAccount,
+            CoreError::StaleOracle => HybridDexError::StaleOraclePrice,
+            CoreError::UncertainOracle => HybridDexError::OracleConfidenceTooWide,
+            CoreError::InvalidPeggedPrice => HybridDexError::InvalidPeggedPrice,
+        }
+    }
 }
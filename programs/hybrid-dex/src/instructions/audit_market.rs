@@ -0,0 +1,178 @@
+use anchor_spl::associated_token::get_associated_token_address;
+
+use crate::*;
+
+/// Funds the market vaults owe to users against what they hold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MarketAudit {
+    /// Base quantity of resting asks, pegged and trigger asks included
+    pub base_locked: u64,
+    /// Quote locked by resting bids, pegged and trigger bids at their limit price included
+    pub quote_locked: u64,
+    /// Base free balance of the passed open orders accounts
+    pub base_free: u64,
+    /// Quote free balance of the passed open orders accounts
+    pub quote_free: u64,
+    pub base_vault: u64,
+    pub quote_vault: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct AuditMarket<'info> {
+    #[account(
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [BID_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub bids_book: Box<Account<'info, Book>>,
+
+    #[account(
+        seeds = [ASK_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub asks_book: Box<Account<'info, Book>>,
+
+    /// required once the market has one, markets created before pegged orders have none
+    #[account(
+        has_one = market,
+        seeds = [PEGGED_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub pegged_book: Option<Box<Account<'info, PeggedBook>>>,
+
+    /// required once the market has one, markets created before trigger orders have none
+    #[account(
+        has_one = market,
+        seeds = [TRIGGER_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trigger_book: Option<Box<Account<'info, TriggerBook>>>,
+
+    /// CHECK: market base ATA, not created before the first ask or deposit
+    #[account(
+        constraint = base_vault_account.key() == get_associated_token_address(&market.key(), &market.base_mint)
+            @ ErrorCode::ConstraintAddress,
+    )]
+    pub base_vault_account: UncheckedAccount<'info>,
+
+    /// CHECK: market quote ATA, not created before the first bid or deposit
+    #[account(
+        constraint = quote_vault_account.key() == get_associated_token_address(&market.key(), &market.quote_mint)
+            @ ErrorCode::ConstraintAddress,
+    )]
+    pub quote_vault_account: UncheckedAccount<'info>,
+    // remaining accounts: open orders accounts of the market whose free balance is counted
+}
+
+impl AuditMarket<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<MarketAudit> {
+        // check books use the current format
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
+        // check pegged and trigger books are counted once the market has them
+        ctx.accounts
+            .market
+            .check_pegged_book(&ctx.accounts.pegged_book)?;
+        ctx.accounts
+            .market
+            .check_trigger_book(&ctx.accounts.trigger_book)?;
+
+        let market_key = ctx.accounts.market.key();
+
+        let base_decimal = ctx.accounts.market.base_decimal;
+        let (mut escrow_base_locked, mut escrow_quote_locked) = (0u64, 0u64);
+        let pegged = ctx
+            .accounts
+            .pegged_book
+            .iter()
+            .flat_map(|book| book.orders.iter())
+            .map(|order| {
+                (
+                    order.side,
+                    order.locked_amount(order.quantity, base_decimal),
+                )
+            });
+        let triggers = ctx
+            .accounts
+            .trigger_book
+            .iter()
+            .flat_map(|book| book.orders.iter())
+            .map(|order| {
+                (
+                    order.side,
+                    order.locked_amount(order.quantity, base_decimal),
+                )
+            });
+        for (side, locked) in pegged.chain(triggers) {
+            match side {
+                Side::Bid => escrow_quote_locked = escrow_quote_locked.safe_add(locked?)?,
+                Side::Ask => escrow_base_locked = escrow_base_locked.safe_add(locked?)?,
+            }
+        }
+
+        let mut audit = MarketAudit {
+            base_locked: Self::book_total(&ctx.accounts.asks_book, |order| Ok(order.quantity))?
+                .safe_add(escrow_base_locked)?,
+            quote_locked: Self::book_total(&ctx.accounts.bids_book, |order| {
+                ctx.accounts.market.bid_locked_quote(order)
+            })?
+            .safe_add(escrow_quote_locked)?,
+            base_vault: vault_amount(&ctx.accounts.base_vault_account)?,
+            quote_vault: vault_amount(&ctx.accounts.quote_vault_account)?,
+            ..MarketAudit::default()
+        };
+
+        let mut audited: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        for info in ctx.remaining_accounts.iter() {
+            // check each open orders account of this market is counted once
+            require!(
+                info.owner.eq(&crate::ID) && !audited.contains(info.key),
+                HybridDexError::InvalidAuditAccount
+            );
+            let open_orders = UserMarketOrders::try_deserialize(&mut &info.data.borrow()[..])?;
+            require!(
+                open_orders.market.eq(&market_key),
+                HybridDexError::InvalidAuditAccount
+            );
+            audited.push(*info.key);
+
+            audit.base_free = audit.base_free.safe_add(open_orders.base_free)?;
+            audit.quote_free = audit.quote_free.safe_add(open_orders.quote_free)?;
+        }
+
+        msg!(
+            "base locked {} free {} vault {}, quote locked {} free {} vault {}",
+            audit.base_locked,
+            audit.base_free,
+            audit.base_vault,
+            audit.quote_locked,
+            audit.quote_free,
+            audit.quote_vault
+        );
+
+        // check vaults cover resting orders and counted free balances
+        require!(
+            audit.base_locked.saturating_add(audit.base_free) <= audit.base_vault
+                && audit.quote_locked.saturating_add(audit.quote_free) <= audit.quote_vault,
+            HybridDexError::VaultInsolvent
+        );
+
+        Ok(audit)
+    }
+
+    fn book_total(book: &Book, locked: impl Fn(&OpenedOrder) -> Result<u64>) -> Result<u64> {
+        book.orders
+            .iter()
+            .take(book.orders_count as usize)
+            .try_fold(0u64, |total, order| total.safe_add(locked(order)?))
+    }
+}
@@ -0,0 +1,79 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CancelPeggedOrder<'info> {
+    /// open orders owner or its delegate
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            maker.key().as_ref(),
+            UserMarketOrders::index_seed(&user_open_orders.account_index),
+        ],
+        bump,
+        constraint = user_open_orders.address.eq(&maker.key()) @ HybridDexError::InvalidAccountOwner,
+        constraint = user_open_orders.is_authorized(&authority.key()) @ HybridDexError::InvalidDelegate,
+    )]
+    pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [PEGGED_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub pegged_book: Box<Account<'info, PeggedBook>>,
+}
+
+impl CancelPeggedOrder<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64, order_id: u64) -> Result<()> {
+        let user_open_orders = &mut ctx.accounts.user_open_orders;
+
+        let order = ctx.accounts.pegged_book.get_order(order_id)?;
+
+        // check maker open orders against order id
+        require!(
+            user_open_orders.is_pegged_order_owner(&order),
+            HybridDexError::IncorrectMakerAddress
+        );
+
+        ctx.accounts.pegged_book.remove_order(order_id)?;
+        user_open_orders.opened_orders_count = user_open_orders.opened_orders_count.safe_sub(1)?;
+
+        // release locked token to free balance, cancels need no oracle price
+        let locked_amount =
+            order.locked_amount(order.quantity, ctx.accounts.market.base_decimal)?;
+        match order.side {
+            Side::Bid => {
+                user_open_orders.quote_deposit_total = user_open_orders
+                    .quote_deposit_total
+                    .safe_sub(locked_amount)?;
+                user_open_orders.quote_free =
+                    user_open_orders.quote_free.safe_add(locked_amount)?;
+            }
+            Side::Ask => {
+                user_open_orders.base_deposit_total = user_open_orders
+                    .base_deposit_total
+                    .safe_sub(locked_amount)?;
+                user_open_orders.base_free = user_open_orders.base_free.safe_add(locked_amount)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,19 +1,16 @@
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
-};
-
 use crate::*;
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
 pub struct CancelSellOrder<'info> {
+    /// open orders owner or its delegate
     #[account(mut)]
-    pub maker: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub maker: UncheckedAccount<'info>,
 
     #[account(
-        has_one = base_mint,
-        has_one = quote_mint,
         seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
         bump,
     )]
@@ -22,81 +19,53 @@ pub struct CancelSellOrder<'info> {
     #[account(
         mut,
         has_one = market,
-        seeds = [USER_MARKET_ORDER_SEED.as_bytes(), market.key().as_ref(), maker.key().as_ref()],
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            maker.key().as_ref(),
+            UserMarketOrders::index_seed(&user_open_orders.account_index),
+        ],
         bump,
         constraint = user_open_orders.address.eq(&maker.key()) @ HybridDexError::InvalidAccountOwner,
+        constraint = user_open_orders.is_authorized(&authority.key()) @ HybridDexError::InvalidDelegate,
     )]
     pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
 
-    pub base_mint: Box<Account<'info, Mint>>,
-
-    pub quote_mint: Box<Account<'info, Mint>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = base_mint,
-        associated_token::authority = maker,
-        payer = maker,
-    )]
-    pub user_base_token_account: Box<Account<'info, TokenAccount>>,
-
-    #[account(
-        mut,
-        associated_token::mint = base_mint,
-        associated_token::authority = market,
-    )]
-    pub base_vault_account: Box<Account<'info, TokenAccount>>,
-
     #[account(
         mut,
         seeds = [ASK_BOOK_SEED.as_bytes(), market.key().as_ref()],
         bump,
     )]
     pub asks_book: Box<Account<'info, Book>>,
-
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 impl CancelSellOrder<'_> {
-    pub fn process_instruction(ctx: &mut Context<Self>, seed: u64, order_id: u64) -> Result<()> {
-        let market = &mut ctx.accounts.market;
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64, order_id: u64) -> Result<()> {
+        // check books use the current format
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
         let user_open_orders = &mut ctx.accounts.user_open_orders;
 
         let asks_book = &mut ctx.accounts.asks_book;
         let order = asks_book.remove_order(order_id)?;
 
-        // check base token vault balance
+        // check maker open orders against order id
         require!(
-            ctx.accounts.base_vault_account.amount >= order.quantity,
-            HybridDexError::InsufficientWithdrawBalance
+            user_open_orders.is_order_owner(&order),
+            HybridDexError::IncorrectMakerAddress
         );
 
-        asks_book.orders_count -= 1;
-        user_open_orders.opened_orders_count -= 1;
-        user_open_orders.base_deposit_total -= order.quantity;
-
-        let seed_bytes = seed.to_le_bytes();
-        let seeds = &[MARKET_SEED.as_bytes(), &seed_bytes, &[ctx.bumps.market]];
-        let signers_seeds = &[&seeds[..]];
-
-        // transfer base token from vault
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.base_vault_account.to_account_info(),
-            to: ctx.accounts.user_base_token_account.to_account_info(),
-            authority: market.to_account_info(),
-        };
+        asks_book.orders_count = asks_book.orders_count.safe_sub(1)?;
+        user_open_orders.opened_orders_count = user_open_orders.opened_orders_count.safe_sub(1)?;
 
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                cpi_accounts,
-                signers_seeds,
-            ),
-            order.quantity,
-        )?;
+        // release base token to free balance, maker withdraws it separately
+        user_open_orders.base_deposit_total = user_open_orders
+            .base_deposit_total
+            .safe_sub(order.quantity)?;
+        user_open_orders.base_free = user_open_orders.base_free.safe_add(order.quantity)?;
 
         Ok(())
     }
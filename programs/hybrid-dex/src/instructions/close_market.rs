@@ -1,4 +1,4 @@
-use anchor_spl::token::Mint;
+use anchor_spl::{associated_token::get_associated_token_address, token::Mint};
 
 use crate::*;
 
@@ -47,12 +47,72 @@ pub struct CloseMarket<'info> {
     )]
     pub asks_book: Box<Account<'info, Book>>,
 
+    /// required once the market has one, markets created before trades were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        close = authority,
+        seeds = [TRADE_HISTORY_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trade_history: Option<Box<Account<'info, TradeHistory>>>,
+
+    /// required once the market has them, markets created before candles were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        close = authority,
+        seeds = [MARKET_STATS_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub market_stats: Option<Box<Account<'info, MarketStats>>>,
+
+    /// required once the market has one, markets created before pegged orders have none
+    #[account(
+        mut,
+        has_one = market,
+        close = authority,
+        seeds = [PEGGED_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub pegged_book: Option<Box<Account<'info, PeggedBook>>>,
+
+    /// required once the market has one, markets created before trigger orders have none
+    #[account(
+        mut,
+        has_one = market,
+        close = authority,
+        seeds = [TRIGGER_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trigger_book: Option<Box<Account<'info, TriggerBook>>>,
+
+    /// CHECK: market base ATA, not created before the first ask or deposit
+    #[account(
+        constraint = base_vault_account.key() == get_associated_token_address(&market.key(), &market.base_mint)
+            @ ErrorCode::ConstraintAddress,
+    )]
+    pub base_vault_account: UncheckedAccount<'info>,
+
+    /// CHECK: market quote ATA, not created before the first bid or deposit
+    #[account(
+        constraint = quote_vault_account.key() == get_associated_token_address(&market.key(), &market.quote_mint)
+            @ ErrorCode::ConstraintAddress,
+    )]
+    pub quote_vault_account: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 impl CloseMarket<'_> {
     pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<()> {
+        // check books use the current format
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
         let global_pool = &mut ctx.accounts.global_pool;
         let market = &mut ctx.accounts.market;
 
@@ -62,13 +122,38 @@ impl CloseMarket<'_> {
             HybridDexError::InvalidCloseMarketAdmin
         );
 
+        // check trade history, stats, pegged and trigger books are passed to be closed
+        // once the market has them
+        market.check_trade_history(&ctx.accounts.trade_history)?;
+        market.check_market_stats(&ctx.accounts.market_stats)?;
+        market.check_pegged_book(&ctx.accounts.pegged_book)?;
+        market.check_trigger_book(&ctx.accounts.trigger_book)?;
+
         // check market order book is empty
         require!(
-            ctx.accounts.bids_book.orders_count == 0 && ctx.accounts.asks_book.orders_count == 0,
+            ctx.accounts.bids_book.orders_count == 0
+                && ctx.accounts.asks_book.orders_count == 0
+                && ctx
+                    .accounts
+                    .pegged_book
+                    .iter()
+                    .all(|book| book.orders.is_empty())
+                && ctx
+                    .accounts
+                    .trigger_book
+                    .iter()
+                    .all(|book| book.orders.is_empty()),
             HybridDexError::NonEmptyMarket
         );
 
-        global_pool.total_market_count -= 1;
+        // check users withdrew their free balances, which needs the market to sign
+        require!(
+            vault_amount(&ctx.accounts.base_vault_account)? == 0
+                && vault_amount(&ctx.accounts.quote_vault_account)? == 0,
+            HybridDexError::NonEmptyMarketVault
+        );
+
+        global_pool.total_market_count = global_pool.total_market_count.safe_sub(1)?;
 
         Ok(())
     }
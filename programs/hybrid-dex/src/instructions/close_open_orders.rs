@@ -0,0 +1,45 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct CloseOpenOrders<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: only used as PDA seed, market account may already be closed
+    pub market: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = market,
+        close = user,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            user.key().as_ref(),
+            UserMarketOrders::index_seed(&user_open_orders.account_index),
+        ],
+        bump,
+        constraint = user_open_orders.address.eq(&user.key()) @ HybridDexError::InvalidAccountOwner,
+    )]
+    pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl CloseOpenOrders<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>) -> Result<()> {
+        let user_open_orders = &ctx.accounts.user_open_orders;
+
+        // check no resting orders and no deposited funds left
+        require!(
+            user_open_orders.opened_orders_count == 0
+                && user_open_orders.base_deposit_total == 0
+                && user_open_orders.quote_deposit_total == 0
+                && user_open_orders.base_free == 0
+                && user_open_orders.quote_free == 0,
+            HybridDexError::NonEmptyOpenOrders
+        );
+
+        Ok(())
+    }
+}
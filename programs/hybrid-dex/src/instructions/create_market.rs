@@ -3,6 +3,7 @@ use anchor_spl::token::Mint;
 use crate::*;
 
 #[derive(Accounts)]
+#[instruction(name: String, max_orders_per_user: u64, max_orders_per_book: u64)]
 pub struct CreateMarket<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -29,7 +30,7 @@ pub struct CreateMarket<'info> {
 
     #[account(
         init,
-        space = Book::size(global_pool.max_orders_per_book),
+        space = Book::size(max_orders_per_book),
         seeds = [BID_BOOK_SEED.as_bytes(), market.key().as_ref()],
         bump,
         payer = authority
@@ -38,24 +39,75 @@ pub struct CreateMarket<'info> {
 
     #[account(
         init,
-        space = Book::size(global_pool.max_orders_per_book),
+        space = Book::size(max_orders_per_book),
         seeds = [ASK_BOOK_SEED.as_bytes(), market.key().as_ref()],
         bump,
         payer = authority
     )]
     pub asks_book: Box<Account<'info, Book>>,
 
+    #[account(
+        init,
+        space = TradeHistory::DATA_SIZE,
+        seeds = [TRADE_HISTORY_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = authority
+    )]
+    pub trade_history: Box<Account<'info, TradeHistory>>,
+
+    #[account(
+        init,
+        space = MarketStats::DATA_SIZE,
+        seeds = [MARKET_STATS_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = authority
+    )]
+    pub market_stats: Box<Account<'info, MarketStats>>,
+
+    #[account(
+        init,
+        space = PeggedBook::DATA_SIZE,
+        seeds = [PEGGED_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = authority
+    )]
+    pub pegged_book: Box<Account<'info, PeggedBook>>,
+
+    #[account(
+        init,
+        space = TriggerBook::DATA_SIZE,
+        seeds = [TRIGGER_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = authority
+    )]
+    pub trigger_book: Box<Account<'info, TriggerBook>>,
+
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 impl CreateMarket<'_> {
-    pub fn process_instruction(ctx: &mut Context<Self>, name: String) -> Result<()> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        name: String,
+        max_orders_per_user: u64,
+        max_orders_per_book: u64,
+    ) -> Result<()> {
         let global_pool = &mut ctx.accounts.global_pool;
         let market = &mut ctx.accounts.market;
         let bids_book = &mut ctx.accounts.bids_book;
         let asks_book = &mut ctx.accounts.asks_book;
 
+        // check market order limits against global bounds
+        require!(
+            max_orders_per_user > 0 && max_orders_per_user <= global_pool.max_orders_per_user,
+            HybridDexError::InvalidOrderLimit
+        );
+        require!(
+            max_orders_per_book > 0 && max_orders_per_book <= global_pool.max_orders_per_book,
+            HybridDexError::InvalidOrderLimit
+        );
+
         market.seed = global_pool.market_seq_num;
         market.market_authority = ctx.accounts.authority.key();
 
@@ -72,14 +124,34 @@ impl CreateMarket<'_> {
 
         market.name = fill_from_str(&name)?;
 
+        market.max_orders_per_user = max_orders_per_user;
+        market.max_orders_per_book = max_orders_per_book;
+        market.min_order_value = market.whole_quote(DEFAULT_MIN_ORDER_VALUE)?;
+        market.min_trigger_trade_value = market.whole_quote(DEFAULT_MIN_TRIGGER_TRADE_VALUE)?;
+        market.book_version = BOOK_VERSION;
+        market.version = MARKET_VERSION;
+
         bids_book.side = Side::Bid;
         bids_book.market = market.key();
 
         asks_book.side = Side::Ask;
         asks_book.market = market.key();
 
-        global_pool.total_market_count += 1;
-        global_pool.market_seq_num += 1;
+        ctx.accounts.trade_history.market = market.key();
+        market.has_trade_history = true;
+
+        let market_stats = &mut ctx.accounts.market_stats;
+        market_stats.market = market.key();
+        market_stats.set_candle_intervals(&DEFAULT_CANDLE_INTERVALS)?;
+        market.has_market_stats = true;
+
+        ctx.accounts.pegged_book.market = market.key();
+        market.has_pegged_book = true;
+        ctx.accounts.trigger_book.market = market.key();
+        market.has_trigger_book = true;
+
+        global_pool.total_market_count = global_pool.total_market_count.safe_add(1)?;
+        global_pool.market_seq_num = global_pool.market_seq_num.safe_add(1)?;
 
         Ok(())
     }
@@ -0,0 +1,39 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateMarketStats<'info> {
+    /// anyone can pay for the stats of a market created before they existed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init,
+        space = MarketStats::DATA_SIZE,
+        seeds = [MARKET_STATS_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = payer
+    )]
+    pub market_stats: Box<Account<'info, MarketStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateMarketStats<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<()> {
+        let market_stats = &mut ctx.accounts.market_stats;
+
+        market_stats.market = ctx.accounts.market.key();
+        market_stats.set_candle_intervals(&DEFAULT_CANDLE_INTERVALS)?;
+        ctx.accounts.market.has_market_stats = true;
+
+        Ok(())
+    }
+}
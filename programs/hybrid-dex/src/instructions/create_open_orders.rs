@@ -1,6 +1,7 @@
 use crate::*;
 
 #[derive(Accounts)]
+#[instruction(account_index: u8)]
 pub struct CreateOpenOrders<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -10,7 +11,12 @@ pub struct CreateOpenOrders<'info> {
     #[account(
         init,
         space = UserMarketOrders::DATA_SIZE,
-        seeds = [USER_MARKET_ORDER_SEED.as_bytes(), market.key().as_ref(), user.key().as_ref()],
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            user.key().as_ref(),
+            UserMarketOrders::index_seed(&account_index),
+        ],
         bump,
         payer = user,
     )]
@@ -21,12 +27,14 @@ pub struct CreateOpenOrders<'info> {
 }
 
 impl CreateOpenOrders<'_> {
-    pub fn process_instruction(ctx: &mut Context<Self>) -> Result<()> {
+    pub fn process_instruction(ctx: &mut Context<Self>, account_index: u8) -> Result<()> {
         let market = &mut ctx.accounts.market;
         let user_open_orders = &mut ctx.accounts.user_open_orders;
 
         user_open_orders.address = ctx.accounts.user.key();
         user_open_orders.market = market.key();
+        user_open_orders.account_index = account_index;
+        user_open_orders.version = OPEN_ORDERS_VERSION;
 
         Ok(())
     }
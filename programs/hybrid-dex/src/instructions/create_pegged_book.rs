@@ -0,0 +1,36 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreatePeggedBook<'info> {
+    /// anyone can pay for the pegged book of a market created before it existed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init,
+        space = PeggedBook::DATA_SIZE,
+        seeds = [PEGGED_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = payer
+    )]
+    pub pegged_book: Box<Account<'info, PeggedBook>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl CreatePeggedBook<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<()> {
+        ctx.accounts.pegged_book.market = ctx.accounts.market.key();
+        ctx.accounts.market.has_pegged_book = true;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateTradeHistory<'info> {
+    /// anyone can pay for the trade history of a market created before it existed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init,
+        space = TradeHistory::DATA_SIZE,
+        seeds = [TRADE_HISTORY_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = payer
+    )]
+    pub trade_history: Box<Account<'info, TradeHistory>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateTradeHistory<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<()> {
+        ctx.accounts.trade_history.market = ctx.accounts.market.key();
+        ctx.accounts.market.has_trade_history = true;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateTriggerBook<'info> {
+    /// anyone can pay for the trigger book of a market created before it existed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init,
+        space = TriggerBook::DATA_SIZE,
+        seeds = [TRIGGER_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        payer = payer
+    )]
+    pub trigger_book: Box<Account<'info, TriggerBook>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl CreateTriggerBook<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<()> {
+        ctx.accounts.trigger_book.market = ctx.accounts.market.key();
+        ctx.accounts.market.has_trigger_book = true;
+
+        Ok(())
+    }
+}
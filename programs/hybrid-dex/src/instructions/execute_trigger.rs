@@ -0,0 +1,402 @@
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{self, Token, Transfer},
+};
+use hybrid_dex_core::CoreError;
+
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct ExecuteTrigger<'info> {
+    /// anyone can execute a crossed trigger and earn its bounty
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// CHECK: trigger order owner, only its key is used to find its open orders
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            owner.key().as_ref(),
+            UserMarketOrders::index_seed(&owner_open_orders.account_index),
+        ],
+        bump,
+        constraint = owner_open_orders.address.eq(&owner.key()) @ HybridDexError::InvalidAccountOwner,
+    )]
+    pub owner_open_orders: Box<Account<'info, UserMarketOrders>>,
+
+    #[account(
+        mut,
+        seeds = [BID_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub bids_book: Box<Account<'info, Book>>,
+
+    #[account(
+        mut,
+        seeds = [ASK_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub asks_book: Box<Account<'info, Book>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [TRIGGER_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trigger_book: Box<Account<'info, TriggerBook>>,
+
+    /// required once the market has one, markets created before trades were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [TRADE_HISTORY_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trade_history: Option<Box<Account<'info, TradeHistory>>>,
+
+    /// required once the market has them, markets created before candles were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [MARKET_STATS_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub market_stats: Option<Box<Account<'info, MarketStats>>>,
+
+    /// CHECK: market base ATA, holds the escrow of asks
+    #[account(
+        mut, constraint = base_vault_account.key() == get_associated_token_address(&market.key(), &market.base_mint)
+            @ ErrorCode::ConstraintAddress,
+    )]
+    pub base_vault_account: UncheckedAccount<'info>,
+
+    /// CHECK: market quote ATA, holds the escrow of bids
+    #[account(
+        mut, constraint = quote_vault_account.key() == get_associated_token_address(&market.key(), &market.quote_mint)
+            @ ErrorCode::ConstraintAddress,
+    )]
+    pub quote_vault_account: UncheckedAccount<'info>,
+
+    /// CHECK: market oracle, required by oracle triggers and once the market has a price band
+    pub oracle: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    // remaining accounts: market triggers pass a writable maker open orders account and
+    // maker ATA of the received mint for each book order they fill, in book order
+}
+
+impl ExecuteTrigger<'_> {
+    pub fn process_instruction<'info>(
+        ctx: &mut Context<'_, '_, 'info, 'info, ExecuteTrigger<'info>>,
+        seed: u64,
+        order_id: u64,
+    ) -> Result<()> {
+        let order = ctx.accounts.trigger_book.get_order(order_id)?;
+
+        // check owner open orders against order id
+        require!(
+            ctx.accounts
+                .owner_open_orders
+                .is_trigger_order_owner(&order),
+            HybridDexError::IncorrectMakerAddress
+        );
+
+        // check watched price has crossed the trigger price
+        let now = Clock::get()?.unix_timestamp;
+        let price = match order.source {
+            TriggerSource::LastPrice => {
+                // trades under the market minimum value are ignored, no last price is never
+                // reached
+                ctx.accounts
+                    .trade_history
+                    .as_ref()
+                    .ok_or(HybridDexError::TradeHistoryRequired)?
+                    .last_price(ctx.accounts.market.min_trigger_trade_value)
+                    .unwrap_or(0)
+            }
+            TriggerSource::Oracle => {
+                let oracle = ctx
+                    .accounts
+                    .oracle
+                    .as_deref()
+                    .ok_or(HybridDexError::InvalidOracleAccount)?;
+                ctx.accounts.market.oracle_price(oracle, now)?
+            }
+        };
+        require!(
+            price > 0 && order.is_triggered(price),
+            HybridDexError::TriggerNotReached
+        );
+
+        // check books hold base quantities
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
+        // check trade history and stats are passed once the market has them
+        ctx.accounts
+            .market
+            .check_trade_history(&ctx.accounts.trade_history)?;
+        ctx.accounts
+            .market
+            .check_market_stats(&ctx.accounts.market_stats)?;
+
+        ctx.accounts.trigger_book.remove_order(order_id)?;
+
+        match order.order_type {
+            TriggerOrderType::Limit => Self::rest_limit_order(ctx, &order, now)?,
+            TriggerOrderType::Market => Self::fill_market_order(ctx, &order, seed, now)?,
+        }
+
+        // pay the keeper out of the trigger book
+        withdraw_lamports(
+            &ctx.accounts.trigger_book.to_account_info(),
+            &ctx.accounts.keeper.to_account_info(),
+            order.bounty,
+        )?;
+
+        Ok(())
+    }
+
+    /// Escrow keeps locking the order in the book, bids lock quote at the same limit price
+    fn rest_limit_order(
+        ctx: &mut Context<ExecuteTrigger>,
+        order: &TriggerOrder,
+        now: i64,
+    ) -> Result<()> {
+        let market = &ctx.accounts.market;
+
+        // check limit price against the oracle band
+        market.check_price_band(ctx.accounts.oracle.as_deref(), order.limit_price, now)?;
+
+        let book = match order.side {
+            Side::Bid => &mut ctx.accounts.bids_book,
+            Side::Ask => &mut ctx.accounts.asks_book,
+        };
+
+        // check max market order book
+        require!(
+            book.orders_count < market.max_orders_per_book,
+            HybridDexError::OpenOrdersFull
+        );
+
+        book.insert_order(order.limit_order(now));
+        book.orders_count = book.orders_count.safe_add(1)?;
+
+        Ok(())
+    }
+
+    /// Take the best opposite orders up to the limit price, the unfilled escrow returns
+    /// to owner free balance
+    fn fill_market_order<'info>(
+        ctx: &mut Context<'_, '_, 'info, 'info, ExecuteTrigger<'info>>,
+        order: &TriggerOrder,
+        seed: u64,
+        now: i64,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let owner_open_orders = &mut ctx.accounts.owner_open_orders;
+        let base_decimal = market.base_decimal;
+
+        // makers receive the escrowed token straight from its vault
+        let (maker_side, book, maker_mint, vault_account) = match order.side {
+            Side::Bid => (
+                Side::Ask,
+                &mut ctx.accounts.asks_book,
+                market.quote_mint,
+                &ctx.accounts.quote_vault_account,
+            ),
+            Side::Ask => (
+                Side::Bid,
+                &mut ctx.accounts.bids_book,
+                market.base_mint,
+                &ctx.accounts.base_vault_account,
+            ),
+        };
+
+        let seed_bytes = seed.to_le_bytes();
+        let seeds = &[MARKET_SEED.as_bytes(), &seed_bytes, &[ctx.bumps.market]];
+        let signers_seeds = &[&seeds[..]];
+
+        let mut escrow = order.locked_amount(order.quantity, base_decimal)?;
+        let mut remaining = order.quantity;
+        let mut maker_accounts = ctx.remaining_accounts.chunks(2);
+        let mut index = 0;
+
+        while remaining > 0 && index < book.orders_count as usize {
+            let best = book.orders[index];
+
+            // stop at the limit price, a zero limit leaves an ask uncapped
+            let within_limit = match order.side {
+                Side::Bid => best.price <= order.limit_price,
+                Side::Ask => best.price >= order.limit_price,
+            };
+            if !within_limit {
+                break;
+            }
+
+            // skip orders of the trigger owner, the trigger fills the next best ones
+            if best.owner == order.owner {
+                index += 1;
+                continue;
+            }
+
+            let fill = remaining.min(best.quantity);
+            let (base_amount, quote_amount) = match hybrid_dex_core::fill_amounts(
+                maker_side.into(),
+                fill,
+                best.price,
+                base_decimal,
+            ) {
+                Ok(amounts) => amounts,
+                Err(CoreError::ZeroFill) => break,
+                Err(err) => return Err(error!(HybridDexError::from(err))),
+            };
+
+            // a bid pays out of its escrow, rounding can leave it short of the last fill
+            let paid_amount = match order.side {
+                Side::Bid => quote_amount,
+                Side::Ask => base_amount,
+            };
+            if paid_amount > escrow {
+                break;
+            }
+
+            // check maker accounts against the resting order
+            let [maker_info, token_info] = maker_accounts
+                .next()
+                .and_then(|accounts| <&[_; 2]>::try_from(accounts).ok())
+                .ok_or(HybridDexError::InvalidTriggerMakerAccount)?;
+            let mut maker = Account::<UserMarketOrders>::try_from(maker_info)?;
+            require!(
+                maker_info.is_writable
+                    && maker_info.key.ne(&owner_open_orders.key())
+                    && maker.market.eq(&market.key())
+                    && maker.is_order_owner(&best)
+                    && token_info
+                        .key
+                        .eq(&get_associated_token_address(&maker.address, &maker_mint)),
+                HybridDexError::InvalidTriggerMakerAccount
+            );
+
+            if fill == best.quantity {
+                book.remove_order(best.order_id)?;
+                book.orders_count = book.orders_count.safe_sub(1)?;
+                maker.opened_orders_count = maker.opened_orders_count.safe_sub(1)?;
+            } else {
+                book.decrease_order(best.order_id, fill)?;
+            }
+
+            match order.side {
+                Side::Bid => {
+                    maker.base_deposit_total = maker.base_deposit_total.safe_sub(base_amount)?;
+
+                    owner_open_orders.quote_deposit_total = owner_open_orders
+                        .quote_deposit_total
+                        .safe_sub(quote_amount)?;
+                    owner_open_orders.base_free =
+                        owner_open_orders.base_free.safe_add(base_amount)?;
+                }
+                Side::Ask => {
+                    // the bid releases the difference of its locked quote and rounding
+                    // dust returns to maker
+                    let released_amount =
+                        locked_quote(best.quantity, best.price, base_decimal)?.safe_sub(
+                            locked_quote(best.quantity.safe_sub(fill)?, best.price, base_decimal)?,
+                        )?;
+                    let dust_amount = released_amount.safe_sub(quote_amount)?;
+                    maker.quote_deposit_total =
+                        maker.quote_deposit_total.safe_sub(released_amount)?;
+                    maker.quote_free = maker.quote_free.safe_add(dust_amount)?;
+
+                    owner_open_orders.base_deposit_total =
+                        owner_open_orders.base_deposit_total.safe_sub(base_amount)?;
+                    owner_open_orders.quote_free =
+                        owner_open_orders.quote_free.safe_add(quote_amount)?;
+                }
+            }
+
+            maker.base_total_volume = maker.base_total_volume.safe_add(base_amount)?;
+            maker.quote_total_volume = maker.quote_total_volume.safe_add(quote_amount)?;
+
+            owner_open_orders.base_total_volume =
+                owner_open_orders.base_total_volume.safe_add(base_amount)?;
+            owner_open_orders.quote_total_volume = owner_open_orders
+                .quote_total_volume
+                .safe_add(quote_amount)?;
+
+            market.base_total_volume = market.base_total_volume.safe_add(base_amount)?;
+            market.quote_total_volume = market.quote_total_volume.safe_add(quote_amount)?;
+
+            // record fill in market trade history and stats, the trigger owner takes
+            let trade = Trade::new(
+                &best,
+                base_amount,
+                quote_amount,
+                order.side,
+                order.owner,
+                now,
+            );
+            if let Some(trade_history) = ctx.accounts.trade_history.as_mut() {
+                trade_history.record(trade);
+            }
+            if let Some(market_stats) = ctx.accounts.market_stats.as_mut() {
+                market_stats.record_fill(best.price, base_amount, quote_amount, now)?;
+            }
+
+            // transfer escrowed token from vault to maker
+            let cpi_accounts = Transfer {
+                from: vault_account.to_account_info(),
+                to: token_info.clone(),
+                authority: market.to_account_info(),
+            };
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signers_seeds,
+                ),
+                paid_amount,
+            )?;
+
+            maker.exit(&crate::ID)?;
+
+            escrow = escrow.safe_sub(paid_amount)?;
+            remaining = remaining.safe_sub(fill)?;
+        }
+
+        // release the unfilled escrow
+        match order.side {
+            Side::Bid => {
+                owner_open_orders.quote_deposit_total =
+                    owner_open_orders.quote_deposit_total.safe_sub(escrow)?;
+                owner_open_orders.quote_free = owner_open_orders.quote_free.safe_add(escrow)?;
+            }
+            Side::Ask => {
+                owner_open_orders.base_deposit_total =
+                    owner_open_orders.base_deposit_total.safe_sub(escrow)?;
+                owner_open_orders.base_free = owner_open_orders.base_free.safe_add(escrow)?;
+            }
+        }
+        owner_open_orders.opened_orders_count =
+            owner_open_orders.opened_orders_count.safe_sub(1)?;
+
+        Ok(())
+    }
+}
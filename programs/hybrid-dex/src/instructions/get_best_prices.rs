@@ -0,0 +1,65 @@
+use hybrid_dex_core::best_level;
+
+use crate::*;
+
+/// Price, total base quantity and order count of a book price level
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceLevel {
+    pub price: u64,
+    pub quantity: u64,
+    pub orders_count: u64,
+}
+
+/// Top of the book, `None` for an empty side
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BestPrices {
+    pub best_bid: Option<PriceLevel>,
+    pub best_ask: Option<PriceLevel>,
+}
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct GetBestPrices<'info> {
+    #[account(
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [BID_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub bids_book: Box<Account<'info, Book>>,
+
+    #[account(
+        seeds = [ASK_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub asks_book: Box<Account<'info, Book>>,
+}
+
+impl GetBestPrices<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<BestPrices> {
+        // check bids book holds base quantities
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
+        Ok(BestPrices {
+            best_bid: Self::best_level(&ctx.accounts.bids_book)?,
+            best_ask: Self::best_level(&ctx.accounts.asks_book)?,
+        })
+    }
+
+    fn best_level(book: &Book) -> Result<Option<PriceLevel>> {
+        let level = best_level(&book.orders, book.orders_count).map_err(HybridDexError::from)?;
+
+        Ok(level.map(|level| PriceLevel {
+            price: level.price,
+            quantity: level.quantity,
+            orders_count: level.orders_count,
+        }))
+    }
+}
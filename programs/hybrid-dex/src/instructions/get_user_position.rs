@@ -0,0 +1,46 @@
+use crate::*;
+
+/// Balances of an open orders account, locked amounts back its resting orders
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UserPosition {
+    pub owner: Pubkey,
+    pub account_index: u8,
+    pub opened_orders_count: u64,
+    pub base_locked: u64,
+    pub quote_locked: u64,
+    pub base_free: u64,
+    pub quote_free: u64,
+    pub base_total_volume: u64,
+    pub quote_total_volume: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct GetUserPosition<'info> {
+    #[account(
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(has_one = market)]
+    pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
+}
+
+impl GetUserPosition<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<UserPosition> {
+        let orders = &ctx.accounts.user_open_orders;
+
+        Ok(UserPosition {
+            owner: orders.address,
+            account_index: orders.account_index,
+            opened_orders_count: orders.opened_orders_count,
+            base_locked: orders.base_deposit_total,
+            quote_locked: orders.quote_deposit_total,
+            base_free: orders.base_free,
+            quote_free: orders.quote_free,
+            base_total_volume: orders.base_total_volume,
+            quote_total_volume: orders.quote_total_volume,
+        })
+    }
+}
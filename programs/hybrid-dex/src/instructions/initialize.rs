@@ -27,6 +27,7 @@ impl Initialize<'_> {
         let global_pool = &mut ctx.accounts.global_pool;
 
         global_pool.admin = ctx.accounts.admin.key();
+        global_pool.version = GLOBAL_POOL_VERSION;
 
         global_pool.max_orders_per_user = max_orders_per_user;
         global_pool.max_orders_per_book = max_orders_per_book;
@@ -0,0 +1,166 @@
+use hybrid_dex_core::{base_amount, Rounding};
+
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MigrateBooks<'info> {
+    /// market authority pays for the wider orders
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+        constraint = market.market_authority.eq(&authority.key()) @ HybridDexError::InvalidMarketAuthority,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    /// CHECK: legacy layout can't be loaded as `Book`, it's decoded and grown
+    #[account(
+        mut,
+        seeds = [BID_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub bids_book: UncheckedAccount<'info>,
+
+    /// CHECK: legacy layout can't be loaded as `Book`, it's decoded and grown
+    #[account(
+        mut,
+        seeds = [ASK_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub asks_book: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    // remaining accounts: writable open orders accounts of every resting bid maker
+}
+
+impl MigrateBooks<'_> {
+    pub fn process_instruction<'info>(
+        ctx: &mut Context<'_, '_, 'info, 'info, Self>,
+        _seed: u64,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        // check books still use the legacy format
+        require!(
+            !market.is_book_current(),
+            HybridDexError::BookAlreadyMigrated
+        );
+
+        let mut makers: Vec<Account<'info, UserMarketOrders>> =
+            Vec::with_capacity(ctx.remaining_accounts.len());
+        for info in ctx.remaining_accounts.iter() {
+            // check each maker open orders account of this market is passed once
+            require!(
+                info.is_writable && !makers.iter().any(|maker| maker.key().eq(info.key)),
+                HybridDexError::InvalidMigrationAccount
+            );
+            let maker = Account::<UserMarketOrders>::try_from(info)?;
+            require!(
+                maker.market.eq(&market.key()),
+                HybridDexError::InvalidMigrationAccount
+            );
+            makers.push(maker);
+        }
+
+        let legacy_bids: BookV0 = decode_legacy_account::<Book, _>(&ctx.accounts.bids_book)?;
+        let legacy_asks: BookV0 = decode_legacy_account::<Book, _>(&ctx.accounts.asks_book)?;
+
+        let mut bids = Vec::with_capacity(legacy_bids.orders_count as usize);
+        for legacy in legacy_bids
+            .orders
+            .iter()
+            .take(legacy_bids.orders_count as usize)
+        {
+            let order = OpenedOrder {
+                order_id: legacy.order_id,
+                owner: legacy.owner,
+                price: legacy.price,
+                quantity: legacy.quantity,
+                created_at: legacy.created_at,
+                account_index: 0,
+            };
+            let maker = makers
+                .iter_mut()
+                .find(|maker| maker.is_order_owner(&order))
+                .ok_or(HybridDexError::InvalidMigrationAccount)?;
+
+            // quote quantity becomes base quantity rounded down, the bid keeps locking
+            // its quote value rounded up and the remainder returns to maker free balance,
+            // bids worth less than one base unit are cancelled
+            let quantity = base_amount(
+                order.quantity,
+                order.price,
+                market.base_decimal,
+                Rounding::Down,
+            )
+            .unwrap_or(0);
+            let locked_amount = if quantity > 0 {
+                locked_quote(quantity, order.price, market.base_decimal)?
+            } else {
+                0
+            };
+            let refund_amount = order.quantity.safe_sub(locked_amount)?;
+
+            maker.quote_deposit_total = maker.quote_deposit_total.safe_sub(refund_amount)?;
+            maker.quote_free = maker.quote_free.safe_add(refund_amount)?;
+
+            if quantity > 0 {
+                bids.push(OpenedOrder { quantity, ..order });
+            } else {
+                maker.opened_orders_count = maker.opened_orders_count.safe_sub(1)?;
+            }
+        }
+
+        let mut asks: Vec<OpenedOrder> = legacy_asks
+            .orders
+            .iter()
+            .take(legacy_asks.orders_count as usize)
+            .map(|legacy| OpenedOrder {
+                order_id: legacy.order_id,
+                owner: legacy.owner,
+                price: legacy.price,
+                quantity: legacy.quantity,
+                created_at: legacy.created_at,
+                account_index: 0,
+            })
+            .collect();
+
+        // legacy books rest worst first, order ids grow with placement time
+        bids.sort_by_key(|order| (std::cmp::Reverse(order.price), order.order_id));
+        asks.sort_by_key(|order| (order.price, order.order_id));
+
+        for (info, side, orders) in [
+            (&ctx.accounts.bids_book, Side::Bid, bids),
+            (&ctx.accounts.asks_book, Side::Ask, asks),
+        ] {
+            let orders_count = orders.len() as u64;
+            grow_account(
+                info,
+                &ctx.accounts.authority,
+                &ctx.accounts.system_program,
+                Book::size(market.max_orders_per_book.max(orders_count)),
+            )?;
+            save_account(
+                info,
+                &Book {
+                    side,
+                    market: market.key(),
+                    orders_count,
+                    orders,
+                },
+            )?;
+        }
+
+        market.book_version = BOOK_VERSION;
+
+        for maker in makers.iter() {
+            maker.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct MigrateGlobalPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: legacy layout can't be loaded as `GlobalPool`, it's decoded and grown
+    #[account(
+        mut,
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_pool: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateGlobalPool<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>) -> Result<()> {
+        let legacy: GlobalPoolV0 =
+            load_legacy_account::<GlobalPool, _>(&ctx.accounts.global_pool, GLOBAL_POOL_VERSION)?;
+
+        // check admin authority
+        require!(
+            legacy.admin.eq(&ctx.accounts.admin.key()),
+            HybridDexError::InvalidAdmin
+        );
+
+        let mut global_pool = GlobalPool::from(legacy);
+        grow_account(
+            &ctx.accounts.global_pool,
+            &ctx.accounts.admin,
+            &ctx.accounts.system_program,
+            GlobalPool::DATA_SIZE,
+        )?;
+
+        global_pool.version = GLOBAL_POOL_VERSION;
+
+        save_account(&ctx.accounts.global_pool, &global_pool)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MigrateMarket<'info> {
+    /// anyone can pay to migrate, the layout change doesn't touch market state
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// global pool must be migrated first, its limits backfill the market limits
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    /// CHECK: legacy layout can't be loaded as `Market`, it's decoded and grown
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: UncheckedAccount<'info>,
+
+    /// CHECK: only its allocated size is read, the book keeps its legacy layout until `migrate_books`
+    #[account(
+        seeds = [BID_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub bids_book: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateMarket<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, _seed: u64) -> Result<()> {
+        let global_pool = &ctx.accounts.global_pool;
+
+        let legacy: MarketV0 =
+            load_legacy_account::<Market, _>(&ctx.accounts.market, MARKET_VERSION)?;
+
+        let mut market = Market::from(legacy);
+        grow_account(
+            &ctx.accounts.market,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            Market::DATA_SIZE,
+        )?;
+
+        // legacy markets used the global limits, bounded by what their books were allocated for
+        market.max_orders_per_user = global_pool.max_orders_per_user;
+        market.max_orders_per_book = BookV0::capacity(ctx.accounts.bids_book.data_len())
+            .min(global_pool.max_orders_per_book);
+        market.min_order_value = market.whole_quote(DEFAULT_MIN_ORDER_VALUE)?;
+        market.min_trigger_trade_value = market.whole_quote(DEFAULT_MIN_TRIGGER_TRADE_VALUE)?;
+
+        market.version = MARKET_VERSION;
+
+        save_account(&ctx.accounts.market, &market)
+    }
+}
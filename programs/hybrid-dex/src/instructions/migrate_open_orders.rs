@@ -0,0 +1,36 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct MigrateOpenOrders<'info> {
+    /// anyone can pay to migrate, the layout change doesn't touch balances
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: legacy layout can't be loaded as `UserMarketOrders`,
+    /// owner and discriminator are checked while decoding
+    #[account(mut)]
+    pub user_open_orders: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl MigrateOpenOrders<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>) -> Result<()> {
+        let legacy: UserMarketOrdersV0 = load_legacy_account::<UserMarketOrders, _>(
+            &ctx.accounts.user_open_orders,
+            OPEN_ORDERS_VERSION,
+        )?;
+
+        let mut user_open_orders = UserMarketOrders::from(legacy);
+        grow_account(
+            &ctx.accounts.user_open_orders,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            UserMarketOrders::DATA_SIZE,
+        )?;
+
+        user_open_orders.version = OPEN_ORDERS_VERSION;
+
+        save_account(&ctx.accounts.user_open_orders, &user_open_orders)
+    }
+}
@@ -1,29 +1,81 @@
+pub mod audit_market;
 pub mod cancel_buy_order;
+pub mod cancel_pegged_order;
 pub mod cancel_sell_order;
+pub mod cancel_trigger_order;
 pub mod change_config;
 pub mod close_market;
+pub mod close_open_orders;
 pub mod create_market;
+pub mod create_market_stats;
 pub mod create_open_orders;
+pub mod create_pegged_book;
+pub mod create_trade_history;
+pub mod create_trigger_book;
+pub mod deposit;
+pub mod execute_trigger;
+pub mod get_best_prices;
+pub mod get_user_position;
 pub mod initialize;
+pub mod migrate_books;
+pub mod migrate_global_pool;
+pub mod migrate_market;
+pub mod migrate_open_orders;
 pub mod partial_take_buy_order;
 pub mod partial_take_sell_order;
 pub mod place_buy_order;
+pub mod place_pegged_order;
 pub mod place_sell_order;
+pub mod place_trigger_order;
+pub mod quote_market_order;
+pub mod resize_book;
+pub mod set_candle_intervals;
+pub mod set_delegate;
+pub mod set_oracle;
+pub mod set_order_minimums;
 pub mod take_buy_order;
+pub mod take_pegged_order;
 pub mod take_sell_order;
 pub mod transfer_admin;
+pub mod withdraw;
 
+pub use audit_market::*;
 pub use cancel_buy_order::*;
+pub use cancel_pegged_order::*;
 pub use cancel_sell_order::*;
+pub use cancel_trigger_order::*;
 pub use change_config::*;
 pub use close_market::*;
+pub use close_open_orders::*;
 pub use create_market::*;
+pub use create_market_stats::*;
 pub use create_open_orders::*;
+pub use create_pegged_book::*;
+pub use create_trade_history::*;
+pub use create_trigger_book::*;
+pub use deposit::*;
+pub use execute_trigger::*;
+pub use get_best_prices::*;
+pub use get_user_position::*;
 pub use initialize::*;
+pub use migrate_books::*;
+pub use migrate_global_pool::*;
+pub use migrate_market::*;
+pub use migrate_open_orders::*;
 pub use partial_take_buy_order::*;
 pub use partial_take_sell_order::*;
 pub use place_buy_order::*;
+pub use place_pegged_order::*;
 pub use place_sell_order::*;
+pub use place_trigger_order::*;
+pub use quote_market_order::*;
+pub use resize_book::*;
+pub use set_candle_intervals::*;
+pub use set_delegate::*;
+pub use set_oracle::*;
+pub use set_order_minimums::*;
 pub use take_buy_order::*;
+pub use take_pegged_order::*;
 pub use take_sell_order::*;
 pub use transfer_admin::*;
+pub use withdraw::*;
@@ -11,7 +11,8 @@ pub struct PartialTakeBuyOrder<'info> {
     #[account(mut)]
     pub taker: Signer<'info>,
 
-    pub maker: SystemAccount<'info>,
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub maker: UncheckedAccount<'info>,
 
     #[account(
         mut,
@@ -25,7 +26,12 @@ pub struct PartialTakeBuyOrder<'info> {
     #[account(
         mut,
         has_one = market,
-        seeds = [USER_MARKET_ORDER_SEED.as_bytes(), market.key().as_ref(), maker.key().as_ref()],
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            maker.key().as_ref(),
+            UserMarketOrders::index_seed(&maker_open_orders.account_index),
+        ],
         bump,
         constraint = maker_open_orders.address.eq(&maker.key()) @ HybridDexError::InvalidAccountOwner,
     )]
@@ -34,7 +40,12 @@ pub struct PartialTakeBuyOrder<'info> {
     #[account(
         mut,
         has_one = market,
-        seeds = [USER_MARKET_ORDER_SEED.as_bytes(), market.key().as_ref(), taker.key().as_ref()],
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            taker.key().as_ref(),
+            UserMarketOrders::index_seed(&taker_open_orders.account_index),
+        ],
         bump,
         constraint = taker_open_orders.address.eq(&taker.key()) @ HybridDexError::InvalidAccountOwner,
     )]
@@ -44,6 +55,7 @@ pub struct PartialTakeBuyOrder<'info> {
 
     pub quote_mint: Box<Account<'info, Mint>>,
 
+    /// maker proceeds always go to its ATA, the taker can't pick the destination
     #[account(
         init_if_needed,
         associated_token::mint = base_mint,
@@ -54,16 +66,15 @@ pub struct PartialTakeBuyOrder<'info> {
 
     #[account(
         mut,
-        associated_token::mint = base_mint,
-        associated_token::authority = taker,
+        token::mint = base_mint,
+        token::authority = taker,
     )]
     pub taker_base_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        init_if_needed,
-        associated_token::mint = quote_mint,
-        associated_token::authority = taker,
-        payer = taker,
+        mut,
+        token::mint = quote_mint,
+        token::authority = taker,
     )]
     pub taker_quote_token_account: Box<Account<'info, TokenAccount>>,
 
@@ -81,6 +92,24 @@ pub struct PartialTakeBuyOrder<'info> {
     )]
     pub bids_book: Box<Account<'info, Book>>,
 
+    /// required once the market has one, markets created before trades were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [TRADE_HISTORY_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trade_history: Option<Box<Account<'info, TradeHistory>>>,
+
+    /// required once the market has them, markets created before candles were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [MARKET_STATS_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub market_stats: Option<Box<Account<'info, MarketStats>>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -93,27 +122,48 @@ impl PartialTakeBuyOrder<'_> {
         seed: u64,
         order_id: u64,
         amount: u64,
-    ) -> Result<()> {
+    ) -> Result<Trade> {
+        // check partial take amount
+        require_non_zero(amount)?;
+
         let market = &mut ctx.accounts.market;
         let maker_open_orders = &mut ctx.accounts.maker_open_orders;
         let taker_open_orders = &mut ctx.accounts.taker_open_orders;
 
+        // check bids book holds base quantities
+        require!(
+            market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
+        // check trade history and stats are passed once the market has them
+        market.check_trade_history(&ctx.accounts.trade_history)?;
+        market.check_market_stats(&ctx.accounts.market_stats)?;
+
         let bids_book = &mut ctx.accounts.bids_book;
         let order = bids_book.decrease_order(order_id, amount)?;
 
-        // check maker address against order id
+        // check maker open orders against order id
         require!(
-            order.owner.eq(&ctx.accounts.maker.key()),
+            maker_open_orders.is_order_owner(&order),
             HybridDexError::IncorrectMakerAddress
         );
 
-        // price should have quote_decimal value
-        let base_amount = (amount as u128 * ((10 as u128).pow(market.base_decimal as u32))
-            / order.price as u128) as u64;
+        // taker gets the quote value rounded down, the order releases the difference
+        // of its locked quote and rounding dust returns to maker
+        let (base_amount, quote_amount) =
+            fill_amounts(Side::Bid, amount, order.price, market.base_decimal)?;
+        let released_amount = locked_quote(
+            order.quantity.safe_add(amount)?,
+            order.price,
+            market.base_decimal,
+        )?
+        .safe_sub(market.bid_locked_quote(&order)?)?;
+        let dust_amount = released_amount.safe_sub(quote_amount)?;
 
         // check quote token vault balance
         require!(
-            ctx.accounts.quote_vault_account.amount >= amount,
+            ctx.accounts.quote_vault_account.amount >= quote_amount,
             HybridDexError::InsufficientWithdrawBalance
         );
 
@@ -123,15 +173,41 @@ impl PartialTakeBuyOrder<'_> {
             HybridDexError::InsufficientDepositBalance
         );
 
-        maker_open_orders.quote_deposit_total -= amount;
-        maker_open_orders.base_total_volume += base_amount;
-        maker_open_orders.quote_total_volume += amount;
-
-        taker_open_orders.base_total_volume += base_amount;
-        taker_open_orders.quote_total_volume += amount;
-
-        market.base_total_volume += base_amount;
-        market.quote_total_volume += amount;
+        maker_open_orders.quote_deposit_total = maker_open_orders
+            .quote_deposit_total
+            .safe_sub(released_amount)?;
+        maker_open_orders.quote_free = maker_open_orders.quote_free.safe_add(dust_amount)?;
+        maker_open_orders.base_total_volume =
+            maker_open_orders.base_total_volume.safe_add(base_amount)?;
+        maker_open_orders.quote_total_volume = maker_open_orders
+            .quote_total_volume
+            .safe_add(quote_amount)?;
+
+        taker_open_orders.base_total_volume =
+            taker_open_orders.base_total_volume.safe_add(base_amount)?;
+        taker_open_orders.quote_total_volume = taker_open_orders
+            .quote_total_volume
+            .safe_add(quote_amount)?;
+
+        market.base_total_volume = market.base_total_volume.safe_add(base_amount)?;
+        market.quote_total_volume = market.quote_total_volume.safe_add(quote_amount)?;
+
+        // record fill in market trade history and stats
+        let now = Clock::get()?.unix_timestamp;
+        let mut trade = Trade::new(
+            &order,
+            base_amount,
+            quote_amount,
+            Side::Ask,
+            ctx.accounts.taker.key(),
+            now,
+        );
+        if let Some(trade_history) = ctx.accounts.trade_history.as_mut() {
+            trade = trade_history.record(trade);
+        }
+        if let Some(market_stats) = ctx.accounts.market_stats.as_mut() {
+            market_stats.record_fill(order.price, base_amount, quote_amount, now)?;
+        }
 
         let seed_bytes = seed.to_le_bytes();
         let seeds = &[MARKET_SEED.as_bytes(), &seed_bytes, &[ctx.bumps.market]];
@@ -150,7 +226,7 @@ impl PartialTakeBuyOrder<'_> {
                 cpi_accounts,
                 signers_seeds,
             ),
-            amount,
+            quote_amount,
         )?;
 
         // transfer base token from taker to maker
@@ -165,6 +241,6 @@ impl PartialTakeBuyOrder<'_> {
             base_amount,
         )?;
 
-        Ok(())
+        Ok(trade)
     }
 }
@@ -11,7 +11,8 @@ pub struct PartialTakeSellOrder<'info> {
     #[account(mut)]
     pub taker: Signer<'info>,
 
-    pub maker: SystemAccount<'info>,
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub maker: UncheckedAccount<'info>,
 
     #[account(
         mut,
@@ -25,7 +26,12 @@ pub struct PartialTakeSellOrder<'info> {
     #[account(
         mut,
         has_one = market,
-        seeds = [USER_MARKET_ORDER_SEED.as_bytes(), market.key().as_ref(), maker.key().as_ref()],
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            maker.key().as_ref(),
+            UserMarketOrders::index_seed(&maker_open_orders.account_index),
+        ],
         bump,
         constraint = maker_open_orders.address.eq(&maker.key()) @ HybridDexError::InvalidAccountOwner,
     )]
@@ -34,7 +40,12 @@ pub struct PartialTakeSellOrder<'info> {
     #[account(
         mut,
         has_one = market,
-        seeds = [USER_MARKET_ORDER_SEED.as_bytes(), market.key().as_ref(), taker.key().as_ref()],
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            taker.key().as_ref(),
+            UserMarketOrders::index_seed(&taker_open_orders.account_index),
+        ],
         bump,
         constraint = taker_open_orders.address.eq(&taker.key()) @ HybridDexError::InvalidAccountOwner,
     )]
@@ -44,6 +55,7 @@ pub struct PartialTakeSellOrder<'info> {
 
     pub quote_mint: Box<Account<'info, Mint>>,
 
+    /// maker proceeds always go to its ATA, the taker can't pick the destination
     #[account(
         init_if_needed,
         associated_token::mint = quote_mint,
@@ -53,17 +65,16 @@ pub struct PartialTakeSellOrder<'info> {
     pub maker_quote_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
-        init_if_needed,
-        associated_token::mint = base_mint,
-        associated_token::authority = taker,
-        payer = taker,
+        mut,
+        token::mint = base_mint,
+        token::authority = taker,
     )]
     pub taker_base_token_account: Box<Account<'info, TokenAccount>>,
 
     #[account(
         mut,
-        associated_token::mint = quote_mint,
-        associated_token::authority = taker,
+        token::mint = quote_mint,
+        token::authority = taker,
     )]
     pub taker_quote_token_account: Box<Account<'info, TokenAccount>>,
 
@@ -81,6 +92,24 @@ pub struct PartialTakeSellOrder<'info> {
     )]
     pub asks_book: Box<Account<'info, Book>>,
 
+    /// required once the market has one, markets created before trades were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [TRADE_HISTORY_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trade_history: Option<Box<Account<'info, TradeHistory>>>,
+
+    /// required once the market has them, markets created before candles were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [MARKET_STATS_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub market_stats: Option<Box<Account<'info, MarketStats>>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -93,7 +122,24 @@ impl PartialTakeSellOrder<'_> {
         seed: u64,
         order_id: u64,
         amount: u64,
-    ) -> Result<()> {
+    ) -> Result<Trade> {
+        // check partial take amount
+        require_non_zero(amount)?;
+
+        // check books use the current format
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
+        // check trade history and stats are passed once the market has them
+        ctx.accounts
+            .market
+            .check_trade_history(&ctx.accounts.trade_history)?;
+        ctx.accounts
+            .market
+            .check_market_stats(&ctx.accounts.market_stats)?;
+
         let market = &mut ctx.accounts.market;
         let maker_open_orders = &mut ctx.accounts.maker_open_orders;
         let taker_open_orders = &mut ctx.accounts.taker_open_orders;
@@ -101,15 +147,14 @@ impl PartialTakeSellOrder<'_> {
         let asks_book = &mut ctx.accounts.asks_book;
         let order = asks_book.decrease_order(order_id, amount)?;
 
-        // check maker address against order id
+        // check maker open orders against order id
         require!(
-            order.owner.eq(&ctx.accounts.maker.key()),
+            maker_open_orders.is_order_owner(&order),
             HybridDexError::IncorrectMakerAddress
         );
 
-        // price should have quote_decimal value
-        let quote_amount = (amount as u128 * order.price as u128
-            / ((10 as u128).pow(market.base_decimal as u32))) as u64;
+        // taker pays the quote value rounded up in the maker's favor
+        let (_, quote_amount) = fill_amounts(Side::Ask, amount, order.price, market.base_decimal)?;
 
         // check base token vault balance
         require!(
@@ -123,15 +168,39 @@ impl PartialTakeSellOrder<'_> {
             HybridDexError::InsufficientDepositBalance
         );
 
-        maker_open_orders.base_deposit_total -= amount;
-        maker_open_orders.base_total_volume += amount;
-        maker_open_orders.quote_total_volume += quote_amount;
-
-        taker_open_orders.base_total_volume += amount;
-        taker_open_orders.quote_total_volume += quote_amount;
-
-        market.base_total_volume += amount;
-        market.quote_total_volume += quote_amount;
+        maker_open_orders.base_deposit_total =
+            maker_open_orders.base_deposit_total.safe_sub(amount)?;
+        maker_open_orders.base_total_volume =
+            maker_open_orders.base_total_volume.safe_add(amount)?;
+        maker_open_orders.quote_total_volume = maker_open_orders
+            .quote_total_volume
+            .safe_add(quote_amount)?;
+
+        taker_open_orders.base_total_volume =
+            taker_open_orders.base_total_volume.safe_add(amount)?;
+        taker_open_orders.quote_total_volume = taker_open_orders
+            .quote_total_volume
+            .safe_add(quote_amount)?;
+
+        market.base_total_volume = market.base_total_volume.safe_add(amount)?;
+        market.quote_total_volume = market.quote_total_volume.safe_add(quote_amount)?;
+
+        // record fill in market trade history and stats
+        let now = Clock::get()?.unix_timestamp;
+        let mut trade = Trade::new(
+            &order,
+            amount,
+            quote_amount,
+            Side::Bid,
+            ctx.accounts.taker.key(),
+            now,
+        );
+        if let Some(trade_history) = ctx.accounts.trade_history.as_mut() {
+            trade = trade_history.record(trade);
+        }
+        if let Some(market_stats) = ctx.accounts.market_stats.as_mut() {
+            market_stats.record_fill(order.price, amount, quote_amount, now)?;
+        }
 
         let seed_bytes = seed.to_le_bytes();
         let seeds = &[MARKET_SEED.as_bytes(), &seed_bytes, &[ctx.bumps.market]];
@@ -165,6 +234,6 @@ impl PartialTakeSellOrder<'_> {
             quote_amount,
         )?;
 
-        Ok(())
+        Ok(trade)
     }
 }
@@ -7,14 +7,12 @@ use crate::*;
 
 #[derive(Accounts)]
 pub struct PlaceBuyOrder<'info> {
+    /// open orders owner or its delegate
     #[account(mut)]
-    pub maker: Signer<'info>,
+    pub authority: Signer<'info>,
 
-    #[account(
-        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
-        bump,
-    )]
-    pub global_pool: Box<Account<'info, GlobalPool>>,
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub maker: UncheckedAccount<'info>,
 
     #[account(
         mut,
@@ -26,9 +24,15 @@ pub struct PlaceBuyOrder<'info> {
     #[account(
         mut,
         has_one = market,
-        seeds = [USER_MARKET_ORDER_SEED.as_bytes(), market.key().as_ref(), maker.key().as_ref()],
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            maker.key().as_ref(),
+            UserMarketOrders::index_seed(&user_open_orders.account_index),
+        ],
         bump,
         constraint = user_open_orders.address.eq(&maker.key()) @ HybridDexError::InvalidAccountOwner,
+        constraint = user_open_orders.is_authorized(&authority.key()) @ HybridDexError::InvalidDelegate,
     )]
     pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
 
@@ -38,8 +42,8 @@ pub struct PlaceBuyOrder<'info> {
 
     #[account(
         mut,
-        associated_token::mint = quote_mint,
-        associated_token::authority = maker,
+        token::mint = quote_mint,
+        token::authority = maker,
     )]
     pub user_quote_token_account: Box<Account<'info, TokenAccount>>,
 
@@ -47,7 +51,7 @@ pub struct PlaceBuyOrder<'info> {
         init_if_needed,
         associated_token::mint = quote_mint,
         associated_token::authority = market,
-        payer = maker,
+        payer = authority,
     )]
     pub quote_vault_account: Box<Account<'info, TokenAccount>>,
 
@@ -58,6 +62,9 @@ pub struct PlaceBuyOrder<'info> {
     )]
     pub bids_book: Box<Account<'info, Book>>,
 
+    /// CHECK: market oracle, required once the market has a price band
+    pub oracle: Option<UncheckedAccount<'info>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -65,22 +72,41 @@ pub struct PlaceBuyOrder<'info> {
 }
 
 impl PlaceBuyOrder<'_> {
-    pub fn process_instruction(ctx: &mut Context<Self>, price: u64, quantity: u64) -> Result<()> {
-        let global_pool = &ctx.accounts.global_pool;
+    pub fn process_instruction(ctx: &mut Context<Self>, price: u64, quantity: u64) -> Result<u64> {
+        // check order price and quantity
+        require_non_zero(price)?;
+        require_non_zero(quantity)?;
+
+        // check bids book holds base quantities
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
+        // check price against the oracle band
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts
+            .market
+            .check_price_band(ctx.accounts.oracle.as_deref(), price, now)?;
+
         let market = &mut ctx.accounts.market;
         let user_open_orders = &mut ctx.accounts.user_open_orders;
 
+        // quantity is base amount, the bid locks its quote value
+        let locked_amount = locked_quote(quantity, price, market.base_decimal)?;
+
         let new_order = OpenedOrder {
             order_id: market.order_seq_num,
             owner: ctx.accounts.maker.key(),
             price,
             quantity,
-            created_at: Clock::get().unwrap().unix_timestamp,
+            created_at: now,
+            account_index: user_open_orders.account_index,
         };
 
         // check max user opened orders
         require!(
-            user_open_orders.opened_orders_count < global_pool.max_orders_per_user,
+            user_open_orders.opened_orders_count < market.max_orders_per_user,
             HybridDexError::OpenOrdersFull
         );
 
@@ -88,36 +114,45 @@ impl PlaceBuyOrder<'_> {
 
         // check max market order book
         require!(
-            bids_book.orders_count < global_pool.max_orders_per_book,
+            bids_book.orders_count < market.max_orders_per_book,
             HybridDexError::OpenOrdersFull
         );
 
         bids_book.insert_order(new_order);
-        bids_book.orders_count += 1;
+        bids_book.orders_count = bids_book.orders_count.safe_add(1)?;
+
+        // lock free balance first, only the rest comes from user quote token account
+        let free_amount = locked_amount.min(user_open_orders.quote_free);
+        let transfer_amount = locked_amount.safe_sub(free_amount)?;
 
         // check user quote token balance
         require!(
-            ctx.accounts.user_quote_token_account.amount >= quantity,
+            ctx.accounts.user_quote_token_account.amount >= transfer_amount,
             HybridDexError::InsufficientDepositBalance
         );
 
-        user_open_orders.opened_orders_count += 1;
-        user_open_orders.quote_deposit_total += quantity;
-
-        // transfer quote token to vault
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.user_quote_token_account.to_account_info(),
-            to: ctx.accounts.quote_vault_account.to_account_info(),
-            authority: ctx.accounts.maker.to_account_info(),
-        };
-
-        token::transfer(
-            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
-            quantity,
-        )?;
-
-        market.order_seq_num += 1;
-
-        Ok(())
+        user_open_orders.opened_orders_count = user_open_orders.opened_orders_count.safe_add(1)?;
+        user_open_orders.quote_free = user_open_orders.quote_free.safe_sub(free_amount)?;
+        user_open_orders.quote_deposit_total = user_open_orders
+            .quote_deposit_total
+            .safe_add(locked_amount)?;
+
+        if transfer_amount > 0 {
+            // transfer quote token to vault, delegate must be approved on maker token account
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.user_quote_token_account.to_account_info(),
+                to: ctx.accounts.quote_vault_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            };
+
+            token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                transfer_amount,
+            )?;
+        }
+
+        market.order_seq_num = market.order_seq_num.safe_add(1)?;
+
+        Ok(new_order.order_id)
     }
 }
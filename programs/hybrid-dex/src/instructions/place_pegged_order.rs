@@ -0,0 +1,191 @@
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::*;
+
+#[derive(Accounts)]
+pub struct PlacePeggedOrder<'info> {
+    /// open orders owner or its delegate
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            maker.key().as_ref(),
+            UserMarketOrders::index_seed(&user_open_orders.account_index),
+        ],
+        bump,
+        constraint = user_open_orders.address.eq(&maker.key()) @ HybridDexError::InvalidAccountOwner,
+        constraint = user_open_orders.is_authorized(&authority.key()) @ HybridDexError::InvalidDelegate,
+    )]
+    pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = maker,
+    )]
+    pub user_base_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = maker,
+    )]
+    pub user_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// both vaults exist once a pegged order rests, takes and cancels rely on it
+    #[account(
+        init_if_needed,
+        associated_token::mint = base_mint,
+        associated_token::authority = market,
+        payer = authority,
+    )]
+    pub base_vault_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = quote_mint,
+        associated_token::authority = market,
+        payer = authority,
+    )]
+    pub quote_vault_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [PEGGED_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub pegged_book: Box<Account<'info, PeggedBook>>,
+
+    /// CHECK: market oracle, checked against the market price source
+    pub oracle: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl PlacePeggedOrder<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        side: Side,
+        price_offset: i64,
+        limit_price: u64,
+        quantity: u64,
+    ) -> Result<u64> {
+        // check order quantity
+        require_non_zero(quantity)?;
+
+        // check order caps its price, bids also lock quote value at it
+        require!(limit_price > 0, HybridDexError::PeggedLimitRequired);
+
+        let market = &mut ctx.accounts.market;
+        let user_open_orders = &mut ctx.accounts.user_open_orders;
+
+        let now = Clock::get()?.unix_timestamp;
+        let new_order = PeggedOrder {
+            order_id: market.order_seq_num,
+            owner: ctx.accounts.maker.key(),
+            account_index: user_open_orders.account_index,
+            side,
+            price_offset,
+            limit_price,
+            quantity,
+            created_at: now,
+        };
+
+        // check current pegged price against the oracle band
+        let oracle = ctx.accounts.oracle.to_account_info();
+        let price = new_order.price(market.oracle_price(&oracle, now)?)?;
+        market.check_price_band(Some(&oracle), price, now)?;
+        market.check_order_value(quantity, price)?;
+
+        // check max user opened orders, pegged orders count with book orders
+        require!(
+            user_open_orders.opened_orders_count < market.max_orders_per_user,
+            HybridDexError::OpenOrdersFull
+        );
+
+        ctx.accounts.pegged_book.insert_order(new_order)?;
+
+        // lock free balance first, only the rest comes from user token account
+        let locked_amount = new_order.locked_amount(quantity, market.base_decimal)?;
+        let (free, deposit_total, user_token_account, vault_account) = match side {
+            Side::Bid => (
+                user_open_orders.quote_free,
+                user_open_orders.quote_deposit_total,
+                &ctx.accounts.user_quote_token_account,
+                &ctx.accounts.quote_vault_account,
+            ),
+            Side::Ask => (
+                user_open_orders.base_free,
+                user_open_orders.base_deposit_total,
+                &ctx.accounts.user_base_token_account,
+                &ctx.accounts.base_vault_account,
+            ),
+        };
+        let free_amount = locked_amount.min(free);
+        let transfer_amount = locked_amount.safe_sub(free_amount)?;
+
+        // check user token balance
+        require!(
+            user_token_account.amount >= transfer_amount,
+            HybridDexError::InsufficientDepositBalance
+        );
+
+        let free = free.safe_sub(free_amount)?;
+        let deposit_total = deposit_total.safe_add(locked_amount)?;
+        match side {
+            Side::Bid => {
+                user_open_orders.quote_free = free;
+                user_open_orders.quote_deposit_total = deposit_total;
+            }
+            Side::Ask => {
+                user_open_orders.base_free = free;
+                user_open_orders.base_deposit_total = deposit_total;
+            }
+        }
+        user_open_orders.opened_orders_count = user_open_orders.opened_orders_count.safe_add(1)?;
+
+        if transfer_amount > 0 {
+            // transfer locked token to vault, delegate must be approved on maker token account
+            let cpi_accounts = Transfer {
+                from: user_token_account.to_account_info(),
+                to: vault_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            };
+
+            token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+                transfer_amount,
+            )?;
+        }
+
+        market.order_seq_num = market.order_seq_num.safe_add(1)?;
+
+        Ok(new_order.order_id)
+    }
+}
@@ -0,0 +1,150 @@
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::*;
+
+/// Trigger order terms chosen by its owner
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct TriggerOrderParams {
+    pub side: Side,
+    pub source: TriggerSource,
+    pub condition: TriggerCondition,
+    pub order_type: TriggerOrderType,
+    pub trigger_price: u64,
+    pub limit_price: u64,
+    pub quantity: u64,
+}
+
+#[derive(Accounts)]
+pub struct PlaceTriggerOrder<'info> {
+    /// open orders owner or its delegate, pays the keeper bounty
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            maker.key().as_ref(),
+            UserMarketOrders::index_seed(&user_open_orders.account_index),
+        ],
+        bump,
+        constraint = user_open_orders.address.eq(&maker.key()) @ HybridDexError::InvalidAccountOwner,
+        constraint = user_open_orders.is_authorized(&authority.key()) @ HybridDexError::InvalidDelegate,
+    )]
+    pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [TRIGGER_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trigger_book: Box<Account<'info, TriggerBook>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl PlaceTriggerOrder<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, params: TriggerOrderParams) -> Result<u64> {
+        // check trigger price and quantity
+        require_non_zero(params.trigger_price)?;
+        require_non_zero(params.quantity)?;
+
+        // check limit price of orders resting in the book and of bids locking quote
+        require!(
+            params.limit_price > 0
+                || (params.order_type == TriggerOrderType::Market && params.side == Side::Ask),
+            HybridDexError::TriggerLimitRequired
+        );
+
+        let market = &mut ctx.accounts.market;
+        let user_open_orders = &mut ctx.accounts.user_open_orders;
+
+        // check oracle triggers have a price to watch
+        require!(
+            params.source == TriggerSource::LastPrice || market.oracle_kind != OracleKind::None,
+            HybridDexError::InvalidOracleAccount
+        );
+
+        // check order value at the price it fills at, market asks without limit at the trigger
+        let price = match params.limit_price {
+            0 => params.trigger_price,
+            limit_price => limit_price,
+        };
+        market.check_order_value(params.quantity, price)?;
+
+        // check max user opened orders, trigger orders count with book orders
+        require!(
+            user_open_orders.opened_orders_count < market.max_orders_per_user,
+            HybridDexError::OpenOrdersFull
+        );
+
+        let new_order = TriggerOrder {
+            order_id: market.order_seq_num,
+            owner: ctx.accounts.maker.key(),
+            account_index: user_open_orders.account_index,
+            side: params.side,
+            source: params.source,
+            condition: params.condition,
+            order_type: params.order_type,
+            trigger_price: params.trigger_price,
+            limit_price: params.limit_price,
+            quantity: params.quantity,
+            bounty: TRIGGER_KEEPER_BOUNTY,
+            created_at: Clock::get()?.unix_timestamp,
+        };
+        ctx.accounts.trigger_book.insert_order(new_order)?;
+
+        // escrow funds from free balance, they stay in the vault until the trigger fires
+        let locked_amount = new_order.locked_amount(params.quantity, market.base_decimal)?;
+        match params.side {
+            Side::Bid => {
+                // check user quote free balance
+                require!(
+                    user_open_orders.quote_free >= locked_amount,
+                    HybridDexError::InsufficientFreeBalance
+                );
+                user_open_orders.quote_free =
+                    user_open_orders.quote_free.safe_sub(locked_amount)?;
+                user_open_orders.quote_deposit_total = user_open_orders
+                    .quote_deposit_total
+                    .safe_add(locked_amount)?;
+            }
+            Side::Ask => {
+                // check user base free balance
+                require!(
+                    user_open_orders.base_free >= locked_amount,
+                    HybridDexError::InsufficientFreeBalance
+                );
+                user_open_orders.base_free = user_open_orders.base_free.safe_sub(locked_amount)?;
+                user_open_orders.base_deposit_total = user_open_orders
+                    .base_deposit_total
+                    .safe_add(locked_amount)?;
+            }
+        }
+        user_open_orders.opened_orders_count = user_open_orders.opened_orders_count.safe_add(1)?;
+
+        // keeper bounty waits in the trigger book
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.trigger_book.to_account_info(),
+        };
+
+        system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts),
+            new_order.bounty,
+        )?;
+
+        market.order_seq_num = market.order_seq_num.safe_add(1)?;
+
+        Ok(new_order.order_id)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::*;
+
+/// Amounts a market order would trade taking resting orders one by one
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MarketOrderQuote {
+    /// Base the book covers, below the requested amount on a thin book
+    pub base_amount: u64,
+    /// Quote the taker receives selling to bids or pays buying from asks
+    pub quote_amount: u64,
+    pub orders_filled: u64,
+    /// Price of the last order filled, zero when nothing fills
+    pub worst_price: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct QuoteMarketOrder<'info> {
+    #[account(
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [BID_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub bids_book: Box<Account<'info, Book>>,
+
+    #[account(
+        seeds = [ASK_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub asks_book: Box<Account<'info, Book>>,
+}
+
+impl QuoteMarketOrder<'_> {
+    /// `side` is the taker side, a bid buys `amount` base from asks
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        _seed: u64,
+        side: Side,
+        amount: u64,
+    ) -> Result<MarketOrderQuote> {
+        // check quoted amount
+        require_non_zero(amount)?;
+
+        // check bids book holds base quantities
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
+        let (maker_side, book) = match side {
+            Side::Bid => (Side::Ask, &ctx.accounts.asks_book),
+            Side::Ask => (Side::Bid, &ctx.accounts.bids_book),
+        };
+
+        let quote = hybrid_dex_core::quote_market_order(
+            maker_side.into(),
+            &book.orders,
+            book.orders_count,
+            amount,
+            ctx.accounts.market.base_decimal,
+        )
+        .map_err(HybridDexError::from)?;
+
+        Ok(MarketOrderQuote {
+            base_amount: quote.base_amount,
+            quote_amount: quote.quote_amount,
+            orders_filled: quote.orders_filled,
+            worst_price: quote.worst_price,
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64, max_orders_per_book: u64)]
+pub struct ResizeBook<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_pool: Box<Account<'info, GlobalPool>>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+        constraint = market.market_authority.eq(&authority.key()) @ HybridDexError::InvalidMarketAuthority,
+        // checked before the books realloc, which fails past the runtime growth limit
+        constraint = max_orders_per_book.saturating_sub(market.max_orders_per_book) <= MAX_BOOK_GROWTH
+            @ HybridDexError::BookGrowthExceeded,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [BID_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        realloc = Book::size(max_orders_per_book),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub bids_book: Box<Account<'info, Book>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [ASK_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+        realloc = Book::size(max_orders_per_book),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub asks_book: Box<Account<'info, Book>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl ResizeBook<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        _seed: u64,
+        max_orders_per_book: u64,
+    ) -> Result<()> {
+        // check books use the current format
+        require!(
+            ctx.accounts.market.is_book_current(),
+            HybridDexError::BookMigrationRequired
+        );
+
+        let global_pool = &ctx.accounts.global_pool;
+        let market = &mut ctx.accounts.market;
+
+        // books can only grow, shrinking would cut resting orders
+        require!(
+            max_orders_per_book > market.max_orders_per_book
+                && max_orders_per_book <= global_pool.max_orders_per_book,
+            HybridDexError::InvalidOrderLimit
+        );
+
+        market.max_orders_per_book = max_orders_per_book;
+
+        Ok(())
+    }
+}
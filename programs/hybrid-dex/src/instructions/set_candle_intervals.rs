@@ -0,0 +1,33 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct SetCandleIntervals<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+        constraint = market.market_authority.eq(&authority.key()) @ HybridDexError::InvalidMarketAuthority,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [MARKET_STATS_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub market_stats: Box<Account<'info, MarketStats>>,
+}
+
+impl SetCandleIntervals<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        _seed: u64,
+        intervals: Vec<i64>,
+    ) -> Result<()> {
+        // candles of replaced series are dropped, last price and volume are kept
+        ctx.accounts.market_stats.set_candle_intervals(&intervals)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::*;
+
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            user.key().as_ref(),
+            UserMarketOrders::index_seed(&user_open_orders.account_index),
+        ],
+        bump,
+        constraint = user_open_orders.address.eq(&user.key()) @ HybridDexError::InvalidAccountOwner,
+    )]
+    pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
+}
+
+impl SetDelegate<'_> {
+    pub fn process_instruction(ctx: &mut Context<Self>, delegate: Option<Pubkey>) -> Result<()> {
+        let user_open_orders = &mut ctx.accounts.user_open_orders;
+
+        user_open_orders.delegate = delegate.unwrap_or_default();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct SetOracle<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+        constraint = market.market_authority.eq(&authority.key()) @ HybridDexError::InvalidMarketAuthority,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    /// CHECK: price account of `oracle_kind`, read once to validate it
+    pub oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Raydium CLMM pool owning the `oracle` observations, read once to check its mints
+    pub oracle_pool: Option<UncheckedAccount<'info>>,
+}
+
+impl SetOracle<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        _seed: u64,
+        oracle_kind: OracleKind,
+        price_band_bps: u16,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        if oracle_kind == OracleKind::None {
+            market.oracle_kind = OracleKind::None;
+            market.oracle = Pubkey::default();
+            market.price_band_bps = 0;
+            return Ok(());
+        }
+
+        // check price band width
+        require!(
+            price_band_bps > 0 && price_band_bps <= MAX_PRICE_BAND_BPS,
+            HybridDexError::InvalidPriceBand
+        );
+
+        let oracle = ctx
+            .accounts
+            .oracle
+            .as_ref()
+            .ok_or(HybridDexError::InvalidOracleAccount)?;
+
+        // check observations belong to a pool of the market mints
+        if oracle_kind == OracleKind::RaydiumClmm {
+            let pool = ctx
+                .accounts
+                .oracle_pool
+                .as_ref()
+                .ok_or(HybridDexError::InvalidOracleAccount)?;
+            require!(
+                pool.owner == &RAYDIUM_CLMM_PROGRAM_ID,
+                HybridDexError::InvalidOracleAccount
+            );
+            require!(
+                oracle.key() == market.clmm_observation(&pool.try_borrow_data()?)?,
+                HybridDexError::InvalidOracleAccount
+            );
+        }
+
+        market.oracle_kind = oracle_kind;
+        market.oracle = oracle.key();
+        market.price_band_bps = price_band_bps;
+
+        // check oracle holds a current price of the market
+        market.oracle_price(oracle, Clock::get()?.unix_timestamp)?;
+
+        Ok(())
+    }
+}
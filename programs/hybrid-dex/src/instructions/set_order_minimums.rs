@@ -0,0 +1,36 @@
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct SetOrderMinimums<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+        constraint = market.market_authority.eq(&authority.key()) @ HybridDexError::InvalidMarketAuthority,
+    )]
+    pub market: Box<Account<'info, Market>>,
+}
+
+impl SetOrderMinimums<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        _seed: u64,
+        min_order_value: u64,
+        min_trigger_trade_value: u64,
+    ) -> Result<()> {
+        // resting orders keep their value, only new ones are checked against it
+        require!(
+            min_order_value > 0 && min_trigger_trade_value > 0,
+            HybridDexError::InvalidOrderMinimum
+        );
+
+        let market = &mut ctx.accounts.market;
+        market.min_order_value = min_order_value;
+        market.min_trigger_trade_value = min_trigger_trade_value;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct TakePeggedOrder<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub maker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = base_mint,
+        has_one = quote_mint,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            maker.key().as_ref(),
+            UserMarketOrders::index_seed(&maker_open_orders.account_index),
+        ],
+        bump,
+        constraint = maker_open_orders.address.eq(&maker.key()) @ HybridDexError::InvalidAccountOwner,
+    )]
+    pub maker_open_orders: Box<Account<'info, UserMarketOrders>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            taker.key().as_ref(),
+            UserMarketOrders::index_seed(&taker_open_orders.account_index),
+        ],
+        bump,
+        constraint = taker_open_orders.address.eq(&taker.key()) @ HybridDexError::InvalidAccountOwner,
+    )]
+    pub taker_open_orders: Box<Account<'info, UserMarketOrders>>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    /// maker proceeds always go to its ATA, the taker can't pick the destination
+    #[account(
+        init_if_needed,
+        associated_token::mint = base_mint,
+        associated_token::authority = maker,
+        payer = taker,
+    )]
+    pub maker_base_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = quote_mint,
+        associated_token::authority = maker,
+        payer = taker,
+    )]
+    pub maker_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = taker,
+    )]
+    pub taker_base_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = taker,
+    )]
+    pub taker_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = market,
+    )]
+    pub base_vault_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = market,
+    )]
+    pub quote_vault_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [PEGGED_BOOK_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub pegged_book: Box<Account<'info, PeggedBook>>,
+
+    /// required once the market has one, markets created before trades were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [TRADE_HISTORY_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub trade_history: Option<Box<Account<'info, TradeHistory>>>,
+
+    /// required once the market has them, markets created before candles were recorded have none
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [MARKET_STATS_SEED.as_bytes(), market.key().as_ref()],
+        bump,
+    )]
+    pub market_stats: Option<Box<Account<'info, MarketStats>>>,
+
+    /// CHECK: market oracle, checked against the market price source
+    pub oracle: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl TakePeggedOrder<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        seed: u64,
+        order_id: u64,
+        amount: u64,
+        limit_price: u64,
+    ) -> Result<Trade> {
+        // check take amount and the taker cap on the oracle price
+        require_non_zero(amount)?;
+        require!(limit_price > 0, HybridDexError::PeggedLimitRequired);
+
+        let market = &mut ctx.accounts.market;
+        let maker_open_orders = &mut ctx.accounts.maker_open_orders;
+        let taker_open_orders = &mut ctx.accounts.taker_open_orders;
+
+        // check trade history and stats are passed once the market has them
+        market.check_trade_history(&ctx.accounts.trade_history)?;
+        market.check_market_stats(&ctx.accounts.market_stats)?;
+
+        let order = ctx.accounts.pegged_book.get_order(order_id)?;
+
+        // check maker open orders against order id
+        require!(
+            maker_open_orders.is_pegged_order_owner(&order),
+            HybridDexError::IncorrectMakerAddress
+        );
+
+        // order price follows the oracle at take time
+        let now = Clock::get()?.unix_timestamp;
+        let price = order.price(market.oracle_price(&ctx.accounts.oracle, now)?)?;
+
+        // check pegged price against the taker limit
+        require!(
+            match order.side {
+                Side::Bid => price >= limit_price,
+                Side::Ask => price <= limit_price,
+            },
+            HybridDexError::PeggedPriceBeyondLimit
+        );
+
+        ctx.accounts.pegged_book.fill_order(order_id, amount)?;
+        let remaining = order.quantity.safe_sub(amount)?;
+        if remaining == 0 {
+            maker_open_orders.opened_orders_count =
+                maker_open_orders.opened_orders_count.safe_sub(1)?;
+        }
+
+        // quote side rounds in the maker's favor, a bid releases the difference of its
+        // quote locked at the limit and the rest returns to maker free balance
+        let (base_amount, quote_amount) =
+            fill_amounts(order.side, amount, price, market.base_decimal)?;
+        let taker_side = match order.side {
+            Side::Bid => {
+                let released_amount = order
+                    .locked_amount(order.quantity, market.base_decimal)?
+                    .safe_sub(order.locked_amount(remaining, market.base_decimal)?)?;
+                let excess_amount = released_amount.safe_sub(quote_amount)?;
+
+                // check quote token vault balance
+                require!(
+                    ctx.accounts.quote_vault_account.amount >= quote_amount,
+                    HybridDexError::InsufficientWithdrawBalance
+                );
+
+                // check taker base token balance
+                require!(
+                    ctx.accounts.taker_base_token_account.amount >= base_amount,
+                    HybridDexError::InsufficientDepositBalance
+                );
+
+                maker_open_orders.quote_deposit_total = maker_open_orders
+                    .quote_deposit_total
+                    .safe_sub(released_amount)?;
+                maker_open_orders.quote_free =
+                    maker_open_orders.quote_free.safe_add(excess_amount)?;
+
+                Side::Ask
+            }
+            Side::Ask => {
+                // check base token vault balance
+                require!(
+                    ctx.accounts.base_vault_account.amount >= base_amount,
+                    HybridDexError::InsufficientWithdrawBalance
+                );
+
+                // check taker quote token balance
+                require!(
+                    ctx.accounts.taker_quote_token_account.amount >= quote_amount,
+                    HybridDexError::InsufficientDepositBalance
+                );
+
+                maker_open_orders.base_deposit_total =
+                    maker_open_orders.base_deposit_total.safe_sub(base_amount)?;
+
+                Side::Bid
+            }
+        };
+
+        maker_open_orders.base_total_volume =
+            maker_open_orders.base_total_volume.safe_add(base_amount)?;
+        maker_open_orders.quote_total_volume = maker_open_orders
+            .quote_total_volume
+            .safe_add(quote_amount)?;
+
+        taker_open_orders.base_total_volume =
+            taker_open_orders.base_total_volume.safe_add(base_amount)?;
+        taker_open_orders.quote_total_volume = taker_open_orders
+            .quote_total_volume
+            .safe_add(quote_amount)?;
+
+        market.base_total_volume = market.base_total_volume.safe_add(base_amount)?;
+        market.quote_total_volume = market.quote_total_volume.safe_add(quote_amount)?;
+
+        // record fill at the pegged price in market trade history and stats
+        let mut trade = Trade::new(
+            &order.at_price(price),
+            base_amount,
+            quote_amount,
+            taker_side,
+            ctx.accounts.taker.key(),
+            now,
+        );
+        if let Some(trade_history) = ctx.accounts.trade_history.as_mut() {
+            trade = trade_history.record(trade);
+        }
+        if let Some(market_stats) = ctx.accounts.market_stats.as_mut() {
+            market_stats.record_fill(price, base_amount, quote_amount, now)?;
+        }
+
+        let seed_bytes = seed.to_le_bytes();
+        let seeds = &[MARKET_SEED.as_bytes(), &seed_bytes, &[ctx.bumps.market]];
+        let signers_seeds = &[&seeds[..]];
+
+        // vault pays the taker out of the maker lock, the taker pays the maker ATA
+        let (
+            vault_account,
+            taker_receive_account,
+            taker_pay_account,
+            maker_receive_account,
+            vault_amount,
+            taker_amount,
+        ) = match order.side {
+            Side::Bid => (
+                &ctx.accounts.quote_vault_account,
+                &ctx.accounts.taker_quote_token_account,
+                &ctx.accounts.taker_base_token_account,
+                &ctx.accounts.maker_base_token_account,
+                quote_amount,
+                base_amount,
+            ),
+            Side::Ask => (
+                &ctx.accounts.base_vault_account,
+                &ctx.accounts.taker_base_token_account,
+                &ctx.accounts.taker_quote_token_account,
+                &ctx.accounts.maker_quote_token_account,
+                base_amount,
+                quote_amount,
+            ),
+        };
+
+        let cpi_accounts = Transfer {
+            from: vault_account.to_account_info(),
+            to: taker_receive_account.to_account_info(),
+            authority: market.to_account_info(),
+        };
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signers_seeds,
+            ),
+            vault_amount,
+        )?;
+
+        let cpi_accounts = Transfer {
+            from: taker_pay_account.to_account_info(),
+            to: maker_receive_account.to_account_info(),
+            authority: ctx.accounts.taker.to_account_info(),
+        };
+
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts),
+            taker_amount,
+        )?;
+
+        Ok(trade)
+    }
+}
@@ -0,0 +1,156 @@
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::*;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct Withdraw<'info> {
+    /// open orders owner, delegates trade but never withdraw
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: open orders owner, only its key is used so program PDAs can trade
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        has_one = base_mint,
+        has_one = quote_mint,
+        seeds = [MARKET_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        has_one = market,
+        seeds = [
+            USER_MARKET_ORDER_SEED.as_bytes(),
+            market.key().as_ref(),
+            user.key().as_ref(),
+            UserMarketOrders::index_seed(&user_open_orders.account_index),
+        ],
+        bump,
+        constraint = user_open_orders.address.eq(&user.key()) @ HybridDexError::InvalidAccountOwner,
+        constraint = user_open_orders.address.eq(&authority.key()) @ HybridDexError::InvalidAccountOwner,
+    )]
+    pub user_open_orders: Box<Account<'info, UserMarketOrders>>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = user,
+    )]
+    pub user_base_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = user,
+    )]
+    pub user_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = base_mint,
+        associated_token::authority = market,
+        payer = authority,
+    )]
+    pub base_vault_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = quote_mint,
+        associated_token::authority = market,
+        payer = authority,
+    )]
+    pub quote_vault_account: Box<Account<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl Withdraw<'_> {
+    pub fn process_instruction(
+        ctx: &mut Context<Self>,
+        seed: u64,
+        base_amount: u64,
+        quote_amount: u64,
+    ) -> Result<()> {
+        // check any amount is moved
+        require!(
+            base_amount > 0 || quote_amount > 0,
+            HybridDexError::ZeroAmount
+        );
+
+        let market = &ctx.accounts.market;
+        let user_open_orders = &mut ctx.accounts.user_open_orders;
+
+        // check user free balances
+        require!(
+            user_open_orders.base_free >= base_amount
+                && user_open_orders.quote_free >= quote_amount,
+            HybridDexError::InsufficientFreeBalance
+        );
+
+        // check token vault balances
+        require!(
+            ctx.accounts.base_vault_account.amount >= base_amount
+                && ctx.accounts.quote_vault_account.amount >= quote_amount,
+            HybridDexError::InsufficientWithdrawBalance
+        );
+
+        user_open_orders.base_free = user_open_orders.base_free.safe_sub(base_amount)?;
+        user_open_orders.quote_free = user_open_orders.quote_free.safe_sub(quote_amount)?;
+
+        let seed_bytes = seed.to_le_bytes();
+        let seeds = &[MARKET_SEED.as_bytes(), &seed_bytes, &[ctx.bumps.market]];
+        let signers_seeds = &[&seeds[..]];
+
+        if base_amount > 0 {
+            // transfer base token from vault
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.base_vault_account.to_account_info(),
+                to: ctx.accounts.user_base_token_account.to_account_info(),
+                authority: market.to_account_info(),
+            };
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signers_seeds,
+                ),
+                base_amount,
+            )?;
+        }
+
+        if quote_amount > 0 {
+            // transfer quote token from vault
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.quote_vault_account.to_account_info(),
+                to: ctx.accounts.user_quote_token_account.to_account_info(),
+                authority: market.to_account_info(),
+            };
+
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signers_seeds,
+                ),
+                quote_amount,
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -30,6 +30,11 @@ pub mod hybrid_dex {
         Initialize::process_instruction(&mut ctx, max_orders_per_user, max_orders_per_book)
     }
 
+    /** Admin can grow global pool into the current account layout */
+    pub fn migrate_global_pool(mut ctx: Context<MigrateGlobalPool>) -> Result<()> {
+        MigrateGlobalPool::process_instruction(&mut ctx)
+    }
+
     /** Admin can transfer admin authority */
     pub fn transfer_admin(mut ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
         TransferAdmin::process_instruction(&mut ctx, new_admin)
@@ -44,9 +49,14 @@ pub mod hybrid_dex {
         ChangeConfig::process_instruction(&mut ctx, max_orders_per_user, max_orders_per_book)
     }
 
-    /** Create market */
-    pub fn create_market(mut ctx: Context<CreateMarket>, name: String) -> Result<()> {
-        CreateMarket::process_instruction(&mut ctx, name)
+    /** Create market with its own order limits bounded by global config */
+    pub fn create_market(
+        mut ctx: Context<CreateMarket>,
+        name: String,
+        max_orders_per_user: u64,
+        max_orders_per_book: u64,
+    ) -> Result<()> {
+        CreateMarket::process_instruction(&mut ctx, name, max_orders_per_user, max_orders_per_book)
     }
 
     /** Close market with market owner authority or global admin authority */
@@ -54,30 +64,174 @@ pub mod hybrid_dex {
         CloseMarket::process_instruction(&mut ctx, seed)
     }
 
-    /** Create open orders PDA for user in certain market */
-    pub fn create_open_orders(mut ctx: Context<CreateOpenOrders>) -> Result<()> {
-        CreateOpenOrders::process_instruction(&mut ctx)
+    /** Create trade history of a market created before trades were recorded */
+    pub fn create_trade_history(mut ctx: Context<CreateTradeHistory>, seed: u64) -> Result<()> {
+        CreateTradeHistory::process_instruction(&mut ctx, seed)
+    }
+
+    /** Create stats of a market created before candles were recorded */
+    pub fn create_market_stats(mut ctx: Context<CreateMarketStats>, seed: u64) -> Result<()> {
+        CreateMarketStats::process_instruction(&mut ctx, seed)
+    }
+
+    /** Create pegged book of a market created before pegged orders existed */
+    pub fn create_pegged_book(mut ctx: Context<CreatePeggedBook>, seed: u64) -> Result<()> {
+        CreatePeggedBook::process_instruction(&mut ctx, seed)
+    }
+
+    /** Create trigger book of a market created before trigger orders existed */
+    pub fn create_trigger_book(mut ctx: Context<CreateTriggerBook>, seed: u64) -> Result<()> {
+        CreateTriggerBook::process_instruction(&mut ctx, seed)
+    }
+
+    /** Replace market candle series with market owner authority */
+    pub fn set_candle_intervals(
+        mut ctx: Context<SetCandleIntervals>,
+        seed: u64,
+        intervals: Vec<i64>,
+    ) -> Result<()> {
+        SetCandleIntervals::process_instruction(&mut ctx, seed, intervals)
+    }
+
+    /**
+     * Attach a Pyth price account or the observations of a Raydium CLMM pool as oracle with
+     * market owner authority, orders must then be placed within `price_band_bps` of its
+     * price, `OracleKind::None` detaches it
+     */
+    pub fn set_oracle(
+        mut ctx: Context<SetOracle>,
+        seed: u64,
+        oracle_kind: OracleKind,
+        price_band_bps: u16,
+    ) -> Result<()> {
+        SetOracle::process_instruction(&mut ctx, seed, oracle_kind, price_band_bps)
+    }
+
+    /**
+     * Set the smallest quote value of pegged and trigger orders and of the trades moving last
+     * price triggers with market owner authority
+     */
+    pub fn set_order_minimums(
+        mut ctx: Context<SetOrderMinimums>,
+        seed: u64,
+        min_order_value: u64,
+        min_trigger_trade_value: u64,
+    ) -> Result<()> {
+        SetOrderMinimums::process_instruction(
+            &mut ctx,
+            seed,
+            min_order_value,
+            min_trigger_trade_value,
+        )
+    }
+
+    /** Grow bids and asks book capacity with market owner authority */
+    pub fn resize_book(
+        mut ctx: Context<ResizeBook>,
+        seed: u64,
+        max_orders_per_book: u64,
+    ) -> Result<()> {
+        ResizeBook::process_instruction(&mut ctx, seed, max_orders_per_book)
+    }
+
+    /**
+     * Check market vaults cover resting orders and free balances of open orders
+     * passed as remaining accounts, returns the audited totals
+     */
+    pub fn audit_market(mut ctx: Context<AuditMarket>, seed: u64) -> Result<MarketAudit> {
+        AuditMarket::process_instruction(&mut ctx, seed)
+    }
+
+    /** View best bid and ask levels, returned for CPI callers and simulation */
+    pub fn get_best_prices(mut ctx: Context<GetBestPrices>, seed: u64) -> Result<BestPrices> {
+        GetBestPrices::process_instruction(&mut ctx, seed)
+    }
+
+    /**
+     * View amounts of a market order taking up to `amount` base on `side`,
+     * returned for CPI callers and simulation
+     */
+    pub fn quote_market_order(
+        mut ctx: Context<QuoteMarketOrder>,
+        seed: u64,
+        side: Side,
+        amount: u64,
+    ) -> Result<MarketOrderQuote> {
+        QuoteMarketOrder::process_instruction(&mut ctx, seed, side, amount)
+    }
+
+    /** View balances of an open orders account, returned for CPI callers and simulation */
+    pub fn get_user_position(mut ctx: Context<GetUserPosition>, seed: u64) -> Result<UserPosition> {
+        GetUserPosition::process_instruction(&mut ctx, seed)
+    }
+
+    /** Grow market into the current account layout, paid by anyone */
+    pub fn migrate_market(mut ctx: Context<MigrateMarket>, seed: u64) -> Result<()> {
+        MigrateMarket::process_instruction(&mut ctx, seed)
+    }
+
+    /** Convert legacy books to the current format best first with market owner authority */
+    pub fn migrate_books<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, MigrateBooks<'info>>,
+        seed: u64,
+    ) -> Result<()> {
+        MigrateBooks::process_instruction(&mut ctx, seed)
+    }
+
+    /** Create open orders PDA for user sub account in certain market */
+    pub fn create_open_orders(mut ctx: Context<CreateOpenOrders>, account_index: u8) -> Result<()> {
+        CreateOpenOrders::process_instruction(&mut ctx, account_index)
     }
 
-    /** Place buy order as maker */
+    /** Close empty open orders PDA and reclaim rent, also after market is closed */
+    pub fn close_open_orders(mut ctx: Context<CloseOpenOrders>) -> Result<()> {
+        CloseOpenOrders::process_instruction(&mut ctx)
+    }
+
+    /** Grow open orders PDA into the current account layout, paid by anyone */
+    pub fn migrate_open_orders(mut ctx: Context<MigrateOpenOrders>) -> Result<()> {
+        MigrateOpenOrders::process_instruction(&mut ctx)
+    }
+
+    /** Set or clear delegate trading authority of open orders PDA as owner */
+    pub fn set_delegate(mut ctx: Context<SetDelegate>, delegate: Option<Pubkey>) -> Result<()> {
+        SetDelegate::process_instruction(&mut ctx, delegate)
+    }
+
+    /** Deposit tokens into open orders free balance as owner or its delegate */
+    pub fn deposit(mut ctx: Context<Deposit>, base_amount: u64, quote_amount: u64) -> Result<()> {
+        Deposit::process_instruction(&mut ctx, base_amount, quote_amount)
+    }
+
+    /** Withdraw open orders free balance to owner token accounts, delegates can't withdraw */
+    pub fn withdraw(
+        mut ctx: Context<Withdraw>,
+        seed: u64,
+        base_amount: u64,
+        quote_amount: u64,
+    ) -> Result<()> {
+        Withdraw::process_instruction(&mut ctx, seed, base_amount, quote_amount)
+    }
+
+    /** Place buy order as maker or its delegate, locking free balance first, returns the order id */
     pub fn place_buy_order(
         mut ctx: Context<PlaceBuyOrder>,
         price: u64,
         quantity: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         PlaceBuyOrder::process_instruction(&mut ctx, price, quantity)
     }
 
-    /** Place sell order as maker */
+    /** Place sell order as maker or its delegate, locking free balance first, returns the order id */
     pub fn place_sell_order(
         mut ctx: Context<PlaceSellOrder>,
         price: u64,
         quantity: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         PlaceSellOrder::process_instruction(&mut ctx, price, quantity)
     }
 
-    /** Cancel buy order as owner */
+    /** Cancel buy order as owner or its delegate, releasing funds to free balance */
     pub fn cancel_buy_order(
         mut ctx: Context<CancelBuyOrder>,
         seed: u64,
@@ -86,7 +240,7 @@ pub mod hybrid_dex {
         CancelBuyOrder::process_instruction(&mut ctx, seed, order_id)
     }
 
-    /** Cancel sell order as owner */
+    /** Cancel sell order as owner or its delegate, releasing funds to free balance */
     pub fn cancel_sell_order(
         mut ctx: Context<CancelSellOrder>,
         seed: u64,
@@ -95,37 +249,114 @@ pub mod hybrid_dex {
         CancelSellOrder::process_instruction(&mut ctx, seed, order_id)
     }
 
-    /** Take buy order as taker */
-    pub fn take_buy_order(mut ctx: Context<TakeBuyOrder>, seed: u64, order_id: u64) -> Result<()> {
+    /**
+     * Place order pegged `price_offset` from the market oracle price as maker or its delegate,
+     * bids lock quote at a non-zero `limit_price` and never pay above it, asks never sell below
+     * it, returns the order id
+     */
+    pub fn place_pegged_order(
+        mut ctx: Context<PlacePeggedOrder>,
+        side: Side,
+        price_offset: i64,
+        limit_price: u64,
+        quantity: u64,
+    ) -> Result<u64> {
+        PlacePeggedOrder::process_instruction(&mut ctx, side, price_offset, limit_price, quantity)
+    }
+
+    /** Cancel pegged order as owner or its delegate, releasing funds to free balance */
+    pub fn cancel_pegged_order(
+        mut ctx: Context<CancelPeggedOrder>,
+        seed: u64,
+        order_id: u64,
+    ) -> Result<()> {
+        CancelPeggedOrder::process_instruction(&mut ctx, seed, order_id)
+    }
+
+    /**
+     * Take `amount` base of pegged order as taker at the current oracle price, failing when
+     * it is worse than a non-zero `limit_price` for the taker, returns the fill
+     */
+    pub fn take_pegged_order(
+        mut ctx: Context<TakePeggedOrder>,
+        seed: u64,
+        order_id: u64,
+        amount: u64,
+        limit_price: u64,
+    ) -> Result<Trade> {
+        TakePeggedOrder::process_instruction(&mut ctx, seed, order_id, amount, limit_price)
+    }
+
+    /**
+     * Place stop or take profit order as maker or its delegate, escrowing its funds from free
+     * balance and a keeper bounty from the signer, returns the order id
+     */
+    pub fn place_trigger_order(
+        mut ctx: Context<PlaceTriggerOrder>,
+        params: TriggerOrderParams,
+    ) -> Result<u64> {
+        PlaceTriggerOrder::process_instruction(&mut ctx, params)
+    }
+
+    /**
+     * Cancel trigger order as owner or its delegate, releasing funds to free balance
+     * and the keeper bounty to the signer
+     */
+    pub fn cancel_trigger_order(
+        mut ctx: Context<CancelTriggerOrder>,
+        seed: u64,
+        order_id: u64,
+    ) -> Result<()> {
+        CancelTriggerOrder::process_instruction(&mut ctx, seed, order_id)
+    }
+
+    /**
+     * Execute trigger order whose price has crossed as any keeper, resting it in the book or
+     * filling it against the book orders of other owners, and pay the keeper its bounty
+     */
+    pub fn execute_trigger<'info>(
+        mut ctx: Context<'_, '_, 'info, 'info, ExecuteTrigger<'info>>,
+        seed: u64,
+        order_id: u64,
+    ) -> Result<()> {
+        ExecuteTrigger::process_instruction(&mut ctx, seed, order_id)
+    }
+
+    /** Take buy order as taker, returns the fill */
+    pub fn take_buy_order(
+        mut ctx: Context<TakeBuyOrder>,
+        seed: u64,
+        order_id: u64,
+    ) -> Result<Trade> {
         TakeBuyOrder::process_instruction(&mut ctx, seed, order_id)
     }
 
-    /** Take sell order as taker */
+    /** Take sell order as taker, returns the fill */
     pub fn take_sell_order(
         mut ctx: Context<TakeSellOrder>,
         seed: u64,
         order_id: u64,
-    ) -> Result<()> {
+    ) -> Result<Trade> {
         TakeSellOrder::process_instruction(&mut ctx, seed, order_id)
     }
 
-    /** Take buy order partially as taker */
+    /** Take buy order partially as taker, returns the fill */
     pub fn partial_take_buy_order(
         mut ctx: Context<PartialTakeBuyOrder>,
         seed: u64,
         order_id: u64,
         amount: u64,
-    ) -> Result<()> {
+    ) -> Result<Trade> {
         PartialTakeBuyOrder::process_instruction(&mut ctx, seed, order_id, amount)
     }
 
-    /** Take sell order partially as taker */
+    /** Take sell order partially as taker, returns the fill */
     pub fn partial_take_sell_order(
         mut ctx: Context<PartialTakeSellOrder>,
         seed: u64,
         order_id: u64,
         amount: u64,
-    ) -> Result<()> {
+    ) -> Result<Trade> {
         PartialTakeSellOrder::process_instruction(&mut ctx, seed, order_id, amount)
     }
 }
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::RESERVED_SIZE;
+
 #[account]
 pub struct GlobalPool {
     pub admin: Pubkey,
@@ -8,6 +10,8 @@ pub struct GlobalPool {
     pub total_market_count: u64,
     pub market_seq_num: u64,
     pub extra: u128,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SIZE],
 }
 
 impl Default for GlobalPool {
@@ -20,6 +24,8 @@ impl Default for GlobalPool {
             total_market_count: 0,
             market_seq_num: 0,
             extra: 0,
+            version: 0,
+            reserved: [0; RESERVED_SIZE],
         }
     }
 }
@@ -0,0 +1,181 @@
+use anchor_lang::prelude::*;
+
+use crate::{Book, GlobalPool, Market, Side, UserMarketOrders};
+
+/// Account layout before versioning, decoded by the migrate instructions
+pub trait LegacyLayout: AnchorDeserialize {
+    /// Serialized length after the discriminator, the current `version` byte follows it
+    const LEN: usize;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct GlobalPoolV0 {
+    pub admin: Pubkey,
+    pub max_orders_per_user: u64,
+    pub max_orders_per_book: u64,
+    pub total_market_count: u64,
+    pub market_seq_num: u64,
+    pub extra: u128,
+}
+
+impl LegacyLayout for GlobalPoolV0 {
+    const LEN: usize = 32 + 8 * 4 + 16;
+}
+
+impl From<GlobalPoolV0> for GlobalPool {
+    fn from(legacy: GlobalPoolV0) -> GlobalPool {
+        GlobalPool {
+            admin: legacy.admin,
+            max_orders_per_user: legacy.max_orders_per_user,
+            max_orders_per_book: legacy.max_orders_per_book,
+            total_market_count: legacy.total_market_count,
+            market_seq_num: legacy.market_seq_num,
+            extra: legacy.extra,
+            ..GlobalPool::default()
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct MarketV0 {
+    pub seed: u64,
+    pub name: [u8; 16],
+    pub market_authority: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_decimal: u8,
+    pub quote_decimal: u8,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub created_at: i64,
+    pub base_total_volume: u64,
+    pub quote_total_volume: u64,
+    pub order_seq_num: u64,
+    pub extra: u128,
+}
+
+impl LegacyLayout for MarketV0 {
+    const LEN: usize = 8 + 16 + 32 * 3 + 2 + 32 * 2 + 8 * 4 + 16;
+}
+
+impl From<MarketV0> for Market {
+    /// Books keep their legacy format and order limits are backfilled by the caller
+    fn from(legacy: MarketV0) -> Market {
+        Market {
+            seed: legacy.seed,
+            name: legacy.name,
+            market_authority: legacy.market_authority,
+            base_mint: legacy.base_mint,
+            quote_mint: legacy.quote_mint,
+            base_decimal: legacy.base_decimal,
+            quote_decimal: legacy.quote_decimal,
+            bids: legacy.bids,
+            asks: legacy.asks,
+            created_at: legacy.created_at,
+            base_total_volume: legacy.base_total_volume,
+            quote_total_volume: legacy.quote_total_volume,
+            order_seq_num: legacy.order_seq_num,
+            book_version: 0,
+            ..Market::default()
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct UserMarketOrdersV0 {
+    pub address: Pubkey,
+    pub market: Pubkey,
+    pub opened_orders_count: u64,
+    pub base_deposit_total: u64,
+    pub quote_deposit_total: u64,
+    pub base_total_volume: u64,
+    pub quote_total_volume: u64,
+    pub extra: u128,
+}
+
+impl LegacyLayout for UserMarketOrdersV0 {
+    const LEN: usize = 32 * 2 + 8 * 5 + 16;
+}
+
+impl From<UserMarketOrdersV0> for UserMarketOrders {
+    /// Legacy accounts are sub account 0 without delegate, all deposits are locked by orders
+    fn from(legacy: UserMarketOrdersV0) -> UserMarketOrders {
+        UserMarketOrders {
+            address: legacy.address,
+            market: legacy.market,
+            opened_orders_count: legacy.opened_orders_count,
+            base_deposit_total: legacy.base_deposit_total,
+            quote_deposit_total: legacy.quote_deposit_total,
+            base_total_volume: legacy.base_total_volume,
+            quote_total_volume: legacy.quote_total_volume,
+            extra: legacy.extra,
+            ..UserMarketOrders::default()
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct OpenedOrderV0 {
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub price: u64,
+    /// Quote amount of bids, base amount of asks
+    pub quantity: u64,
+    pub created_at: i64,
+}
+
+/// Book of worst first orders, without version byte so the market `book_version` tells it apart
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BookV0 {
+    pub side: Side,
+    pub market: Pubkey,
+    pub orders_count: u64,
+    pub orders: Vec<OpenedOrderV0>,
+}
+
+impl BookV0 {
+    /// Orders a legacy book account of `space` bytes holds
+    pub fn capacity(space: usize) -> u64 {
+        (space.saturating_sub(Book::DATA_SIZE) / std::mem::size_of::<OpenedOrderV0>()) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_len<T: AnchorSerialize + Default>() -> usize {
+        T::default().try_to_vec().unwrap().len()
+    }
+
+    fn version_offset<T: AccountSerialize>(state: &T) -> usize {
+        let mut data = vec![];
+        state.try_serialize(&mut data).unwrap();
+        data[8..].iter().position(|byte| *byte == 0xAB).unwrap()
+    }
+
+    #[test]
+    fn version_follows_legacy_layout() {
+        assert_eq!(legacy_len::<GlobalPoolV0>(), GlobalPoolV0::LEN);
+        assert_eq!(legacy_len::<MarketV0>(), MarketV0::LEN);
+        assert_eq!(legacy_len::<UserMarketOrdersV0>(), UserMarketOrdersV0::LEN);
+
+        let global_pool = GlobalPool {
+            version: 0xAB,
+            ..GlobalPool::default()
+        };
+        assert_eq!(version_offset(&global_pool), GlobalPoolV0::LEN);
+
+        let market = Market {
+            version: 0xAB,
+            ..Market::default()
+        };
+        assert_eq!(version_offset(&market), MarketV0::LEN);
+
+        let open_orders = UserMarketOrders {
+            version: 0xAB,
+            ..UserMarketOrders::default()
+        };
+        assert_eq!(version_offset(&open_orders), UserMarketOrdersV0::LEN);
+    }
+}
@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
+use hybrid_dex_core as book;
 
-use crate::HybridDexError;
+use crate::{
+    locked_quote, HybridDexError, BOOK_VERSION, MARKET_RESERVED_SIZE, MAX_ORACLE_CONFIDENCE_BPS,
+    MAX_ORACLE_STALENESS, ORACLE_TWAP_WINDOW, PYTH_ORACLE_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID,
+};
 
 #[account]
 pub struct Market {
@@ -17,7 +21,42 @@ pub struct Market {
     pub base_total_volume: u64,
     pub quote_total_volume: u64,
     pub order_seq_num: u64,
-    pub extra: u128,
+    /// Format of `bids` and `asks`, carved from the reserved bytes so legacy markets read 0
+    pub book_version: u8,
+    pub extra: [u8; 15],
+    pub version: u8,
+    /// Order limits of this market, carved from the reserved bytes and backfilled by `migrate_market`
+    pub max_orders_per_user: u64,
+    pub max_orders_per_book: u64,
+    /// Price source bounding order prices, carved from the reserved bytes so legacy markets have none
+    pub oracle_kind: OracleKind,
+    pub oracle: Pubkey,
+    /// Orders must rest within this many basis points of the oracle price
+    pub price_band_bps: u16,
+    /// Trade history account was created, fills must record in it, carved from the reserved bytes
+    pub has_trade_history: bool,
+    /// Market stats account was created, fills must record in it, carved from the reserved bytes
+    pub has_market_stats: bool,
+    /// Pegged book was created, close and audit must count it, carved from the reserved bytes
+    pub has_pegged_book: bool,
+    /// Trigger book was created, close and audit must count it, carved from the reserved bytes
+    pub has_trigger_book: bool,
+    /// Smallest quote value of a pegged order at its placement price or of a trigger order at
+    /// its limit, in quote atomic units
+    pub min_order_value: u64,
+    /// Smallest quote value of a trade moving last price triggers, in quote atomic units
+    pub min_trigger_trade_value: u64,
+    pub reserved: [u8; MARKET_RESERVED_SIZE],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub enum OracleKind {
+    #[default]
+    None,
+    /// Pyth price account
+    Pyth,
+    /// Observation account of a Raydium CLMM pool between the market mints, priced by its TWAP
+    RaydiumClmm,
 }
 
 impl Default for Market {
@@ -37,7 +76,21 @@ impl Default for Market {
             base_total_volume: 0,
             quote_total_volume: 0,
             order_seq_num: 0,
-            extra: 0,
+            book_version: 0,
+            extra: [0; 15],
+            version: 0,
+            max_orders_per_user: 0,
+            max_orders_per_book: 0,
+            oracle_kind: OracleKind::None,
+            oracle: Pubkey::default(),
+            price_band_bps: 0,
+            has_trade_history: false,
+            has_market_stats: false,
+            has_pegged_book: false,
+            has_trigger_book: false,
+            min_order_value: 0,
+            min_trigger_trade_value: 0,
+            reserved: [0; MARKET_RESERVED_SIZE],
         }
     }
 }
@@ -45,6 +98,152 @@ impl Default for Market {
 impl Market {
     pub const DATA_SIZE: usize = 8 + std::mem::size_of::<Market>();
 
+    /// Books are in the current format, best first with base quantities on both sides
+    pub fn is_book_current(&self) -> bool {
+        self.book_version == BOOK_VERSION
+    }
+
+    /// Quote amount locked in the vault by a resting bid
+    pub fn bid_locked_quote(&self, order: &OpenedOrder) -> Result<u64> {
+        locked_quote(order.quantity, order.price, self.base_decimal)
+    }
+
+    /// Check the trade history is passed once the market has one
+    pub fn check_trade_history<T>(&self, trade_history: &Option<T>) -> Result<()> {
+        require!(
+            trade_history.is_some() || !self.has_trade_history,
+            HybridDexError::TradeHistoryRequired
+        );
+
+        Ok(())
+    }
+
+    /// Check the market stats are passed once the market has them
+    pub fn check_market_stats<T>(&self, market_stats: &Option<T>) -> Result<()> {
+        require!(
+            market_stats.is_some() || !self.has_market_stats,
+            HybridDexError::MarketStatsRequired
+        );
+
+        Ok(())
+    }
+
+    /// Check the pegged book is passed once the market has one
+    pub fn check_pegged_book<T>(&self, pegged_book: &Option<T>) -> Result<()> {
+        require!(
+            pegged_book.is_some() || !self.has_pegged_book,
+            HybridDexError::PeggedBookRequired
+        );
+
+        Ok(())
+    }
+
+    /// Check the trigger book is passed once the market has one
+    pub fn check_trigger_book<T>(&self, trigger_book: &Option<T>) -> Result<()> {
+        require!(
+            trigger_book.is_some() || !self.has_trigger_book,
+            HybridDexError::TriggerBookRequired
+        );
+
+        Ok(())
+    }
+
+    /// Oracle price in market units, `oracle` must be the market price source
+    pub fn oracle_price(&self, oracle: &AccountInfo, now: i64) -> Result<u64> {
+        // check oracle account against market price source
+        require!(
+            oracle.key() == self.oracle,
+            HybridDexError::InvalidOracleAccount
+        );
+
+        let data = oracle.try_borrow_data()?;
+        let price = match self.oracle_kind {
+            OracleKind::None => return err!(HybridDexError::InvalidOracleAccount),
+            OracleKind::Pyth => {
+                require!(
+                    oracle.owner == &PYTH_ORACLE_PROGRAM_ID,
+                    HybridDexError::InvalidOracleAccount
+                );
+                book::pyth_price(
+                    &data,
+                    self.quote_decimal,
+                    now,
+                    MAX_ORACLE_STALENESS,
+                    MAX_ORACLE_CONFIDENCE_BPS,
+                )
+            }
+            OracleKind::RaydiumClmm => {
+                require!(
+                    oracle.owner == &RAYDIUM_CLMM_PROGRAM_ID,
+                    HybridDexError::InvalidOracleAccount
+                );
+                book::clmm_twap_price(
+                    &data,
+                    &self.base_mint.to_bytes(),
+                    &self.quote_mint.to_bytes(),
+                    self.base_decimal,
+                    ORACLE_TWAP_WINDOW,
+                )
+            }
+        };
+
+        Ok(price.map_err(HybridDexError::from)?)
+    }
+
+    /// Quote atomic units of `amount` whole quote tokens
+    pub fn whole_quote(&self, amount: u64) -> Result<u64> {
+        Ok(10u64
+            .checked_pow(self.quote_decimal as u32)
+            .and_then(|unit| unit.checked_mul(amount))
+            .ok_or(HybridDexError::MathOverflow)?)
+    }
+
+    /// Check `quantity` at `price` is worth at least the market minimum order value
+    pub fn check_order_value(&self, quantity: u64, price: u64) -> Result<()> {
+        let value = locked_quote(quantity, price, self.base_decimal)?;
+        require!(
+            value >= self.min_order_value,
+            HybridDexError::OrderValueTooSmall
+        );
+
+        Ok(())
+    }
+
+    /// Observation account of a Raydium CLMM `pool` between the market mints
+    pub fn clmm_observation(&self, pool: &[u8]) -> Result<Pubkey> {
+        let observation = book::clmm_pool_observation(
+            pool,
+            &self.base_mint.to_bytes(),
+            &self.quote_mint.to_bytes(),
+        )
+        .map_err(HybridDexError::from)?;
+
+        Ok(Pubkey::new_from_array(observation))
+    }
+
+    /// Check `price` is within the oracle band, markets without oracle accept any price
+    pub fn check_price_band(
+        &self,
+        oracle: Option<&AccountInfo>,
+        price: u64,
+        now: i64,
+    ) -> Result<()> {
+        if self.oracle_kind == OracleKind::None {
+            return Ok(());
+        }
+
+        let oracle = oracle.ok_or(HybridDexError::InvalidOracleAccount)?;
+        let (min_price, max_price) =
+            book::price_band(self.oracle_price(oracle, now)?, self.price_band_bps);
+
+        require!(
+            price >= min_price && price <= max_price,
+            HybridDexError::PriceOutsideBand
+        );
+
+        Ok(())
+    }
+
     pub fn name(&self) -> &str {
         std::str::from_utf8(&self.name)
             .unwrap()
@@ -52,19 +251,47 @@ impl Market {
     }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
 pub enum Side {
     Bid = 0,
     Ask = 1,
 }
 
+impl From<Side> for book::Side {
+    fn from(side: Side) -> book::Side {
+        match side {
+            Side::Bid => book::Side::Bid,
+            Side::Ask => book::Side::Ask,
+        }
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
 pub struct OpenedOrder {
     pub order_id: u64,
     pub owner: Pubkey,
-    pub price: u64, // use same decimal with quote token
-    pub quantity: u64,
+    pub price: u64,    // use same decimal with quote token
+    pub quantity: u64, // base amount on both sides
     pub created_at: i64,
+    pub account_index: u8,
+}
+
+impl book::BookOrder for OpenedOrder {
+    fn order_id(&self) -> u64 {
+        self.order_id
+    }
+
+    fn price(&self) -> u64 {
+        self.price
+    }
+
+    fn quantity(&self) -> u64 {
+        self.quantity
+    }
+
+    fn set_quantity(&mut self, quantity: u64) {
+        self.quantity = quantity;
+    }
 }
 
 #[account]
@@ -83,74 +310,24 @@ impl Book {
     }
 
     pub fn insert_order(&mut self, new_order: OpenedOrder) {
-        let idx: usize = 0;
-        let mut inserted: u8 = 0;
-
-        for idx in 0..self.orders_count as usize {
-            if self.side == Side::Bid {
-                if self.orders[idx].price > new_order.price {
-                    self.orders.insert(idx, new_order);
-                    inserted = 1;
-                    break;
-                }
-            } else if self.side == Side::Ask {
-                if self.orders[idx].price < new_order.price {
-                    self.orders.insert(idx, new_order);
-                    inserted = 1;
-                    break;
-                }
-            }
-        }
-
-        if inserted == 0 {
-            if self.orders.len() == self.orders_count as usize {
-                self.orders.push(new_order);
-            } else {
-                self.orders[idx] = new_order;
-            }
-        }
+        book::insert_order(
+            self.side.into(),
+            &mut self.orders,
+            self.orders_count,
+            new_order,
+        );
     }
 
     pub fn remove_order(&mut self, order_id: u64) -> Result<OpenedOrder> {
-        if self.orders_count == 0 {
-            return Err(HybridDexError::OrderNotFound.into());
-        } else {
-            for idx in 0..self.orders_count as usize {
-                if self.orders[idx].order_id == order_id {
-                    let order = self.orders.remove(idx);
-                    return Ok(order);
-                } else if self.orders[idx].order_id == order_id {
-                    let order = self.orders.remove(idx);
-                    return Ok(order);
-                }
-            }
-            return Err(HybridDexError::OrderNotFound.into());
-        }
+        let order = book::remove_order(&mut self.orders, self.orders_count, order_id)
+            .map_err(HybridDexError::from)?;
+        Ok(order)
     }
 
     pub fn decrease_order(&mut self, order_id: u64, amount: u64) -> Result<OpenedOrder> {
-        if self.orders_count == 0 {
-            return Err(HybridDexError::OrderNotFound.into());
-        } else {
-            for idx in 0..self.orders_count as usize {
-                if self.orders[idx].order_id == order_id {
-                    if self.orders[idx].quantity <= amount {
-                        return Err(HybridDexError::PartialOrderAmountExceed.into());
-                    }
-                    let mut order = self.orders[idx];
-                    order.quantity -= amount;
-                    return Ok(order);
-                } else if self.orders[idx].order_id == order_id {
-                    if self.orders[idx].quantity <= amount {
-                        return Err(HybridDexError::PartialOrderAmountExceed.into());
-                    }
-                    let mut order = self.orders[idx];
-                    order.quantity -= amount;
-                    return Ok(order);
-                }
-            }
-            return Err(HybridDexError::OrderNotFound.into());
-        }
+        let order = book::decrease_order(&mut self.orders, self.orders_count, order_id, amount)
+            .map_err(HybridDexError::from)?;
+        Ok(order)
     }
 }
 
@@ -165,6 +342,7 @@ mod tests {
             price,
             quantity: price,
             created_at: id as i64,
+            account_index: 0,
         }
     }
 
@@ -188,15 +366,22 @@ mod tests {
         mockup.insert_order(generate_new_order(mockup.orders_count, 55));
         mockup.orders_count += 1;
 
+        let ids = |book: &Book| book.orders.iter().map(|o| o.order_id).collect::<Vec<_>>();
+        assert_eq!(ids(&mockup), vec![3, 1, 0, 2]);
+
         mockup.remove_order(1)?;
         mockup.orders_count -= 1;
 
-        println!("{:#?}", mockup.orders);
+        assert_eq!(ids(&mockup), vec![3, 0, 2]);
 
         mockup.remove_order(3)?;
         mockup.orders_count -= 1;
 
-        println!("{:#?}", mockup.orders);
+        assert_eq!(ids(&mockup), vec![0, 2]);
+
+        let order = mockup.decrease_order(0, 11)?;
+        assert_eq!(order.quantity, 40);
+        assert_eq!(mockup.orders[0].quantity, 40);
 
         Ok(())
     }
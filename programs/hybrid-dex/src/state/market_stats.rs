@@ -0,0 +1,314 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    HybridDexError, PriceObservation, SafeMath, CANDLES_PER_SERIES, MAX_CANDLE_SERIES,
+    VOLUME_BUCKETS, VOLUME_BUCKET_INTERVAL,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub struct VolumeBucket {
+    pub start: i64,
+    pub base_volume: u64,
+    pub quote_volume: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub struct Candle {
+    /// Start of the candle interval, zero for a slot never traded in
+    pub open_time: i64,
+    pub open: u64,
+    pub high: u64,
+    pub low: u64,
+    pub close: u64,
+    pub base_volume: u64,
+    pub quote_volume: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Eq, PartialEq, Debug)]
+pub struct CandleSeries {
+    /// Candle length in seconds
+    pub interval: i64,
+    /// Ring buffer indexed by `open_time / interval % CANDLES_PER_SERIES`
+    pub candles: Vec<Candle>,
+}
+
+impl CandleSeries {
+    fn record(
+        &mut self,
+        price: u64,
+        base_amount: u64,
+        quote_amount: u64,
+        timestamp: i64,
+    ) -> Result<()> {
+        let open_time = timestamp - timestamp.rem_euclid(self.interval);
+        let index = (open_time / self.interval).rem_euclid(CANDLES_PER_SERIES as i64) as usize;
+        let candle = &mut self.candles[index];
+
+        if candle.open_time != open_time {
+            *candle = Candle {
+                open_time,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                base_volume: base_amount,
+                quote_volume: quote_amount,
+            };
+        } else {
+            candle.high = candle.high.max(price);
+            candle.low = candle.low.min(price);
+            candle.close = price;
+            candle.base_volume = candle.base_volume.safe_add(base_amount)?;
+            candle.quote_volume = candle.quote_volume.safe_add(quote_amount)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Last trade price, rolling day volume and OHLCV candles of a market, updated on each fill
+#[account]
+pub struct MarketStats {
+    pub market: Pubkey,
+    pub last_price: u64,
+    pub last_trade_at: i64,
+    /// Hourly volume ring buffer of the last day
+    pub volume_buckets: Vec<VolumeBucket>,
+    pub candle_series: Vec<CandleSeries>,
+    /// Sum of each trade price times the seconds it stood, wraps on overflow
+    pub price_cumulative: u128,
+}
+
+impl MarketStats {
+    pub const DATA_SIZE: usize = 8
+        + std::mem::size_of::<MarketStats>()
+        + std::mem::size_of::<VolumeBucket>() * VOLUME_BUCKETS
+        + (std::mem::size_of::<CandleSeries>()
+            + std::mem::size_of::<Candle>() * CANDLES_PER_SERIES)
+            * MAX_CANDLE_SERIES;
+
+    /// Replace candle series by empty ones of `intervals` seconds
+    pub fn set_candle_intervals(&mut self, intervals: &[i64]) -> Result<()> {
+        // check intervals are positive and strictly increasing
+        require!(
+            intervals.len() <= MAX_CANDLE_SERIES
+                && intervals.iter().all(|interval| *interval > 0)
+                && intervals.windows(2).all(|pair| pair[0] < pair[1]),
+            HybridDexError::InvalidCandleIntervals
+        );
+
+        self.volume_buckets
+            .resize(VOLUME_BUCKETS, VolumeBucket::default());
+        self.candle_series = intervals
+            .iter()
+            .map(|interval| CandleSeries {
+                interval: *interval,
+                candles: vec![Candle::default(); CANDLES_PER_SERIES],
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    /// Record a fill, clock going backwards never reopens an older candle
+    pub fn record_fill(
+        &mut self,
+        price: u64,
+        base_amount: u64,
+        quote_amount: u64,
+        timestamp: i64,
+    ) -> Result<()> {
+        let timestamp = timestamp.max(self.last_trade_at);
+
+        self.price_cumulative = self.observe(timestamp).price_cumulative;
+        self.last_price = price;
+        self.last_trade_at = timestamp;
+
+        let start = timestamp - timestamp.rem_euclid(VOLUME_BUCKET_INTERVAL);
+        let index = (start / VOLUME_BUCKET_INTERVAL).rem_euclid(VOLUME_BUCKETS as i64) as usize;
+        let bucket = &mut self.volume_buckets[index];
+        if bucket.start != start {
+            *bucket = VolumeBucket {
+                start,
+                ..VolumeBucket::default()
+            };
+        }
+        bucket.base_volume = bucket.base_volume.safe_add(base_amount)?;
+        bucket.quote_volume = bucket.quote_volume.safe_add(quote_amount)?;
+
+        for series in self.candle_series.iter_mut() {
+            series.record(price, base_amount, quote_amount, timestamp)?;
+        }
+
+        Ok(())
+    }
+
+    /// Price accumulator at `now`, counting the last trade price up to it,
+    /// two observations give the TWAP between them with `twap`
+    pub fn observe(&self, now: i64) -> PriceObservation {
+        PriceObservation {
+            timestamp: self.last_trade_at,
+            price_cumulative: self.price_cumulative,
+        }
+        .advance(self.last_price, now)
+    }
+
+    /// Base and quote volume traded in the day before `now`, at hourly resolution
+    pub fn volume_24h(&self, now: i64) -> Result<(u64, u64)> {
+        let current = now - now.rem_euclid(VOLUME_BUCKET_INTERVAL);
+        let oldest = current - VOLUME_BUCKET_INTERVAL * (VOLUME_BUCKETS as i64 - 1);
+
+        let mut volume = (0u64, 0u64);
+        for bucket in self.volume_buckets.iter() {
+            if bucket.start >= oldest && bucket.start <= current {
+                volume.0 = volume.0.safe_add(bucket.base_volume)?;
+                volume.1 = volume.1.safe_add(bucket.quote_volume)?;
+            }
+        }
+
+        Ok(volume)
+    }
+
+    /// Traded candles of the `interval` series, oldest first
+    pub fn candles(&self, interval: i64) -> Option<Vec<Candle>> {
+        let series = self
+            .candle_series
+            .iter()
+            .find(|series| series.interval == interval)?;
+
+        let mut candles: Vec<Candle> = series
+            .candles
+            .iter()
+            .filter(|candle| candle.open_time != 0)
+            .copied()
+            .collect();
+        candles.sort_by_key(|candle| candle.open_time);
+
+        Some(candles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{twap, utils::tests::assert_error};
+
+    fn stats(intervals: &[i64]) -> MarketStats {
+        let mut stats = MarketStats {
+            market: Pubkey::default(),
+            last_price: 0,
+            last_trade_at: 0,
+            volume_buckets: vec![],
+            candle_series: vec![],
+            price_cumulative: 0,
+        };
+        stats.set_candle_intervals(intervals).unwrap();
+        stats
+    }
+
+    #[test]
+    fn candles_track_fills() {
+        let mut stats = stats(&[60, 3600]);
+        let start = 1_700_000_040;
+
+        stats.record_fill(10, 1, 10, start).unwrap();
+        stats.record_fill(14, 2, 28, start + 10).unwrap();
+        stats.record_fill(8, 1, 8, start + 15).unwrap();
+        // next minute
+        stats.record_fill(9, 3, 27, start + 60).unwrap();
+        // clock going backwards stays in the latest candle
+        stats.record_fill(11, 1, 11, start - 100).unwrap();
+
+        assert_eq!(stats.last_price, 11);
+        assert_eq!(stats.last_trade_at, start + 60);
+
+        let minutes = stats.candles(60).unwrap();
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(
+            minutes[0],
+            Candle {
+                open_time: 1_700_000_040,
+                open: 10,
+                high: 14,
+                low: 8,
+                close: 8,
+                base_volume: 4,
+                quote_volume: 46,
+            }
+        );
+        assert_eq!(minutes[1].open, 9);
+        assert_eq!(minutes[1].close, 11);
+        assert_eq!(minutes[1].base_volume, 4);
+
+        let hours = stats.candles(3600).unwrap();
+        assert_eq!(hours.len(), 1);
+        assert_eq!((hours[0].high, hours[0].low), (14, 8));
+        assert_eq!(hours[0].base_volume, 8);
+
+        assert!(stats.candles(300).is_none());
+
+        // candles wrap after CANDLES_PER_SERIES intervals
+        let later = start + 60 * CANDLES_PER_SERIES as i64;
+        stats.record_fill(20, 1, 20, later).unwrap();
+        let minutes = stats.candles(60).unwrap();
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(minutes[1].open, 20);
+    }
+
+    #[test]
+    fn volume_rolls_over_a_day() {
+        let mut stats = stats(&[]);
+        let hour = VOLUME_BUCKET_INTERVAL;
+        let start = 1_700_000_000 - 1_700_000_000 % hour;
+
+        stats.record_fill(1, 5, 50, start).unwrap();
+        stats.record_fill(1, 7, 70, start + 5 * hour).unwrap();
+
+        assert_eq!(stats.volume_24h(start + 5 * hour).unwrap(), (12, 120));
+        // the first hour drops out a day later
+        assert_eq!(stats.volume_24h(start + 24 * hour).unwrap(), (7, 70));
+        assert_eq!(stats.volume_24h(start + 30 * hour).unwrap(), (0, 0));
+
+        // same bucket index a day later starts over
+        stats.record_fill(1, 1, 10, start + 24 * hour).unwrap();
+        assert_eq!(stats.volume_24h(start + 24 * hour).unwrap(), (8, 80));
+    }
+
+    #[test]
+    fn fills_accumulate_price_time() {
+        let mut stats = stats(&[]);
+        let start = 1_700_000_000;
+
+        // nothing accumulates before the first trade
+        stats.record_fill(100, 1, 100, start).unwrap();
+        let first = stats.observe(start);
+        assert_eq!(first.price_cumulative, 0);
+
+        stats.record_fill(200, 1, 200, start + 60).unwrap();
+        stats.record_fill(50, 1, 50, start + 90).unwrap();
+        assert_eq!(stats.price_cumulative, 100 * 60 + 200 * 30);
+
+        // last price keeps counting until the observation time
+        let later = stats.observe(start + 120);
+        assert_eq!(later.price_cumulative, 100 * 60 + 200 * 30 + 50 * 30);
+        assert_eq!(twap(&first, &later).unwrap(), 112);
+        assert_eq!(twap(&stats.observe(start + 90), &later).unwrap(), 50);
+
+        assert_error(
+            twap(&later, &first).unwrap_err(),
+            HybridDexError::InvalidObservationWindow,
+        );
+    }
+
+    #[test]
+    fn candle_intervals_are_validated() {
+        let mut stats = stats(&[]);
+
+        for intervals in [&[60, 60][..], &[0], &[1, 2, 3, 4, 5]] {
+            assert_error(
+                stats.set_candle_intervals(intervals).unwrap_err(),
+                HybridDexError::InvalidCandleIntervals,
+            );
+        }
+    }
+}
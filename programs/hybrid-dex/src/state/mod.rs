@@ -1,6 +1,16 @@
 pub mod global;
 pub use global::*;
+pub mod legacy;
+pub use legacy::*;
 pub mod market;
 pub use market::*;
+pub mod market_stats;
+pub use market_stats::*;
 pub mod open_orders;
 pub use open_orders::*;
+pub mod pegged_book;
+pub use pegged_book::*;
+pub mod trade_history;
+pub use trade_history::*;
+pub mod trigger_book;
+pub use trigger_book::*;
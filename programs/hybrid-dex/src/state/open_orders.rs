@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::{OpenedOrder, PeggedOrder, TriggerOrder, OPEN_ORDERS_RESERVED_SIZE};
+
 #[account]
 pub struct UserMarketOrders {
     pub address: Pubkey,
@@ -10,6 +12,15 @@ pub struct UserMarketOrders {
     pub base_total_volume: u64,
     pub quote_total_volume: u64,
     pub extra: u128,
+    pub version: u8,
+    /// Trading authority besides the owner, carved from the reserved bytes so legacy accounts have none
+    pub delegate: Pubkey,
+    /// Sub account of the owner in this market, legacy accounts are index 0
+    pub account_index: u8,
+    /// Deposited funds not locked by orders, withdrawable by the owner
+    pub base_free: u64,
+    pub quote_free: u64,
+    pub reserved: [u8; OPEN_ORDERS_RESERVED_SIZE],
 }
 
 impl Default for UserMarketOrders {
@@ -24,10 +35,86 @@ impl Default for UserMarketOrders {
             base_total_volume: 0,
             quote_total_volume: 0,
             extra: 0,
+            version: 0,
+            delegate: Pubkey::default(),
+            account_index: 0,
+            base_free: 0,
+            quote_free: 0,
+            reserved: [0; OPEN_ORDERS_RESERVED_SIZE],
         }
     }
 }
 
 impl UserMarketOrders {
     pub const DATA_SIZE: usize = 8 + std::mem::size_of::<UserMarketOrders>();
+
+    /// PDA seed of sub account index, index 0 keeps the legacy `[market, user]` address
+    pub fn index_seed(account_index: &u8) -> &[u8] {
+        if *account_index == 0 {
+            &[]
+        } else {
+            std::slice::from_ref(account_index)
+        }
+    }
+
+    /// Order was placed from this sub account
+    pub fn is_order_owner(&self, order: &OpenedOrder) -> bool {
+        order.owner.eq(&self.address) && order.account_index == self.account_index
+    }
+
+    pub fn is_pegged_order_owner(&self, order: &PeggedOrder) -> bool {
+        order.owner.eq(&self.address) && order.account_index == self.account_index
+    }
+
+    pub fn is_trigger_order_owner(&self, order: &TriggerOrder) -> bool {
+        order.owner.eq(&self.address) && order.account_index == self.account_index
+    }
+
+    /// Owner or its delegate can place and cancel orders
+    pub fn is_authorized(&self, signer: &Pubkey) -> bool {
+        self.address.eq(signer)
+            || (self.delegate.ne(&Pubkey::default()) && self.delegate.eq(signer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ID, USER_MARKET_ORDER_SEED};
+
+    #[test]
+    fn index_zero_keeps_legacy_address() {
+        let market = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+
+        let (legacy, _) = Pubkey::find_program_address(
+            &[
+                USER_MARKET_ORDER_SEED.as_bytes(),
+                market.as_ref(),
+                user.as_ref(),
+            ],
+            &ID,
+        );
+        let (first, _) = Pubkey::find_program_address(
+            &[
+                USER_MARKET_ORDER_SEED.as_bytes(),
+                market.as_ref(),
+                user.as_ref(),
+                UserMarketOrders::index_seed(&0),
+            ],
+            &ID,
+        );
+        let (second, _) = Pubkey::find_program_address(
+            &[
+                USER_MARKET_ORDER_SEED.as_bytes(),
+                market.as_ref(),
+                user.as_ref(),
+                UserMarketOrders::index_seed(&1),
+            ],
+            &ID,
+        );
+
+        assert_eq!(legacy, first);
+        assert_ne!(legacy, second);
+    }
 }
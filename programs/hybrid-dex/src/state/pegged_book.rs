@@ -0,0 +1,217 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    locked_quote, HybridDexError, OpenedOrder, Side, MAX_PEGGED_ORDERS_PER_USER,
+    PEGGED_BOOK_CAPACITY,
+};
+
+/// Resting order priced from the market oracle when it is taken
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct PeggedOrder {
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub account_index: u8,
+    pub side: Side,
+    /// Added to the oracle price, negative offsets rest below it
+    pub price_offset: i64,
+    /// Highest bid or lowest ask price, bids lock their quote value at it
+    pub limit_price: u64,
+    pub quantity: u64,
+    pub created_at: i64,
+}
+
+impl PeggedOrder {
+    /// Price of the order at `oracle_price`
+    pub fn price(&self, oracle_price: u64) -> Result<u64> {
+        hybrid_dex_core::pegged_price(
+            self.side.into(),
+            oracle_price,
+            self.price_offset,
+            self.limit_price,
+        )
+        .map_err(|err| error!(HybridDexError::from(err)))
+    }
+
+    /// Funds `quantity` of the order locks, base of asks and quote at the limit of bids
+    pub fn locked_amount(&self, quantity: u64, base_decimal: u8) -> Result<u64> {
+        match self.side {
+            Side::Bid => locked_quote(quantity, self.limit_price, base_decimal),
+            Side::Ask => Ok(quantity),
+        }
+    }
+
+    /// Book order view filled at `price`, for trade records
+    pub fn at_price(&self, price: u64) -> OpenedOrder {
+        OpenedOrder {
+            order_id: self.order_id,
+            owner: self.owner,
+            price,
+            quantity: self.quantity,
+            created_at: self.created_at,
+            account_index: self.account_index,
+        }
+    }
+}
+
+/// Oracle pegged orders of a market, both sides in placement order
+#[account]
+pub struct PeggedBook {
+    pub market: Pubkey,
+    pub orders: Vec<PeggedOrder>,
+}
+
+impl PeggedBook {
+    pub const DATA_SIZE: usize = 8
+        + std::mem::size_of::<PeggedBook>()
+        + std::mem::size_of::<PeggedOrder>() * PEGGED_BOOK_CAPACITY;
+
+    pub fn insert_order(&mut self, order: PeggedOrder) -> Result<()> {
+        // check pegged book capacity
+        require!(
+            self.orders.len() < PEGGED_BOOK_CAPACITY,
+            HybridDexError::OpenOrdersFull
+        );
+        // check orders of the owner
+        require!(
+            self.orders
+                .iter()
+                .filter(|resting| resting.owner == order.owner)
+                .count()
+                < MAX_PEGGED_ORDERS_PER_USER,
+            HybridDexError::OwnerBookOrdersFull
+        );
+        self.orders.push(order);
+
+        Ok(())
+    }
+
+    pub fn get_order(&self, order_id: u64) -> Result<PeggedOrder> {
+        self.orders
+            .iter()
+            .find(|order| order.order_id == order_id)
+            .copied()
+            .ok_or_else(|| error!(HybridDexError::OrderNotFound))
+    }
+
+    pub fn remove_order(&mut self, order_id: u64) -> Result<PeggedOrder> {
+        let index = self
+            .orders
+            .iter()
+            .position(|order| order.order_id == order_id)
+            .ok_or(HybridDexError::OrderNotFound)?;
+
+        Ok(self.orders.remove(index))
+    }
+
+    /// Fill `amount` of an order, removing it once nothing is left,
+    /// returns the order as it was before the fill
+    pub fn fill_order(&mut self, order_id: u64, amount: u64) -> Result<PeggedOrder> {
+        let order = self
+            .orders
+            .iter_mut()
+            .find(|order| order.order_id == order_id)
+            .ok_or(HybridDexError::OrderNotFound)?;
+
+        // check fill amount against order quantity
+        require!(
+            amount <= order.quantity,
+            HybridDexError::PartialOrderAmountExceed
+        );
+
+        let filled = *order;
+        order.quantity -= amount;
+        if order.quantity == 0 {
+            self.remove_order(order_id)?;
+        }
+
+        Ok(filled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::tests::assert_error;
+
+    fn order(order_id: u64, side: Side, quantity: u64) -> PeggedOrder {
+        PeggedOrder {
+            order_id,
+            owner: Pubkey::default(),
+            account_index: 0,
+            side,
+            price_offset: -10,
+            limit_price: 1_000,
+            quantity,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn fills_until_empty() {
+        let mut book = PeggedBook {
+            market: Pubkey::default(),
+            orders: vec![],
+        };
+        book.insert_order(order(1, Side::Bid, 5)).unwrap();
+        book.insert_order(order(2, Side::Ask, 7)).unwrap();
+
+        assert_eq!(book.fill_order(1, 3).unwrap().quantity, 5);
+        assert_eq!(book.get_order(1).unwrap().quantity, 2);
+        assert_error(
+            book.fill_order(1, 3).unwrap_err(),
+            HybridDexError::PartialOrderAmountExceed,
+        );
+
+        book.fill_order(1, 2).unwrap();
+        assert_error(
+            book.get_order(1).unwrap_err(),
+            HybridDexError::OrderNotFound,
+        );
+        assert_eq!(book.remove_order(2).unwrap().quantity, 7);
+        assert!(book.orders.is_empty());
+
+        for id in 0..MAX_PEGGED_ORDERS_PER_USER as u64 {
+            book.insert_order(order(id, Side::Ask, 1)).unwrap();
+        }
+        assert_error(
+            book.insert_order(order(98, Side::Ask, 1)).unwrap_err(),
+            HybridDexError::OwnerBookOrdersFull,
+        );
+
+        for id in MAX_PEGGED_ORDERS_PER_USER as u64..PEGGED_BOOK_CAPACITY as u64 {
+            let owner = Pubkey::new_unique();
+            book.insert_order(PeggedOrder {
+                owner,
+                ..order(id, Side::Ask, 1)
+            })
+            .unwrap();
+        }
+        let owner = Pubkey::new_unique();
+        assert_error(
+            book.insert_order(PeggedOrder {
+                owner,
+                ..order(99, Side::Ask, 1)
+            })
+            .unwrap_err(),
+            HybridDexError::OpenOrdersFull,
+        );
+    }
+
+    #[test]
+    fn bids_lock_quote_at_limit() {
+        let bid = order(1, Side::Bid, 5);
+        assert_eq!(bid.price(1_005).unwrap(), 995);
+        assert_eq!(bid.price(2_000).unwrap(), 1_000);
+        // 2.5 base at a 1000 limit with 3 base decimals
+        assert_eq!(bid.locked_amount(2_500, 3).unwrap(), 2_500);
+        assert_eq!(
+            order(2, Side::Ask, 5).locked_amount(2_500, 3).unwrap(),
+            2_500
+        );
+
+        assert_error(
+            bid.price(5).unwrap_err(),
+            HybridDexError::InvalidPeggedPrice,
+        );
+    }
+}
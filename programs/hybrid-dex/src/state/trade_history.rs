@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+
+use crate::{OpenedOrder, Side, TRADE_HISTORY_CAPACITY};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Trade {
+    /// Position in the market trade sequence, orders trades across ring buffer wraps
+    pub seq_num: u64,
+    pub order_id: u64,
+    pub price: u64,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub taker_side: Side,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Ring buffer of the last `TRADE_HISTORY_CAPACITY` trades of a market
+#[account]
+pub struct TradeHistory {
+    pub market: Pubkey,
+    /// Trades recorded since creation, the next trade overwrites `seq_num % capacity`
+    pub trade_seq_num: u64,
+    pub trades: Vec<Trade>,
+}
+
+impl Trade {
+    /// Fill of the resting `order`, sequenced once a trade history records it
+    pub fn new(
+        order: &OpenedOrder,
+        base_amount: u64,
+        quote_amount: u64,
+        taker_side: Side,
+        taker: Pubkey,
+        timestamp: i64,
+    ) -> Trade {
+        Trade {
+            seq_num: 0,
+            order_id: order.order_id,
+            price: order.price,
+            base_amount,
+            quote_amount,
+            taker_side,
+            maker: order.owner,
+            taker,
+            timestamp,
+        }
+    }
+}
+
+impl TradeHistory {
+    pub const DATA_SIZE: usize = 8
+        + std::mem::size_of::<TradeHistory>()
+        + std::mem::size_of::<Trade>() * TRADE_HISTORY_CAPACITY;
+
+    /// Record `trade` with the next sequence number, overwriting the oldest trade once the
+    /// buffer is full, returns the recorded trade
+    pub fn record(&mut self, trade: Trade) -> Trade {
+        let trade = Trade {
+            seq_num: self.trade_seq_num,
+            ..trade
+        };
+
+        if self.trades.len() < TRADE_HISTORY_CAPACITY {
+            self.trades.push(trade);
+        } else {
+            self.trades[(self.trade_seq_num % TRADE_HISTORY_CAPACITY as u64) as usize] = trade;
+        }
+
+        self.trade_seq_num = self.trade_seq_num.wrapping_add(1);
+
+        trade
+    }
+
+    /// Price of the newest recorded trade worth at least `min_quote_amount`
+    pub fn last_price(&self, min_quote_amount: u64) -> Option<u64> {
+        self.trades
+            .iter()
+            .filter(|trade| trade.quote_amount >= min_quote_amount)
+            .max_by_key(|trade| trade.seq_num)
+            .map(|trade| trade.price)
+    }
+
+    /// Recorded trades, newest first
+    pub fn recent(&self) -> Vec<Trade> {
+        let mut trades = self.trades.clone();
+        trades.sort_by_key(|trade| std::cmp::Reverse(trade.seq_num));
+        trades
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_keeps_last_trades() {
+        let mut history = TradeHistory {
+            market: Pubkey::default(),
+            trade_seq_num: 0,
+            trades: vec![],
+        };
+        let maker = Pubkey::new_unique();
+        let taker = Pubkey::new_unique();
+
+        let total = TRADE_HISTORY_CAPACITY as u64 + 3;
+        for order_id in 0..total {
+            let order = OpenedOrder {
+                order_id,
+                owner: maker,
+                price: 2,
+                quantity: 1,
+                created_at: 0,
+                account_index: 0,
+            };
+            history.record(Trade::new(&order, 1, 2, Side::Bid, taker, order_id as i64));
+        }
+
+        assert_eq!(history.trade_seq_num, total);
+        assert_eq!(history.trades.len(), TRADE_HISTORY_CAPACITY);
+
+        let recent = history.recent();
+        assert_eq!(recent[0].order_id, total - 1);
+        assert_eq!(recent[TRADE_HISTORY_CAPACITY - 1].order_id, 3);
+        assert_eq!(recent[0].maker, maker);
+        assert_eq!(recent[0].taker, taker);
+        assert_eq!(recent[0].timestamp, total as i64 - 1);
+        assert!(recent.windows(2).all(|w| w[0].seq_num == w[1].seq_num + 1));
+    }
+
+    #[test]
+    fn last_price_skips_small_trades() {
+        let mut history = TradeHistory {
+            market: Pubkey::default(),
+            trade_seq_num: 0,
+            trades: vec![],
+        };
+        assert_eq!(history.last_price(0), None);
+
+        let taker = Pubkey::new_unique();
+        for (order_id, price, quote_amount) in [(0, 20, 100), (1, 30, 150), (2, 10, 5)] {
+            let order = OpenedOrder {
+                order_id,
+                owner: Pubkey::new_unique(),
+                price,
+                quantity: 5,
+                created_at: 0,
+                account_index: 0,
+            };
+            history.record(Trade::new(&order, 5, quote_amount, Side::Ask, taker, 0));
+        }
+
+        assert_eq!(history.last_price(0), Some(10));
+        assert_eq!(history.last_price(100), Some(30));
+        assert_eq!(history.last_price(151), None);
+    }
+}
@@ -0,0 +1,248 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    locked_quote, HybridDexError, OpenedOrder, Side, MAX_TRIGGER_ORDERS_PER_USER,
+    TRIGGER_BOOK_CAPACITY,
+};
+
+/// Price a trigger order watches
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TriggerSource {
+    /// Price of the last recorded trade worth at least the market `min_trigger_trade_value`
+    LastPrice,
+    /// Market oracle price
+    Oracle,
+}
+
+/// Direction of the watched price move
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TriggerCondition {
+    /// Asks fire at or below the trigger price, bids at or above it
+    Stop,
+    /// Asks fire at or above the trigger price, bids at or below it
+    TakeProfit,
+}
+
+/// Order a trigger turns into once it fires
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TriggerOrderType {
+    /// Rests in the book at the limit price
+    Limit,
+    /// Fills against the book up to the limit price, the rest returns to free balance
+    Market,
+}
+
+/// Conditional order holding its funds until a keeper executes it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct TriggerOrder {
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub account_index: u8,
+    pub side: Side,
+    pub source: TriggerSource,
+    pub condition: TriggerCondition,
+    pub order_type: TriggerOrderType,
+    pub trigger_price: u64,
+    /// Price of the resulting limit order or worst fill price of a market order,
+    /// bids lock their quote value at it, zero leaves a market ask uncapped
+    pub limit_price: u64,
+    pub quantity: u64,
+    /// Lamports the executing keeper receives
+    pub bounty: u64,
+    pub created_at: i64,
+}
+
+impl TriggerOrder {
+    /// Watched `price` has crossed the trigger price
+    pub fn is_triggered(&self, price: u64) -> bool {
+        match (self.side, self.condition) {
+            (Side::Ask, TriggerCondition::Stop) | (Side::Bid, TriggerCondition::TakeProfit) => {
+                price <= self.trigger_price
+            }
+            (Side::Bid, TriggerCondition::Stop) | (Side::Ask, TriggerCondition::TakeProfit) => {
+                price >= self.trigger_price
+            }
+        }
+    }
+
+    /// Funds `quantity` of the order escrows, base of asks and quote at the limit of bids
+    pub fn locked_amount(&self, quantity: u64, base_decimal: u8) -> Result<u64> {
+        match self.side {
+            Side::Bid => locked_quote(quantity, self.limit_price, base_decimal),
+            Side::Ask => Ok(quantity),
+        }
+    }
+
+    /// Limit order resting in the book once a limit trigger fires at `now`
+    pub fn limit_order(&self, now: i64) -> OpenedOrder {
+        OpenedOrder {
+            order_id: self.order_id,
+            owner: self.owner,
+            price: self.limit_price,
+            quantity: self.quantity,
+            created_at: now,
+            account_index: self.account_index,
+        }
+    }
+}
+
+/// Trigger orders of a market waiting for their price, both sides in placement order,
+/// the account holds the keeper bounties on top of its rent
+#[account]
+pub struct TriggerBook {
+    pub market: Pubkey,
+    pub orders: Vec<TriggerOrder>,
+}
+
+impl TriggerBook {
+    pub const DATA_SIZE: usize = 8
+        + std::mem::size_of::<TriggerBook>()
+        + std::mem::size_of::<TriggerOrder>() * TRIGGER_BOOK_CAPACITY;
+
+    pub fn insert_order(&mut self, order: TriggerOrder) -> Result<()> {
+        // check trigger book capacity
+        require!(
+            self.orders.len() < TRIGGER_BOOK_CAPACITY,
+            HybridDexError::OpenOrdersFull
+        );
+        // check orders of the owner
+        require!(
+            self.orders
+                .iter()
+                .filter(|resting| resting.owner == order.owner)
+                .count()
+                < MAX_TRIGGER_ORDERS_PER_USER,
+            HybridDexError::OwnerBookOrdersFull
+        );
+        self.orders.push(order);
+
+        Ok(())
+    }
+
+    pub fn get_order(&self, order_id: u64) -> Result<TriggerOrder> {
+        self.orders
+            .iter()
+            .find(|order| order.order_id == order_id)
+            .copied()
+            .ok_or_else(|| error!(HybridDexError::OrderNotFound))
+    }
+
+    pub fn remove_order(&mut self, order_id: u64) -> Result<TriggerOrder> {
+        let index = self
+            .orders
+            .iter()
+            .position(|order| order.order_id == order_id)
+            .ok_or(HybridDexError::OrderNotFound)?;
+
+        Ok(self.orders.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::tests::assert_error;
+
+    fn order(side: Side, condition: TriggerCondition) -> TriggerOrder {
+        TriggerOrder {
+            order_id: 1,
+            owner: Pubkey::default(),
+            account_index: 0,
+            side,
+            source: TriggerSource::LastPrice,
+            condition,
+            order_type: TriggerOrderType::Market,
+            trigger_price: 1_000,
+            limit_price: 900,
+            quantity: 5,
+            bounty: 0,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn triggers_on_price_cross() {
+        let stop_loss = order(Side::Ask, TriggerCondition::Stop);
+        assert!(stop_loss.is_triggered(1_000));
+        assert!(stop_loss.is_triggered(999));
+        assert!(!stop_loss.is_triggered(1_001));
+
+        let take_profit = order(Side::Ask, TriggerCondition::TakeProfit);
+        assert!(take_profit.is_triggered(1_001));
+        assert!(!take_profit.is_triggered(999));
+
+        let buy_stop = order(Side::Bid, TriggerCondition::Stop);
+        assert!(buy_stop.is_triggered(1_001));
+        assert!(!buy_stop.is_triggered(999));
+
+        let buy_limit = order(Side::Bid, TriggerCondition::TakeProfit);
+        assert!(buy_limit.is_triggered(999));
+        assert!(!buy_limit.is_triggered(1_001));
+    }
+
+    #[test]
+    fn book_holds_capacity_orders() {
+        let mut book = TriggerBook {
+            market: Pubkey::default(),
+            orders: vec![],
+        };
+        for order_id in 0..TRIGGER_BOOK_CAPACITY as u64 {
+            book.insert_order(TriggerOrder {
+                order_id,
+                owner: Pubkey::new_unique(),
+                ..order(Side::Bid, TriggerCondition::Stop)
+            })
+            .unwrap();
+        }
+        assert_error(
+            book.insert_order(TriggerOrder {
+                owner: Pubkey::new_unique(),
+                ..order(Side::Ask, TriggerCondition::Stop)
+            })
+            .unwrap_err(),
+            HybridDexError::OpenOrdersFull,
+        );
+
+        assert_eq!(book.remove_order(3).unwrap().order_id, 3);
+        assert_error(
+            book.get_order(3).unwrap_err(),
+            HybridDexError::OrderNotFound,
+        );
+        // 5 base at a 900 limit with 1 base decimal
+        assert_eq!(book.get_order(4).unwrap().locked_amount(5, 1).unwrap(), 450);
+    }
+
+    #[test]
+    fn book_caps_orders_per_owner() {
+        let mut book = TriggerBook {
+            market: Pubkey::default(),
+            orders: vec![],
+        };
+        for order_id in 0..MAX_TRIGGER_ORDERS_PER_USER as u64 {
+            book.insert_order(TriggerOrder {
+                order_id,
+                ..order(Side::Ask, TriggerCondition::Stop)
+            })
+            .unwrap();
+        }
+        assert_error(
+            book.insert_order(order(Side::Bid, TriggerCondition::Stop))
+                .unwrap_err(),
+            HybridDexError::OwnerBookOrdersFull,
+        );
+
+        // other owners still place, and a removed order frees a slot
+        book.insert_order(TriggerOrder {
+            order_id: 10,
+            owner: Pubkey::new_unique(),
+            ..order(Side::Bid, TriggerCondition::Stop)
+        })
+        .unwrap();
+        book.remove_order(0).unwrap();
+        book.insert_order(TriggerOrder {
+            order_id: 11,
+            ..order(Side::Bid, TriggerCondition::Stop)
+        })
+        .unwrap();
+    }
+}
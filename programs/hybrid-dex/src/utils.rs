@@ -1,5 +1,10 @@
-use crate::error::HybridDexError;
-use anchor_lang::prelude::*;
+use crate::{error::HybridDexError, LegacyLayout, Side};
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+    Discriminator,
+};
+use anchor_spl::token::TokenAccount;
 
 pub fn fill_from_str<const N: usize>(name: &str) -> Result<[u8; N]> {
     let name_bytes = name.as_bytes();
@@ -11,3 +16,202 @@ pub fn fill_from_str<const N: usize>(name: &str) -> Result<[u8; N]> {
     name_[..name_bytes.len()].copy_from_slice(name_bytes);
     Ok(name_)
 }
+
+/// Checked `u64` arithmetic failing with `MathOverflow` instead of panicking or wrapping
+pub trait SafeMath: Sized {
+    fn safe_add(self, rhs: Self) -> Result<Self>;
+    fn safe_sub(self, rhs: Self) -> Result<Self>;
+    fn safe_mul(self, rhs: Self) -> Result<Self>;
+    fn safe_div(self, rhs: Self) -> Result<Self>;
+}
+
+impl SafeMath for u64 {
+    fn safe_add(self, rhs: u64) -> Result<u64> {
+        self.checked_add(rhs)
+            .ok_or_else(|| error!(HybridDexError::MathOverflow))
+    }
+
+    fn safe_sub(self, rhs: u64) -> Result<u64> {
+        self.checked_sub(rhs)
+            .ok_or_else(|| error!(HybridDexError::MathOverflow))
+    }
+
+    fn safe_mul(self, rhs: u64) -> Result<u64> {
+        self.checked_mul(rhs)
+            .ok_or_else(|| error!(HybridDexError::MathOverflow))
+    }
+
+    fn safe_div(self, rhs: u64) -> Result<u64> {
+        self.checked_div(rhs)
+            .ok_or_else(|| error!(HybridDexError::MathOverflow))
+    }
+}
+
+/// Narrow a wide intermediate result, never truncating
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(HybridDexError::MathOverflow))
+}
+
+/// Amount passed by user, price or order quantity must be non-zero
+pub fn require_non_zero(amount: u64) -> Result<u64> {
+    require!(amount > 0, HybridDexError::ZeroAmount);
+    Ok(amount)
+}
+
+/// Quote amount a bid of `quantity` base at `price` locks, rounded up for the vault
+pub fn locked_quote(quantity: u64, price: u64, base_decimal: u8) -> Result<u64> {
+    hybrid_dex_core::locked_quote_amount(quantity, price, base_decimal)
+        .ok_or_else(|| error!(HybridDexError::PriceOverflow))
+}
+
+/// Base and quote amounts of filling `amount` base of a resting `maker_side` order,
+/// the quote side rounds in the maker's favor
+pub fn fill_amounts(
+    maker_side: Side,
+    amount: u64,
+    price: u64,
+    base_decimal: u8,
+) -> Result<(u64, u64)> {
+    require_non_zero(price)?;
+    hybrid_dex_core::fill_amounts(maker_side.into(), amount, price, base_decimal)
+        .map_err(|err| error!(HybridDexError::from(err)))
+}
+
+pub use hybrid_dex_core::PriceObservation;
+
+/// Time weighted average price between an earlier `start` and a later `end`
+/// observation of `MarketStats::observe`
+pub fn twap(start: &PriceObservation, end: &PriceObservation) -> Result<u64> {
+    hybrid_dex_core::twap(start, end).map_err(|err| error!(HybridDexError::from(err)))
+}
+
+/// Decode a program account of type `T` stored in legacy layout `L`
+pub fn decode_legacy_account<T: Discriminator, L: AnchorDeserialize>(
+    account: &AccountInfo,
+) -> Result<L> {
+    require_keys_eq!(
+        *account.owner,
+        crate::ID,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == T::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    Ok(L::deserialize(&mut &data[8..])?)
+}
+
+/// Decode a program account of type `T` still in its legacy layout `L`,
+/// accounts already at `version` or later are rejected
+pub fn load_legacy_account<T: Discriminator, L: LegacyLayout>(
+    account: &AccountInfo,
+    version: u8,
+) -> Result<L> {
+    let legacy = decode_legacy_account::<T, L>(account)?;
+
+    // legacy accounts end before the version byte or are zero padded there
+    let account_version = account
+        .try_borrow_data()?
+        .get(8 + L::LEN)
+        .copied()
+        .unwrap_or(0);
+    require!(
+        account_version < version,
+        HybridDexError::AccountAlreadyMigrated
+    );
+
+    Ok(legacy)
+}
+
+/// Grow a program account to `space`, topping up rent from `payer` and zeroing the new bytes
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    if account.data_len() < space {
+        let lamports = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(account.lamports());
+        if lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: payer.clone(),
+                        to: account.clone(),
+                    },
+                ),
+                lamports,
+            )?;
+        }
+        account.realloc(space, true)?;
+    }
+
+    Ok(())
+}
+
+/// Move lamports out of a program owned account, which the system program can't debit
+pub fn withdraw_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from.lamports().safe_sub(amount)?;
+    **to.try_borrow_mut_lamports()? = to.lamports().safe_add(amount)?;
+    Ok(())
+}
+
+/// Token amount of a market vault, zero while its ATA isn't created
+pub fn vault_amount(vault: &AccountInfo) -> Result<u64> {
+    if vault.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(TokenAccount::try_deserialize(&mut &vault.data.borrow()[..])?.amount)
+}
+
+/// Write migrated state back to its account
+pub fn save_account<T: AccountSerialize>(account: &AccountInfo, state: &T) -> Result<()> {
+    state.try_serialize(&mut &mut account.data.borrow_mut()[..])
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn assert_error(err: Error, expected: HybridDexError) {
+        match err {
+            Error::AnchorError(err) => assert_eq!(err.error_code_number, u32::from(expected)),
+            Error::ProgramError(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn math_errors() {
+        assert_eq!(u64::MAX.safe_sub(1).unwrap(), u64::MAX - 1);
+        assert_error(
+            u64::MAX.safe_add(1).unwrap_err(),
+            HybridDexError::MathOverflow,
+        );
+        assert_error(0u64.safe_sub(1).unwrap_err(), HybridDexError::MathOverflow);
+        assert_error(
+            to_u64(u64::MAX as u128 + 1).unwrap_err(),
+            HybridDexError::MathOverflow,
+        );
+
+        assert_eq!(fill_amounts(Side::Bid, 7, 1_500_000, 6).unwrap(), (7, 10));
+        assert_eq!(locked_quote(7, 1_500_000, 6).unwrap(), 11);
+        assert_error(
+            fill_amounts(Side::Bid, 1, 0, 6).unwrap_err(),
+            HybridDexError::ZeroAmount,
+        );
+        assert_error(
+            fill_amounts(Side::Bid, 1, 500_000, 6).unwrap_err(),
+            HybridDexError::ZeroFill,
+        );
+        assert_error(
+            fill_amounts(Side::Ask, u64::MAX, u64::MAX, 0).unwrap_err(),
+            HybridDexError::PriceOverflow,
+        );
+    }
+}
@@ -0,0 +1,592 @@
+//! In-process runtime fixture shared by integration tests
+//!
+//! Tests run the program natively under `cargo test`, `cargo test-sbf` sets
+//! `SBF_OUT_DIR` and deploys the compiled `hybrid_dex.so` instead.
+
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{
+        clock::Clock, entrypoint::ProgramResult, instruction::Instruction, program_pack::Pack,
+    },
+    AccountDeserialize, AccountSerialize, AnchorDeserialize, AnchorSerialize, Discriminator,
+};
+use anchor_spl::{
+    associated_token::{get_associated_token_address, spl_associated_token_account},
+    token::spl_token,
+};
+use hybrid_dex::{
+    Book, BookV0, HybridDexError, Market, MarketAudit, OpenedOrderV0, PeggedBook, TriggerBook,
+    UserMarketOrders, ID, PYTH_ORACLE_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID,
+};
+use hybrid_dex_client::{check_solvency, instructions, MarketKeys, SolvencyReport};
+use hybrid_dex_core::{clmm, pyth};
+use solana_program_test::{
+    processor, BanksClientError, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext,
+};
+use solana_sdk::{
+    hash::Hash,
+    instruction::InstructionError,
+    signature::{Keypair, Signature, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const MAX_ORDERS_PER_USER: u64 = 8;
+pub const MAX_ORDERS_PER_BOOK: u64 = 16;
+
+pub const BASE_DECIMALS: u8 = 6;
+pub const QUOTE_DECIMALS: u8 = 6;
+
+/// One whole base or quote token in atomic units
+pub const UNIT: u64 = 1_000_000;
+
+// anchor entry ties account infos to a single lifetime the runtime can't name
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    hybrid_dex::entry(program_id, accounts, data)
+}
+
+pub struct TestEnv {
+    pub ctx: ProgramTestContext,
+    pub admin: Keypair,
+    /// Markets and open orders accounts audited after every processed transaction
+    pub markets: Vec<MarketKeys>,
+    pub open_orders: Vec<Pubkey>,
+    /// Signatures of sent transactions, sending one again needs a new blockhash
+    sent_signatures: HashSet<Signature>,
+}
+
+impl TestEnv {
+    /// Fresh runtime with initialized global pool
+    pub async fn new() -> TestEnv {
+        let program_test = ProgramTest::new("hybrid_dex", ID, processor!(process_instruction));
+        let ctx = program_test.start_with_context().await;
+        let admin = Keypair::from_bytes(&ctx.payer.to_bytes()).unwrap();
+
+        let mut env = TestEnv {
+            ctx,
+            admin,
+            markets: vec![],
+            open_orders: vec![],
+            sent_signatures: HashSet::new(),
+        };
+        env.process(
+            &[instructions::initialize(
+                &env.admin.pubkey(),
+                MAX_ORDERS_PER_USER,
+                MAX_ORDERS_PER_BOOK,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        env
+    }
+
+    /// Send instructions paid by admin, then assert every tracked market stays solvent
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let result = self.send(instructions, signers).await;
+
+        for market in self.markets.clone() {
+            if self.account_exists(&market.address).await {
+                self.assert_solvent(&market).await;
+            }
+        }
+
+        result
+    }
+
+    /// Send instructions paid by admin, `signers` adds to the payer signature
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let mut tx = self.transaction(instructions, signers).await;
+        // an identical transaction in the same blockhash returns the cached result
+        if !self.sent_signatures.insert(tx.signatures[0]) {
+            let blockhash = self
+                .ctx
+                .banks_client
+                .get_new_latest_blockhash(&tx.message.recent_blockhash)
+                .await
+                .unwrap();
+            tx = self.sign(instructions, signers, blockhash);
+            self.sent_signatures.insert(tx.signatures[0]);
+        }
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    async fn transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Transaction {
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await.unwrap();
+        self.sign(instructions, signers, blockhash)
+    }
+
+    fn sign(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+        blockhash: Hash,
+    ) -> Transaction {
+        let mut all_signers = vec![&self.admin];
+        all_signers.extend_from_slice(signers);
+
+        Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.admin.pubkey()),
+            &all_signers,
+            blockhash,
+        )
+    }
+
+    /// Tracked open orders accounts of `market` that still exist
+    pub async fn market_open_orders(
+        &mut self,
+        market: &MarketKeys,
+    ) -> Vec<(Pubkey, UserMarketOrders)> {
+        let mut open_orders = vec![];
+        for address in self.open_orders.clone() {
+            let Some(account) = self.ctx.banks_client.get_account(address).await.unwrap() else {
+                continue;
+            };
+            let state = UserMarketOrders::try_deserialize(&mut account.data.as_slice()).unwrap();
+            if state.market == market.address {
+                open_orders.push((address, state));
+            }
+        }
+        open_orders
+    }
+
+    /// Market keys without the accounts a market created before them lacks
+    pub async fn existing_keys(&mut self, market: &MarketKeys) -> MarketKeys {
+        let state: Market = self.account(&market.address).await;
+        MarketKeys::from_market(&state)
+    }
+
+    /// Off-chain solvency report of tracked open orders accounts
+    pub async fn solvency_report(&mut self, market: &MarketKeys) -> SolvencyReport {
+        let market = &self.existing_keys(market).await;
+        let state: Market = self.account(&market.address).await;
+        let bids: Book = self.account(&market.bids).await;
+        let asks: Book = self.account(&market.asks).await;
+        let pegged = match market.pegged_book {
+            Some(address) => self.account::<PeggedBook>(&address).await.orders,
+            None => vec![],
+        };
+        let triggers = match market.trigger_book {
+            Some(address) => self.account::<TriggerBook>(&address).await.orders,
+            None => vec![],
+        };
+        let open_orders: Vec<UserMarketOrders> = self
+            .market_open_orders(market)
+            .await
+            .into_iter()
+            .map(|(_, state)| state)
+            .collect();
+
+        let base_vault = self.token_balance(&market.address, &market.base_mint).await;
+        let quote_vault = self
+            .token_balance(&market.address, &market.quote_mint)
+            .await;
+
+        check_solvency(
+            &state,
+            &bids,
+            &asks,
+            &pegged,
+            &triggers,
+            &open_orders,
+            base_vault,
+            quote_vault,
+        )
+    }
+
+    /// Simulate a view instruction and decode the result it returns
+    pub async fn simulate<T: AnchorDeserialize>(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<T, TransactionError> {
+        let tx = self.transaction(&[instruction], &[]).await;
+        let simulation = self
+            .ctx
+            .banks_client
+            .simulate_transaction(tx)
+            .await
+            .unwrap();
+        simulation.result.unwrap()?;
+
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        Ok(T::try_from_slice(&return_data.data).unwrap())
+    }
+
+    /// Simulate `audit_market` over tracked open orders accounts, returning its totals
+    pub async fn audit_market(
+        &mut self,
+        market: &MarketKeys,
+    ) -> Result<MarketAudit, TransactionError> {
+        let open_orders: Vec<Pubkey> = self
+            .market_open_orders(market)
+            .await
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect();
+
+        let market = self.existing_keys(market).await;
+        self.simulate(instructions::audit_market(&market, &open_orders))
+            .await
+    }
+
+    /// Vaults hold every resting order and free balance, on-chain and off-chain
+    pub async fn assert_solvent(&mut self, market: &MarketKeys) {
+        let report = self.solvency_report(market).await;
+        assert!(report.is_solvent(), "{:?}", report.discrepancies);
+
+        let audit = self.audit_market(market).await.unwrap();
+        assert_eq!(audit, report.audit);
+    }
+
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &self.admin.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &self.admin.pubkey(),
+                    None,
+                    decimals,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+
+        mint.pubkey()
+    }
+
+    /// Funded wallet holding `amount` of each mint in its associated token accounts
+    pub async fn create_user(&mut self, mints: &[(Pubkey, u64)]) -> Keypair {
+        let user = Keypair::new();
+
+        let mut ixs = vec![system_instruction::transfer(
+            &self.admin.pubkey(),
+            &user.pubkey(),
+            10_000_000_000,
+        )];
+
+        for (mint, amount) in mints {
+            ixs.push(
+                spl_associated_token_account::instruction::create_associated_token_account(
+                    &self.admin.pubkey(),
+                    &user.pubkey(),
+                    mint,
+                    &spl_token::ID,
+                ),
+            );
+            ixs.push(
+                spl_token::instruction::mint_to(
+                    &spl_token::ID,
+                    mint,
+                    &get_associated_token_address(&user.pubkey(), mint),
+                    &self.admin.pubkey(),
+                    &[],
+                    *amount,
+                )
+                .unwrap(),
+            );
+        }
+
+        self.process(&ixs, &[]).await.unwrap();
+
+        user
+    }
+
+    /// Create market over new base and quote mints with the next market seed
+    pub async fn create_market(
+        &mut self,
+        authority: &Keypair,
+        max_orders_per_user: u64,
+        max_orders_per_book: u64,
+    ) -> Result<MarketKeys, BanksClientError> {
+        let base_mint = self.create_mint(BASE_DECIMALS).await;
+        let quote_mint = self.create_mint(QUOTE_DECIMALS).await;
+
+        let global_pool = self.global_pool().await;
+
+        self.process(
+            &[instructions::create_market(
+                &authority.pubkey(),
+                global_pool.market_seq_num,
+                &base_mint,
+                &quote_mint,
+                "SOL/USDC",
+                max_orders_per_user,
+                max_orders_per_book,
+            )],
+            &[authority],
+        )
+        .await?;
+
+        let market = MarketKeys::new(global_pool.market_seq_num, base_mint, quote_mint);
+        self.markets.push(market);
+
+        Ok(market)
+    }
+
+    pub async fn create_open_orders(&mut self, user: &Keypair, market: &MarketKeys) {
+        self.create_sub_account(user, market, 0).await;
+    }
+
+    /// Open orders account of `user` at `account_index`, tracked for solvency audits
+    pub async fn create_sub_account(
+        &mut self,
+        user: &Keypair,
+        market: &MarketKeys,
+        account_index: u8,
+    ) -> Pubkey {
+        self.process(
+            &[instructions::create_open_orders(
+                &user.pubkey(),
+                &market.address,
+                account_index,
+            )],
+            &[user],
+        )
+        .await
+        .unwrap();
+
+        let address = market.user_market_orders(&user.pubkey(), account_index);
+        self.open_orders.push(address);
+        address
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .expect("account not found");
+
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Overwrite program account state behind the program, keeping its size and lamports
+    pub async fn set_account_state<T: AccountSerialize>(&mut self, address: &Pubkey, state: &T) {
+        let mut account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .expect("account not found");
+
+        let mut data = vec![];
+        state.try_serialize(&mut data).unwrap();
+        account.data[..data.len()].copy_from_slice(&data);
+
+        self.ctx.set_account(address, &account.into());
+    }
+
+    /// Replace a program account of type `T` with `legacy` state serialized in its layout
+    /// before versioning, allocated as older program versions did
+    pub async fn set_legacy_account<T: Discriminator, L: AnchorSerialize>(
+        &mut self,
+        address: &Pubkey,
+        legacy: &L,
+    ) {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data.resize(8 + std::mem::size_of::<L>(), 0);
+
+        self.set_account_data(address, data).await;
+    }
+
+    /// Replace a book with `legacy` orders in their format before versioning,
+    /// allocated for `max_orders` as older program versions did
+    pub async fn set_legacy_book(&mut self, address: &Pubkey, legacy: &BookV0, max_orders: u64) {
+        let mut data = Book::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        data.resize(
+            Book::DATA_SIZE + std::mem::size_of::<OpenedOrderV0>() * max_orders as usize,
+            0,
+        );
+
+        self.set_account_data(address, data).await;
+    }
+
+    async fn set_account_data(&mut self, address: &Pubkey, data: Vec<u8>) {
+        let mut account = self
+            .ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        account.lamports = rent.minimum_balance(data.len());
+        account.data = data;
+
+        self.ctx.set_account(address, &account.into());
+    }
+
+    /// Move the runtime clock to `unix_timestamp`
+    pub async fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// Mock Pyth price account trading at `price * 10^exponent`, published at `publish_time`
+    pub fn set_pyth_price(
+        &mut self,
+        oracle: &Pubkey,
+        price: i64,
+        exponent: i32,
+        publish_time: i64,
+    ) {
+        self.set_pyth_price_confidence(oracle, price, 0, exponent, publish_time);
+    }
+
+    /// Mock Pyth price account trading at `price * 10^exponent` within `confidence` of it
+    pub fn set_pyth_price_confidence(
+        &mut self,
+        oracle: &Pubkey,
+        price: i64,
+        confidence: u64,
+        exponent: i32,
+        publish_time: i64,
+    ) {
+        let mut data = vec![0; pyth::ACCOUNT_SIZE];
+        data[pyth::MAGIC_OFFSET..][..4].copy_from_slice(&pyth::MAGIC.to_le_bytes());
+        data[pyth::VERSION_OFFSET..][..4].copy_from_slice(&pyth::VERSION.to_le_bytes());
+        data[pyth::ACCOUNT_TYPE_OFFSET..][..4]
+            .copy_from_slice(&pyth::PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[pyth::EXPONENT_OFFSET..][..4].copy_from_slice(&exponent.to_le_bytes());
+        data[pyth::PUBLISH_TIME_OFFSET..][..8].copy_from_slice(&publish_time.to_le_bytes());
+        data[pyth::PRICE_OFFSET..][..8].copy_from_slice(&price.to_le_bytes());
+        data[pyth::CONFIDENCE_OFFSET..][..8].copy_from_slice(&confidence.to_le_bytes());
+        data[pyth::STATUS_OFFSET..][..4].copy_from_slice(&pyth::STATUS_TRADING.to_le_bytes());
+
+        self.set_oracle_account(oracle, data, PYTH_ORACLE_PROGRAM_ID);
+    }
+
+    /// Mock Raydium CLMM pool between `mint_a` and `mint_b` recording to `observation`
+    pub fn set_clmm_pool(
+        &mut self,
+        pool: &Pubkey,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        observation: &Pubkey,
+    ) {
+        let mut data = vec![0; clmm::ACCOUNT_SIZE];
+        data[..8].copy_from_slice(&clmm::DISCRIMINATOR);
+        data[clmm::MINT_0_OFFSET..][..32].copy_from_slice(mint_a.min(mint_b).as_ref());
+        data[clmm::MINT_1_OFFSET..][..32].copy_from_slice(mint_a.max(mint_b).as_ref());
+        data[clmm::OBSERVATION_KEY_OFFSET..][..32].copy_from_slice(observation.as_ref());
+
+        self.set_oracle_account(pool, data, RAYDIUM_CLMM_PROGRAM_ID);
+    }
+
+    /// Mock Raydium CLMM observations of `(block_timestamp, tick_cumulative)`, oldest first
+    pub fn set_clmm_observations(&mut self, observation: &Pubkey, observations: &[(u32, i64)]) {
+        let mut data = vec![0; clmm::OBSERVATION_ACCOUNT_SIZE];
+        data[..8].copy_from_slice(&clmm::OBSERVATION_DISCRIMINATOR);
+        let latest = observations.len() as u16 - 1;
+        data[clmm::OBSERVATION_INDEX_OFFSET..][..2].copy_from_slice(&latest.to_le_bytes());
+        for (index, (timestamp, tick_cumulative)) in observations.iter().enumerate() {
+            let offset = clmm::OBSERVATIONS_OFFSET + index * clmm::OBSERVATION_SIZE;
+            data[offset..][..4].copy_from_slice(&timestamp.to_le_bytes());
+            data[offset + 4..][..8].copy_from_slice(&tick_cumulative.to_le_bytes());
+        }
+
+        self.set_oracle_account(observation, data, RAYDIUM_CLMM_PROGRAM_ID);
+    }
+
+    fn set_oracle_account(&mut self, address: &Pubkey, data: Vec<u8>, owner: Pubkey) {
+        let account = solana_sdk::account::Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.ctx.set_account(address, &account.into());
+    }
+
+    /// Drop an account as if it was never created
+    pub fn remove_account(&mut self, address: &Pubkey) {
+        self.ctx
+            .set_account(address, &solana_sdk::account::AccountSharedData::default());
+    }
+
+    pub async fn account_exists(&mut self, address: &Pubkey) -> bool {
+        self.ctx
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    pub async fn global_pool(&mut self) -> hybrid_dex::GlobalPool {
+        self.account(&hybrid_dex_client::global_pool_address())
+            .await
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(*address).await.unwrap()
+    }
+
+    pub async fn token_balance(&mut self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        let address = get_associated_token_address(owner, mint);
+
+        match self.ctx.banks_client.get_account(address).await.unwrap() {
+            Some(account) => {
+                spl_token::state::Account::unpack(&account.data)
+                    .unwrap()
+                    .amount
+            }
+            None => 0,
+        }
+    }
+}
+
+/// Transaction failed with `expected` program error
+pub fn assert_dex_error(result: Result<(), BanksClientError>, expected: HybridDexError) {
+    let err = result.expect_err("transaction should fail").unwrap();
+
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::Custom(expected.into())),
+    );
+}
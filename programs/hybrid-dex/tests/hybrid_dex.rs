@@ -0,0 +1,3110 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use hybrid_dex::{
+    BestPrices, Book, BookV0, GlobalPool, GlobalPoolV0, HybridDexError, Market, MarketAudit,
+    MarketOrderQuote, MarketStats, MarketV0, OpenedOrderV0, OracleKind, PeggedBook, PriceLevel,
+    Side, TradeHistory, TriggerBook, TriggerCondition, TriggerOrderParams, TriggerOrderType,
+    TriggerSource, UserMarketOrders, UserMarketOrdersV0, UserPosition, BOOK_VERSION,
+    DEFAULT_CANDLE_INTERVALS, GLOBAL_POOL_VERSION, MARKET_VERSION, MAX_BOOK_GROWTH,
+    MAX_ORACLE_STALENESS, MAX_PRICE_BAND_BPS, OPEN_ORDERS_VERSION, TRIGGER_KEEPER_BOUNTY,
+};
+use hybrid_dex_client::{
+    instructions::{self, TakeAccounts},
+    Asset, Discrepancy, MarketKeys,
+};
+use solana_sdk::{
+    instruction::InstructionError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn take_accounts(taker: &Keypair, maker: &Keypair) -> TakeAccounts {
+    TakeAccounts {
+        taker: taker.pubkey(),
+        taker_account_index: 0,
+        maker: maker.pubkey(),
+        maker_account_index: 0,
+    }
+}
+
+#[tokio::test]
+async fn market_lifecycle() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let global_pool = env.global_pool().await;
+    assert_eq!(global_pool.version, GLOBAL_POOL_VERSION);
+    assert_eq!(global_pool.admin, admin.pubkey());
+    assert_eq!(global_pool.max_orders_per_user, MAX_ORDERS_PER_USER);
+    assert_eq!(global_pool.max_orders_per_book, MAX_ORDERS_PER_BOOK);
+
+    // market limits are bounded by global config
+    let result = env
+        .create_market(&admin, MAX_ORDERS_PER_USER, MAX_ORDERS_PER_BOOK + 1)
+        .await
+        .map(|_| ());
+    assert_dex_error(result, HybridDexError::InvalidOrderLimit);
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+
+    let state: Market = env.account(&market.address).await;
+    assert_eq!(state.seed, 0);
+    assert_eq!(state.version, MARKET_VERSION);
+    assert_eq!(state.market_authority, admin.pubkey());
+    assert_eq!(state.base_mint, market.base_mint);
+    assert_eq!(state.quote_mint, market.quote_mint);
+    assert_eq!(state.base_decimal, BASE_DECIMALS);
+    assert_eq!(state.bids, market.bids);
+    assert_eq!(state.asks, market.asks);
+    assert_eq!(state.max_orders_per_user, 4);
+    assert_eq!(state.max_orders_per_book, 8);
+    assert!(state.has_trade_history);
+    assert!(state.has_market_stats);
+    assert!(state.has_pegged_book);
+    assert!(state.has_trigger_book);
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.side, Side::Bid);
+    assert_eq!(bids.market, market.address);
+
+    let asks: Book = env.account(&market.asks).await;
+    assert_eq!(asks.side, Side::Ask);
+
+    let trade_history: TradeHistory = env.account(&market.trade_history.unwrap()).await;
+    assert_eq!(trade_history.market, market.address);
+    assert_eq!(trade_history.trade_seq_num, 0);
+
+    let stats: MarketStats = env.account(&market.stats.unwrap()).await;
+    assert_eq!(stats.market, market.address);
+    assert_eq!(stats.last_price, 0);
+    assert_eq!(
+        stats
+            .candle_series
+            .iter()
+            .map(|series| series.interval)
+            .collect::<Vec<_>>(),
+        DEFAULT_CANDLE_INTERVALS
+    );
+
+    let global_pool = env.global_pool().await;
+    assert_eq!(global_pool.total_market_count, 1);
+    assert_eq!(global_pool.market_seq_num, 1);
+
+    // only market authority closes the market
+    let other = env.create_user(&[]).await;
+    let result = env
+        .process(
+            &[instructions::close_market(&other.pubkey(), &market)],
+            &[&other],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidCloseMarketAdmin);
+
+    // accounts the market has close with it
+    let without_history = MarketKeys {
+        trade_history: None,
+        ..market
+    };
+    assert_dex_error(
+        env.process(
+            &[instructions::close_market(
+                &admin.pubkey(),
+                &without_history,
+            )],
+            &[],
+        )
+        .await,
+        HybridDexError::TradeHistoryRequired,
+    );
+    let without_stats = MarketKeys {
+        stats: None,
+        ..market
+    };
+    assert_dex_error(
+        env.process(
+            &[instructions::close_market(&admin.pubkey(), &without_stats)],
+            &[],
+        )
+        .await,
+        HybridDexError::MarketStatsRequired,
+    );
+
+    env.process(&[instructions::close_market(&admin.pubkey(), &market)], &[])
+        .await
+        .unwrap();
+
+    assert!(!env.account_exists(&market.address).await);
+    assert!(!env.account_exists(&market.bids).await);
+    assert!(!env.account_exists(&market.asks).await);
+    assert!(!env.account_exists(&market.trade_history.unwrap()).await);
+    assert!(!env.account_exists(&market.stats.unwrap()).await);
+    assert_eq!(env.global_pool().await.total_market_count, 0);
+}
+
+#[tokio::test]
+async fn place_and_cancel_orders() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let maker = env
+        .create_user(&[
+            (market.base_mint, 100 * UNIT),
+            (market.quote_mint, 100 * UNIT),
+        ])
+        .await;
+    env.create_open_orders(&maker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let open_orders = market.user_market_orders(&maker_key, 0);
+
+    // bid of 5 base locks its 10 quote value in vault
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            5 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // ask locks base quantity in vault
+    env.process(
+        &[instructions::place_sell_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            3 * UNIT,
+            4 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        90 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&maker_key, &market.base_mint).await,
+        96 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&market.address, &market.quote_mint).await,
+        10 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&market.address, &market.base_mint).await,
+        4 * UNIT
+    );
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders_count, 1);
+    assert_eq!(bids.orders[0].order_id, 0);
+    assert_eq!(bids.orders[0].owner, maker_key);
+    assert_eq!(bids.orders[0].price, 2 * UNIT);
+    assert_eq!(bids.orders[0].quantity, 5 * UNIT);
+
+    let asks: Book = env.account(&market.asks).await;
+    assert_eq!(asks.orders_count, 1);
+    assert_eq!(asks.orders[0].order_id, 1);
+
+    let state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(state.opened_orders_count, 2);
+    assert_eq!(state.quote_deposit_total, 10 * UNIT);
+    assert_eq!(state.base_deposit_total, 4 * UNIT);
+
+    // market with resting orders can't be closed
+    let result = env
+        .process(&[instructions::close_market(&admin.pubkey(), &market)], &[])
+        .await;
+    assert_dex_error(result, HybridDexError::NonEmptyMarket);
+
+    // cancel releases funds to free balance, vault keeps them
+    env.process(
+        &[
+            instructions::cancel_buy_order(&maker_key, &maker_key, 0, &market, 0),
+            instructions::cancel_sell_order(&maker_key, &maker_key, 0, &market, 1),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(state.opened_orders_count, 0);
+    assert_eq!(state.quote_deposit_total, 0);
+    assert_eq!(state.base_deposit_total, 0);
+    assert_eq!(state.quote_free, 10 * UNIT);
+    assert_eq!(state.base_free, 4 * UNIT);
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        90 * UNIT
+    );
+
+    let result = env
+        .process(
+            &[instructions::cancel_buy_order(
+                &maker_key, &maker_key, 0, &market, 0,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::OrderNotFound);
+
+    // market with empty books can't be closed before free balances are withdrawn
+    let result = env
+        .process(&[instructions::close_market(&admin.pubkey(), &market)], &[])
+        .await;
+    assert_dex_error(result, HybridDexError::NonEmptyMarketVault);
+
+    // next bid is funded from free balance without token transfer
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            3 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        90 * UNIT
+    );
+    let state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(state.quote_free, 4 * UNIT);
+    assert_eq!(state.quote_deposit_total, 6 * UNIT);
+
+    env.process(
+        &[
+            instructions::cancel_buy_order(&maker_key, &maker_key, 0, &market, 2),
+            instructions::withdraw(&maker_key, &maker_key, 0, &market, 4 * UNIT, 10 * UNIT),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        100 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&maker_key, &market.base_mint).await,
+        100 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&market.address, &market.quote_mint).await,
+        0
+    );
+    assert_eq!(
+        env.token_balance(&market.address, &market.base_mint).await,
+        0
+    );
+
+    env.process(&[instructions::close_market(&admin.pubkey(), &market)], &[])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn close_market_created_before_pegged_and_trigger_orders() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+
+    // close and audit count the pegged and trigger books the market has
+    let without_pegged_book = MarketKeys {
+        pegged_book: None,
+        ..market
+    };
+    assert_dex_error(
+        env.process(
+            &[instructions::audit_market(&without_pegged_book, &[])],
+            &[],
+        )
+        .await,
+        HybridDexError::PeggedBookRequired,
+    );
+    assert_dex_error(
+        env.process(
+            &[instructions::close_market(
+                &admin.pubkey(),
+                &without_pegged_book,
+            )],
+            &[],
+        )
+        .await,
+        HybridDexError::PeggedBookRequired,
+    );
+    let without_trigger_book = MarketKeys {
+        trigger_book: None,
+        ..market
+    };
+    assert_dex_error(
+        env.process(
+            &[instructions::audit_market(&without_trigger_book, &[])],
+            &[],
+        )
+        .await,
+        HybridDexError::TriggerBookRequired,
+    );
+    assert_dex_error(
+        env.process(
+            &[instructions::close_market(
+                &admin.pubkey(),
+                &without_trigger_book,
+            )],
+            &[],
+        )
+        .await,
+        HybridDexError::TriggerBookRequired,
+    );
+
+    // markets created before pegged and trigger orders have no such book to check or close
+    env.remove_account(&market.pegged_book.unwrap());
+    env.remove_account(&market.trigger_book.unwrap());
+    let state: Market = env.account(&market.address).await;
+    let state = Market {
+        has_pegged_book: false,
+        has_trigger_book: false,
+        ..state
+    };
+    env.set_account_state(&market.address, &state).await;
+    let market = MarketKeys {
+        pegged_book: None,
+        trigger_book: None,
+        ..market
+    };
+    assert_eq!(env.audit_market(&market).await, Ok(MarketAudit::default()));
+
+    env.process(&[instructions::close_market(&admin.pubkey(), &market)], &[])
+        .await
+        .unwrap();
+    assert!(!env.account_exists(&market.address).await);
+}
+
+#[tokio::test]
+async fn take_orders() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let funds = [
+        (market.base_mint, 100 * UNIT),
+        (market.quote_mint, 100 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let taker = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    env.create_open_orders(&taker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let taker_key = taker.pubkey();
+    let accounts = take_accounts(&taker, &maker);
+
+    // bid of 5 base at 2 quote per base
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            5 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    env.process(
+        &[instructions::take_buy_order(&accounts, &market, 0)],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&maker_key, &market.base_mint).await,
+        105 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        90 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.base_mint).await,
+        95 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.quote_mint).await,
+        110 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&market.address, &market.quote_mint).await,
+        0
+    );
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders_count, 0);
+
+    // ask of 3 base at 4 quote per base
+    env.process(
+        &[instructions::place_sell_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            4 * UNIT,
+            3 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    env.process(
+        &[instructions::take_sell_order(&accounts, &market, 1)],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&maker_key, &market.base_mint).await,
+        102 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        102 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.base_mint).await,
+        98 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.quote_mint).await,
+        98 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&market.address, &market.base_mint).await,
+        0
+    );
+
+    let asks: Book = env.account(&market.asks).await;
+    assert_eq!(asks.orders_count, 0);
+
+    let state: Market = env.account(&market.address).await;
+    assert_eq!(state.base_total_volume, 8 * UNIT);
+    assert_eq!(state.quote_total_volume, 22 * UNIT);
+
+    let maker_state: UserMarketOrders =
+        env.account(&market.user_market_orders(&maker_key, 0)).await;
+    assert_eq!(maker_state.opened_orders_count, 0);
+    assert_eq!(maker_state.quote_deposit_total, 0);
+    assert_eq!(maker_state.base_deposit_total, 0);
+    assert_eq!(maker_state.base_total_volume, 8 * UNIT);
+
+    let taker_state: UserMarketOrders =
+        env.account(&market.user_market_orders(&taker_key, 0)).await;
+    assert_eq!(taker_state.quote_total_volume, 22 * UNIT);
+
+    // both fills are recorded, newest first
+    let trade_history: TradeHistory = env.account(&market.trade_history.unwrap()).await;
+    assert_eq!(trade_history.trade_seq_num, 2);
+
+    let trades = trade_history.recent();
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[0].seq_num, 1);
+    assert_eq!(trades[0].order_id, 1);
+    assert_eq!(trades[0].taker_side, Side::Bid);
+    assert_eq!(trades[0].price, 4 * UNIT);
+    assert_eq!(trades[0].base_amount, 3 * UNIT);
+    assert_eq!(trades[0].quote_amount, 12 * UNIT);
+    assert_eq!(trades[1].order_id, 0);
+    assert_eq!(trades[1].taker_side, Side::Ask);
+    assert_eq!(trades[1].base_amount, 5 * UNIT);
+    assert_eq!(trades[1].quote_amount, 10 * UNIT);
+    assert_eq!(trades[1].maker, maker_key);
+    assert_eq!(trades[1].taker, taker_key);
+
+    // both fills land in the same candles of the frozen test clock
+    let stats: MarketStats = env.account(&market.stats.unwrap()).await;
+    assert_eq!(stats.last_price, 4 * UNIT);
+    assert_eq!(
+        stats.volume_24h(stats.last_trade_at).unwrap(),
+        (8 * UNIT, 22 * UNIT)
+    );
+
+    let candles = stats.candles(3600).unwrap();
+    assert_eq!(candles.len(), 1);
+    assert_eq!(
+        (
+            candles[0].open,
+            candles[0].high,
+            candles[0].low,
+            candles[0].close
+        ),
+        (2 * UNIT, 4 * UNIT, 2 * UNIT, 4 * UNIT)
+    );
+    assert_eq!(candles[0].base_volume, 8 * UNIT);
+    assert_eq!(candles[0].quote_volume, 22 * UNIT);
+
+    let result = env
+        .process(
+            &[instructions::take_sell_order(&accounts, &market, 1)],
+            &[&taker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::OrderNotFound);
+}
+
+#[tokio::test]
+async fn partial_take_orders() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let funds = [
+        (market.base_mint, 100 * UNIT),
+        (market.quote_mint, 100 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let taker = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    env.create_open_orders(&taker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let taker_key = taker.pubkey();
+    let accounts = take_accounts(&taker, &maker);
+
+    env.process(
+        &[
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 2 * UNIT, 5 * UNIT),
+            instructions::place_sell_order(&maker_key, &maker_key, 0, &market, 4 * UNIT, 3 * UNIT),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // take 2 of 5 base from the bid, receiving 4 quote
+    env.process(
+        &[instructions::partial_take_buy_order(
+            &accounts,
+            &market,
+            0,
+            2 * UNIT,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&maker_key, &market.base_mint).await,
+        99 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.base_mint).await,
+        98 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.quote_mint).await,
+        104 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&market.address, &market.quote_mint).await,
+        6 * UNIT
+    );
+
+    // take 1 of 3 base from the ask, paying 4 quote
+    env.process(
+        &[instructions::partial_take_sell_order(
+            &accounts, &market, 1, UNIT,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        94 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.base_mint).await,
+        99 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.quote_mint).await,
+        100 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&market.address, &market.base_mint).await,
+        2 * UNIT
+    );
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders[0].quantity, 3 * UNIT);
+    let asks: Book = env.account(&market.asks).await;
+    assert_eq!(asks.orders[0].quantity, 2 * UNIT);
+
+    let maker_state: UserMarketOrders =
+        env.account(&market.user_market_orders(&maker_key, 0)).await;
+    assert_eq!(maker_state.opened_orders_count, 2);
+    assert_eq!(maker_state.quote_deposit_total, 6 * UNIT);
+    assert_eq!(maker_state.base_deposit_total, 2 * UNIT);
+
+    let result = env
+        .process(
+            &[instructions::partial_take_sell_order(
+                &accounts,
+                &market,
+                1,
+                2 * UNIT,
+            )],
+            &[&taker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::PartialOrderAmountExceed);
+
+    // bid of 5 atomic base at half a quote per base locks 3 quote, rounded up
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            UNIT / 2,
+            5,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // one atomic base unit is worth half a quote unit, too small to fill
+    let result = env
+        .process(
+            &[instructions::partial_take_buy_order(
+                &accounts, &market, 2, 1,
+            )],
+            &[&taker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::ZeroFill);
+
+    // three base units get one quote unit rounded down for the maker, the bid
+    // releases two of its locked quote and the dust returns to maker free balance
+    let taker_quote = env.token_balance(&taker_key, &market.quote_mint).await;
+    env.process(
+        &[instructions::partial_take_buy_order(
+            &accounts, &market, 2, 3,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.token_balance(&taker_key, &market.quote_mint).await,
+        taker_quote + 1
+    );
+
+    let maker_state: UserMarketOrders =
+        env.account(&market.user_market_orders(&maker_key, 0)).await;
+    assert_eq!(maker_state.quote_deposit_total, 6 * UNIT + 1);
+    assert_eq!(maker_state.quote_free, 1);
+}
+
+#[tokio::test]
+async fn order_limits_and_authority() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 1, 8).await.unwrap();
+    let funds = [
+        (market.base_mint, 100 * UNIT),
+        (market.quote_mint, 100 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let other = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    env.create_open_orders(&other, &market).await;
+
+    let maker_key = maker.pubkey();
+    let other_key = other.pubkey();
+
+    let result = env
+        .process(
+            &[instructions::place_buy_order(
+                &maker_key,
+                &maker_key,
+                0,
+                &market,
+                2 * UNIT,
+                0,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::ZeroAmount);
+
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            5 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // market allows one open order per user
+    let result = env
+        .process(
+            &[instructions::place_sell_order(
+                &maker_key,
+                &maker_key,
+                0,
+                &market,
+                3 * UNIT,
+                UNIT,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::OpenOrdersFull);
+
+    // stranger can't cancel maker order
+    let result = env
+        .process(
+            &[instructions::cancel_buy_order(
+                &other_key, &maker_key, 0, &market, 0,
+            )],
+            &[&other],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidDelegate);
+
+    // maker open orders must own the taken order
+    let result = env
+        .process(
+            &[instructions::take_buy_order(
+                &take_accounts(&maker, &other),
+                &market,
+                0,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::IncorrectMakerAddress);
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders_count, 1);
+    assert_eq!(
+        env.token_balance(&market.address, &market.quote_mint).await,
+        10 * UNIT
+    );
+}
+
+#[tokio::test]
+async fn close_open_orders_account() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let maker = env.create_user(&[(market.quote_mint, 10 * UNIT)]).await;
+    env.create_open_orders(&maker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let open_orders = market.user_market_orders(&maker_key, 0);
+    let close = instructions::close_open_orders(&maker_key, &market.address, 0);
+
+    // resting orders keep the account open
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let result = env.process(&[close.clone()], &[&maker]).await;
+    assert_dex_error(result, HybridDexError::NonEmptyOpenOrders);
+
+    // cancelled order leaves a free balance
+    env.process(
+        &[instructions::cancel_buy_order(
+            &maker_key, &maker_key, 0, &market, 0,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let maker_state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(maker_state.opened_orders_count, 0);
+    assert_eq!(maker_state.quote_free, 2 * UNIT);
+
+    let result = env.process(&[close.clone()], &[&maker]).await;
+    assert_dex_error(result, HybridDexError::NonEmptyOpenOrders);
+
+    // deposits locked without a resting order keep the account open too
+    let mut state = maker_state.clone();
+    state.quote_free = 0;
+    state.base_deposit_total = 1;
+    env.set_account_state(&open_orders, &state).await;
+
+    let result = env.send(&[close.clone()], &[&maker]).await;
+    assert_dex_error(result, HybridDexError::NonEmptyOpenOrders);
+
+    env.set_account_state(&open_orders, &maker_state).await;
+
+    // stranger can't close the maker account
+    let other = env.create_user(&[]).await;
+    env.process(
+        &[instructions::withdraw(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            0,
+            2 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let mut stranger_close = close.clone();
+    stranger_close.accounts[0].pubkey = other.pubkey();
+    let result = env.process(&[stranger_close], &[&other]).await;
+    assert!(result.is_err());
+
+    // empty account closes and refunds its rent to the owner
+    let rent = env.lamports(&open_orders).await;
+    let maker_lamports = env.lamports(&maker_key).await;
+
+    env.process(&[close], &[&maker]).await.unwrap();
+
+    assert!(!env.account_exists(&open_orders).await);
+    assert_eq!(env.lamports(&maker_key).await, maker_lamports + rent);
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        10 * UNIT
+    );
+}
+
+#[tokio::test]
+async fn delegated_trading() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let maker = env.create_user(&[(market.quote_mint, 10 * UNIT)]).await;
+    let delegate = env.create_user(&[]).await;
+    env.create_open_orders(&maker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let delegate_key = delegate.pubkey();
+    let open_orders = market.user_market_orders(&maker_key, 0);
+
+    // delegate trades from the owner free balance
+    env.process(
+        &[
+            instructions::deposit(&maker_key, &maker_key, 0, &market, 0, 10 * UNIT),
+            instructions::set_delegate(&maker_key, &market.address, 0, Some(delegate_key)),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let maker_state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(maker_state.delegate, delegate_key);
+
+    env.process(
+        &[instructions::place_buy_order(
+            &delegate_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            5 * UNIT,
+        )],
+        &[&delegate],
+    )
+    .await
+    .unwrap();
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders[0].owner, maker_key);
+    let maker_state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(maker_state.opened_orders_count, 1);
+    assert_eq!(maker_state.quote_free, 0);
+
+    env.process(
+        &[instructions::cancel_buy_order(
+            &delegate_key,
+            &maker_key,
+            0,
+            &market,
+            0,
+        )],
+        &[&delegate],
+    )
+    .await
+    .unwrap();
+
+    let maker_state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(maker_state.opened_orders_count, 0);
+    assert_eq!(maker_state.quote_free, 10 * UNIT);
+
+    // delegate can't withdraw, even to the owner token accounts
+    let result = env
+        .process(
+            &[instructions::withdraw(
+                &delegate_key,
+                &maker_key,
+                0,
+                &market,
+                0,
+                UNIT,
+            )],
+            &[&delegate],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidAccountOwner);
+
+    // only the owner sets the delegate
+    let result = env
+        .process(
+            &[instructions::set_delegate(
+                &delegate_key,
+                &market.address,
+                0,
+                None,
+            )],
+            &[&delegate],
+        )
+        .await;
+    assert!(result.is_err());
+
+    // cleared delegate loses access to the orders it placed
+    env.process(
+        &[instructions::place_buy_order(
+            &delegate_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            UNIT,
+        )],
+        &[&delegate],
+    )
+    .await
+    .unwrap();
+    env.process(
+        &[instructions::set_delegate(
+            &maker_key,
+            &market.address,
+            0,
+            None,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let maker_state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(maker_state.delegate, Pubkey::default());
+
+    let result = env
+        .process(
+            &[instructions::cancel_buy_order(
+                &delegate_key,
+                &maker_key,
+                0,
+                &market,
+                1,
+            )],
+            &[&delegate],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidDelegate);
+
+    let result = env
+        .process(
+            &[instructions::place_buy_order(
+                &delegate_key,
+                &maker_key,
+                0,
+                &market,
+                2 * UNIT,
+                UNIT,
+            )],
+            &[&delegate],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidDelegate);
+
+    // owner keeps full control
+    env.process(
+        &[
+            instructions::cancel_buy_order(&maker_key, &maker_key, 0, &market, 1),
+            instructions::withdraw(&maker_key, &maker_key, 0, &market, 0, 10 * UNIT),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        10 * UNIT
+    );
+}
+
+#[tokio::test]
+async fn resize_book_in_steps() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+    let admin_key = admin.pubkey();
+
+    env.process(
+        &[instructions::change_config(&admin_key, None, Some(1_000))],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let maker = env.create_user(&[(market.base_mint, 10 * UNIT)]).await;
+    env.create_open_orders(&maker, &market).await;
+    let maker_key = maker.pubkey();
+
+    let result = env
+        .process(
+            &[instructions::resize_book(&maker_key, &market, 16)],
+            &[&maker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidMarketAuthority);
+
+    // one realloc can't add more than the runtime growth limit
+    let result = env
+        .process(
+            &[instructions::resize_book(
+                &admin_key,
+                &market,
+                8 + MAX_BOOK_GROWTH + 1,
+            )],
+            &[],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::BookGrowthExceeded);
+
+    // larger books grow in steps
+    for max_orders_per_book in [8 + MAX_BOOK_GROWTH, 8 + 2 * MAX_BOOK_GROWTH] {
+        env.process(
+            &[instructions::resize_book(
+                &admin_key,
+                &market,
+                max_orders_per_book,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let state: Market = env.account(&market.address).await;
+        assert_eq!(state.max_orders_per_book, max_orders_per_book);
+        for book in [market.bids, market.asks] {
+            let account = env
+                .ctx
+                .banks_client
+                .get_account(book)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(account.data.len(), Book::size(max_orders_per_book));
+        }
+    }
+
+    // books never shrink
+    let result = env
+        .process(&[instructions::resize_book(&admin_key, &market, 16)], &[])
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidOrderLimit);
+
+    // resized books keep trading
+    env.process(
+        &[instructions::place_sell_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let asks: Book = env.account(&market.asks).await;
+    assert_eq!(asks.orders_count, 1);
+}
+
+#[tokio::test]
+async fn sub_account_orders() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let maker = env.create_user(&[(market.quote_mint, 100 * UNIT)]).await;
+    env.create_open_orders(&maker, &market).await;
+    let sub_account = env.create_sub_account(&maker, &market, 1).await;
+
+    let maker_key = maker.pubkey();
+    let open_orders = market.user_market_orders(&maker_key, 0);
+    assert_ne!(sub_account, open_orders);
+
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            1,
+            &market,
+            2 * UNIT,
+            5 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders[0].owner, maker_key);
+    assert_eq!(bids.orders[0].account_index, 1);
+
+    let state: UserMarketOrders = env.account(&sub_account).await;
+    assert_eq!(state.account_index, 1);
+    assert_eq!(state.opened_orders_count, 1);
+    assert_eq!(state.quote_deposit_total, 10 * UNIT);
+
+    let state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(state.account_index, 0);
+    assert_eq!(state.opened_orders_count, 0);
+
+    // order belongs to the sub account, not to other accounts of the same wallet
+    let result = env
+        .process(
+            &[instructions::cancel_buy_order(
+                &maker_key, &maker_key, 0, &market, 0,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::IncorrectMakerAddress);
+
+    env.process(
+        &[instructions::cancel_buy_order(
+            &maker_key, &maker_key, 1, &market, 0,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders_count, 0);
+
+    let state: UserMarketOrders = env.account(&sub_account).await;
+    assert_eq!(state.opened_orders_count, 0);
+    assert_eq!(state.quote_deposit_total, 0);
+    assert_eq!(state.quote_free, 10 * UNIT);
+}
+
+#[tokio::test]
+async fn audit_market_detects_insolvency() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let maker = env.create_user(&[(market.quote_mint, 100 * UNIT)]).await;
+    env.create_open_orders(&maker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let open_orders = market.user_market_orders(&maker_key, 0);
+
+    // 4 quote locked in a bid and 6 quote left free in the vault
+    env.process(
+        &[
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 2 * UNIT, 5 * UNIT),
+            instructions::cancel_buy_order(&maker_key, &maker_key, 0, &market, 0),
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 2 * UNIT, 2 * UNIT),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.audit_market(&market).await,
+        Ok(MarketAudit {
+            quote_locked: 4 * UNIT,
+            quote_free: 6 * UNIT,
+            quote_vault: 10 * UNIT,
+            ..MarketAudit::default()
+        })
+    );
+
+    // each open orders account is counted once
+    let result = env
+        .send(
+            &[instructions::audit_market(
+                &market,
+                &[open_orders, open_orders],
+            )],
+            &[],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidAuditAccount);
+
+    // drain the quote vault behind the program
+    let mut account = env
+        .ctx
+        .banks_client
+        .get_account(market.quote_vault)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut vault = spl_token::state::Account::unpack(&account.data).unwrap();
+    vault.amount = 9 * UNIT;
+    spl_token::state::Account::pack(vault, &mut account.data).unwrap();
+    env.ctx.set_account(&market.quote_vault, &account.into());
+
+    let report = env.solvency_report(&market).await;
+    assert_eq!(
+        report.discrepancies,
+        vec![Discrepancy::VaultShortfall {
+            asset: Asset::Quote,
+            required: 10 * UNIT,
+            vault: 9 * UNIT,
+        }]
+    );
+    assert_eq!(
+        env.audit_market(&market).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(HybridDexError::VaultInsolvent.into())
+        ))
+    );
+}
+
+#[tokio::test]
+async fn migrate_legacy_books() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 8, 8).await.unwrap();
+    let maker = env
+        .create_user(&[
+            (market.base_mint, 10 * UNIT),
+            (market.quote_mint, 100 * UNIT),
+        ])
+        .await;
+    env.create_open_orders(&maker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let open_orders = market.user_market_orders(&maker_key, 0);
+
+    // bids locking 10 quote, 21 and 3 atomic quote, asks locking 3 base
+    env.process(
+        &[
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 2 * UNIT, 5 * UNIT),
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 3 * UNIT, 7),
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 3 * UNIT, 1),
+            instructions::place_sell_order(&maker_key, &maker_key, 0, &market, 5 * UNIT, UNIT),
+            instructions::place_sell_order(&maker_key, &maker_key, 0, &market, 4 * UNIT, 2 * UNIT),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // rewrite them as legacy worst first books, bids of 22 and 2 atomic quote keep the total
+    let mut state: Market = env.account(&market.address).await;
+    state.book_version = 0;
+    env.set_account_state(&market.address, &state).await;
+
+    let legacy_order = |order_id: u64, price: u64, quantity: u64| OpenedOrderV0 {
+        order_id,
+        owner: maker_key,
+        price,
+        quantity,
+        created_at: 0,
+    };
+    let legacy_bids = BookV0 {
+        side: Side::Bid,
+        market: market.address,
+        orders_count: 3,
+        orders: vec![
+            legacy_order(0, 2 * UNIT, 10 * UNIT),
+            legacy_order(1, 3 * UNIT, 22),
+            legacy_order(2, 3 * UNIT, 2),
+        ],
+    };
+    let legacy_asks = BookV0 {
+        side: Side::Ask,
+        market: market.address,
+        orders_count: 2,
+        orders: vec![
+            legacy_order(3, 5 * UNIT, UNIT),
+            legacy_order(4, 4 * UNIT, 2 * UNIT),
+        ],
+    };
+    env.set_legacy_book(&market.bids, &legacy_bids, 8).await;
+    env.set_legacy_book(&market.asks, &legacy_asks, 8).await;
+
+    // legacy books can't be traded or read until they migrate
+    let result = env
+        .send(
+            &[instructions::place_buy_order(
+                &maker_key,
+                &maker_key,
+                0,
+                &market,
+                2 * UNIT,
+                UNIT,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert!(result.is_err());
+
+    let result = env
+        .send(
+            &[instructions::cancel_sell_order(
+                &maker_key, &maker_key, 0, &market, 4,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert!(result.is_err());
+
+    let result = env
+        .send(
+            &[instructions::migrate_books(
+                &maker_key,
+                &market,
+                &[open_orders],
+            )],
+            &[&maker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidMarketAuthority);
+
+    // every resting bid maker must be passed
+    let result = env
+        .send(
+            &[instructions::migrate_books(&admin.pubkey(), &market, &[])],
+            &[],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidMigrationAccount);
+
+    env.process(
+        &[instructions::migrate_books(
+            &admin.pubkey(),
+            &market,
+            &[open_orders],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let state: Market = env.account(&market.address).await;
+    assert_eq!(state.book_version, BOOK_VERSION);
+
+    // books rest best first, 22 quote at 3 buys 7 base locking 21, 2 quote buys nothing and is cancelled
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders_count, 2);
+    assert_eq!(
+        bids.orders
+            .iter()
+            .map(|order| (order.order_id, order.quantity, order.account_index))
+            .collect::<Vec<_>>(),
+        vec![(1, 7, 0), (0, 5 * UNIT, 0)]
+    );
+
+    let asks: Book = env.account(&market.asks).await;
+    assert_eq!(asks.orders_count, 2);
+    assert_eq!(
+        asks.orders
+            .iter()
+            .map(|order| (order.order_id, order.quantity))
+            .collect::<Vec<_>>(),
+        vec![(4, 2 * UNIT), (3, UNIT)]
+    );
+
+    let maker_state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(maker_state.opened_orders_count, 4);
+    assert_eq!(maker_state.quote_deposit_total, 10 * UNIT + 21);
+    assert_eq!(maker_state.quote_free, 3);
+
+    // best levels read the front of the migrated books
+    let best: BestPrices = env
+        .simulate(instructions::get_best_prices(&market))
+        .await
+        .unwrap();
+    assert_eq!(best.best_bid.map(|level| level.price), Some(3 * UNIT));
+    assert_eq!(best.best_ask.map(|level| level.price), Some(4 * UNIT));
+
+    let result = env
+        .process(
+            &[instructions::migrate_books(
+                &admin.pubkey(),
+                &market,
+                &[open_orders],
+            )],
+            &[],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::BookAlreadyMigrated);
+
+    env.process(
+        &[
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 2 * UNIT, UNIT),
+            instructions::cancel_sell_order(&maker_key, &maker_key, 0, &market, 4),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn migrate_legacy_layouts() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let maker = env.create_user(&[(market.base_mint, 10 * UNIT)]).await;
+    env.create_open_orders(&maker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let open_orders = market.user_market_orders(&maker_key, 0);
+    let global_pool = hybrid_dex_client::global_pool_address();
+
+    env.process(
+        &[instructions::place_sell_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // rewrite the accounts in their layouts before versioning, as older program versions created them
+    let pool: GlobalPool = env.account(&global_pool).await;
+    let legacy_pool = GlobalPoolV0 {
+        admin: pool.admin,
+        max_orders_per_user: pool.max_orders_per_user,
+        max_orders_per_book: pool.max_orders_per_book,
+        total_market_count: pool.total_market_count,
+        market_seq_num: pool.market_seq_num,
+        extra: 0,
+    };
+    let state: Market = env.account(&market.address).await;
+    let legacy_market = MarketV0 {
+        seed: state.seed,
+        name: state.name,
+        market_authority: state.market_authority,
+        base_mint: state.base_mint,
+        quote_mint: state.quote_mint,
+        base_decimal: state.base_decimal,
+        quote_decimal: state.quote_decimal,
+        bids: state.bids,
+        asks: state.asks,
+        created_at: state.created_at,
+        base_total_volume: 3 * UNIT,
+        quote_total_volume: 5 * UNIT,
+        order_seq_num: state.order_seq_num,
+        extra: 0,
+    };
+    let maker_state: UserMarketOrders = env.account(&open_orders).await;
+    let legacy_open_orders = UserMarketOrdersV0 {
+        address: maker_state.address,
+        market: maker_state.market,
+        opened_orders_count: maker_state.opened_orders_count,
+        base_deposit_total: maker_state.base_deposit_total,
+        quote_deposit_total: maker_state.quote_deposit_total,
+        base_total_volume: 7 * UNIT,
+        quote_total_volume: 11 * UNIT,
+        extra: 0,
+    };
+
+    env.set_legacy_account::<GlobalPool, _>(&global_pool, &legacy_pool)
+        .await;
+    env.set_legacy_account::<Market, _>(&market.address, &legacy_market)
+        .await;
+    env.set_legacy_account::<UserMarketOrders, _>(&open_orders, &legacy_open_orders)
+        .await;
+
+    let asks: Book = env.account(&market.asks).await;
+    let ask = asks.orders[0];
+    let legacy_asks = BookV0 {
+        side: Side::Ask,
+        market: market.address,
+        orders_count: 1,
+        orders: vec![OpenedOrderV0 {
+            order_id: ask.order_id,
+            owner: ask.owner,
+            price: ask.price,
+            quantity: ask.quantity,
+            created_at: ask.created_at,
+        }],
+    };
+    let legacy_bids = BookV0 {
+        side: Side::Bid,
+        market: market.address,
+        orders_count: 0,
+        orders: vec![],
+    };
+    env.set_legacy_book(&market.asks, &legacy_asks, 8).await;
+    env.set_legacy_book(&market.bids, &legacy_bids, 8).await;
+
+    // legacy accounts can't be loaded until they migrate, solvency can't be audited either
+    let result = env
+        .send(
+            &[instructions::cancel_sell_order(
+                &maker_key, &maker_key, 0, &market, 0,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert!(result.is_err());
+
+    let result = env
+        .send(&[instructions::migrate_global_pool(&maker_key)], &[&maker])
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidAdmin);
+
+    // market limits are backfilled from the migrated global pool
+    let result = env
+        .send(
+            &[instructions::migrate_market(&maker_key, market.seed)],
+            &[&maker],
+        )
+        .await;
+    assert!(result.is_err());
+
+    // open orders and market migration is paid by anyone
+    env.send(
+        &[
+            instructions::migrate_global_pool(&admin.pubkey()),
+            instructions::migrate_market(&maker_key, market.seed),
+            instructions::migrate_open_orders(&maker_key, &open_orders),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let pool = env.global_pool().await;
+    assert_eq!(pool.version, GLOBAL_POOL_VERSION);
+    assert_eq!(pool.admin, legacy_pool.admin);
+    assert_eq!(pool.max_orders_per_user, legacy_pool.max_orders_per_user);
+    assert_eq!(pool.max_orders_per_book, legacy_pool.max_orders_per_book);
+    assert_eq!(pool.total_market_count, legacy_pool.total_market_count);
+    assert_eq!(pool.market_seq_num, legacy_pool.market_seq_num);
+
+    let state: Market = env.account(&market.address).await;
+    assert_eq!(state.version, MARKET_VERSION);
+    assert_eq!(state.seed, legacy_market.seed);
+    assert_eq!(state.name, legacy_market.name);
+    assert_eq!(state.market_authority, legacy_market.market_authority);
+    assert_eq!(state.base_mint, legacy_market.base_mint);
+    assert_eq!(state.quote_mint, legacy_market.quote_mint);
+    assert_eq!(state.base_decimal, legacy_market.base_decimal);
+    assert_eq!(state.quote_decimal, legacy_market.quote_decimal);
+    assert_eq!(state.bids, legacy_market.bids);
+    assert_eq!(state.asks, legacy_market.asks);
+    assert_eq!(state.created_at, legacy_market.created_at);
+    assert_eq!(state.base_total_volume, 3 * UNIT);
+    assert_eq!(state.quote_total_volume, 5 * UNIT);
+    assert_eq!(state.order_seq_num, legacy_market.order_seq_num);
+    // books keep their legacy format until they migrate, legacy markets have no oracle
+    assert_eq!(state.book_version, 0);
+    assert_eq!(state.oracle_kind, OracleKind::None);
+    // legacy limits come from the global pool, bounded by the allocated book
+    assert_eq!(state.max_orders_per_user, MAX_ORDERS_PER_USER);
+    assert_eq!(state.max_orders_per_book, 8);
+    assert_eq!(
+        (state.min_order_value, state.min_trigger_trade_value),
+        (UNIT, UNIT)
+    );
+
+    let maker_state: UserMarketOrders = env.account(&open_orders).await;
+    assert_eq!(maker_state.version, OPEN_ORDERS_VERSION);
+    assert_eq!(maker_state.address, maker_key);
+    assert_eq!(maker_state.market, market.address);
+    assert_eq!(maker_state.opened_orders_count, 1);
+    assert_eq!(maker_state.base_deposit_total, UNIT);
+    assert_eq!(maker_state.quote_deposit_total, 0);
+    assert_eq!(maker_state.base_total_volume, 7 * UNIT);
+    assert_eq!(maker_state.quote_total_volume, 11 * UNIT);
+    assert_eq!(maker_state.delegate, Pubkey::default());
+    assert_eq!(maker_state.account_index, 0);
+    assert_eq!((maker_state.base_free, maker_state.quote_free), (0, 0));
+
+    let result = env
+        .send(
+            &[instructions::migrate_market(&maker_key, market.seed)],
+            &[&maker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::AccountAlreadyMigrated);
+
+    // legacy books still can't be traded
+    let result = env
+        .send(
+            &[instructions::cancel_sell_order(
+                &maker_key, &maker_key, 0, &market, 0,
+            )],
+            &[&maker],
+        )
+        .await;
+    assert!(result.is_err());
+
+    // migrated accounts trade again once their books migrate
+    env.process(
+        &[
+            instructions::migrate_books(&admin.pubkey(), &market, &[]),
+            instructions::cancel_sell_order(&maker_key, &maker_key, 0, &market, 0),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let asks: Book = env.account(&market.asks).await;
+    assert_eq!(asks.orders_count, 0);
+}
+
+#[tokio::test]
+async fn create_trade_history_of_existing_market() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let funds = [
+        (market.base_mint, 10 * UNIT),
+        (market.quote_mint, 10 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let taker = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    env.create_open_orders(&taker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let taker_key = taker.pubkey();
+    let accounts = take_accounts(&taker, &maker);
+
+    env.process(
+        &[instructions::place_sell_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            4 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // markets created before trades were recorded have no trade history to write
+    env.remove_account(&market.trade_history.unwrap());
+    let state: Market = env.account(&market.address).await;
+    let state = Market {
+        has_trade_history: false,
+        ..state
+    };
+    env.set_account_state(&market.address, &state).await;
+
+    let result = env
+        .process(
+            &[instructions::partial_take_sell_order(
+                &accounts, &market, 0, UNIT,
+            )],
+            &[&taker],
+        )
+        .await;
+    assert!(result.is_err());
+
+    // fills skip the missing trade history
+    let legacy_market = MarketKeys {
+        trade_history: None,
+        ..market
+    };
+    env.process(
+        &[instructions::partial_take_sell_order(
+            &accounts,
+            &legacy_market,
+            0,
+            UNIT,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    env.process(
+        &[instructions::create_trade_history(&taker_key, &market)],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    let result = env
+        .send(
+            &[instructions::create_trade_history(&taker_key, &market)],
+            &[&taker],
+        )
+        .await;
+    assert!(result.is_err());
+
+    // fills must record their trades once the market has a trade history
+    let state: Market = env.account(&market.address).await;
+    assert!(state.has_trade_history);
+    assert_dex_error(
+        env.process(
+            &[instructions::partial_take_sell_order(
+                &accounts,
+                &legacy_market,
+                0,
+                UNIT,
+            )],
+            &[&taker],
+        )
+        .await,
+        HybridDexError::TradeHistoryRequired,
+    );
+
+    env.process(
+        &[instructions::partial_take_sell_order(
+            &accounts, &market, 0, UNIT,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    let trade_history: TradeHistory = env.account(&market.trade_history.unwrap()).await;
+    assert_eq!(trade_history.market, market.address);
+    assert_eq!(trade_history.trade_seq_num, 1);
+    assert_eq!(trade_history.trades[0].base_amount, UNIT);
+    assert_eq!(trade_history.trades[0].quote_amount, 2 * UNIT);
+    assert_eq!(trade_history.trades[0].taker, taker_key);
+}
+
+#[tokio::test]
+async fn market_stats_candle_intervals() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let funds = [
+        (market.base_mint, 10 * UNIT),
+        (market.quote_mint, 10 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let taker = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    env.create_open_orders(&taker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let taker_key = taker.pubkey();
+    let accounts = take_accounts(&taker, &maker);
+
+    // only market authority sets valid candle intervals
+    let result = env
+        .process(
+            &[instructions::set_candle_intervals(
+                &taker_key,
+                &market,
+                &[300],
+            )],
+            &[&taker],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidMarketAuthority);
+
+    let result = env
+        .process(
+            &[instructions::set_candle_intervals(
+                &admin.pubkey(),
+                &market,
+                &[3600, 300],
+            )],
+            &[],
+        )
+        .await;
+    assert_dex_error(result, HybridDexError::InvalidCandleIntervals);
+
+    env.process(
+        &[instructions::set_candle_intervals(
+            &admin.pubkey(),
+            &market,
+            &[300, 900],
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    env.process(
+        &[instructions::place_sell_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            3 * UNIT,
+            2 * UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // markets created before candles were recorded have no stats to write
+    env.remove_account(&market.stats.unwrap());
+    let state: Market = env.account(&market.address).await;
+    let state = Market {
+        has_market_stats: false,
+        ..state
+    };
+    env.set_account_state(&market.address, &state).await;
+
+    let result = env
+        .process(
+            &[instructions::partial_take_sell_order(
+                &accounts, &market, 0, UNIT,
+            )],
+            &[&taker],
+        )
+        .await;
+    assert!(result.is_err());
+
+    // fills skip the missing stats
+    let legacy_market = MarketKeys {
+        stats: None,
+        ..market
+    };
+    env.process(
+        &[instructions::partial_take_sell_order(
+            &accounts,
+            &legacy_market,
+            0,
+            UNIT,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    env.process(
+        &[instructions::create_market_stats(&taker_key, &market)],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    // fills must record their candles once the market has stats
+    let state: Market = env.account(&market.address).await;
+    assert!(state.has_market_stats);
+    assert_dex_error(
+        env.process(
+            &[instructions::partial_take_sell_order(
+                &accounts,
+                &legacy_market,
+                0,
+                UNIT,
+            )],
+            &[&taker],
+        )
+        .await,
+        HybridDexError::MarketStatsRequired,
+    );
+
+    env.process(
+        &[instructions::partial_take_sell_order(
+            &accounts, &market, 0, UNIT,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    let stats: MarketStats = env.account(&market.stats.unwrap()).await;
+    assert_eq!(stats.last_price, 3 * UNIT);
+    assert_eq!(stats.candle_series.len(), DEFAULT_CANDLE_INTERVALS.len());
+    assert!(stats.candles(300).is_none());
+
+    let candles = stats.candles(60).unwrap();
+    assert_eq!(candles.len(), 1);
+    assert_eq!(candles[0].close, 3 * UNIT);
+    assert_eq!(candles[0].quote_volume, 3 * UNIT);
+}
+
+#[tokio::test]
+async fn twap_from_market_stats() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 4, 8).await.unwrap();
+    let funds = [
+        (market.base_mint, 10 * UNIT),
+        (market.quote_mint, 10 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let taker = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    env.create_open_orders(&taker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let accounts = take_accounts(&taker, &maker);
+
+    for price in [2 * UNIT, 4 * UNIT] {
+        env.process(
+            &[instructions::place_sell_order(
+                &maker_key, &maker_key, 0, &market, price, UNIT,
+            )],
+            &[&maker],
+        )
+        .await
+        .unwrap();
+    }
+
+    let start = 1_800_000_000;
+    env.set_unix_timestamp(start).await;
+    env.process(
+        &[instructions::take_sell_order(&accounts, &market, 0)],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    let stats: MarketStats = env.account(&market.stats.unwrap()).await;
+    let first = stats.observe(start);
+    assert_eq!(first.price_cumulative, 0);
+
+    env.set_unix_timestamp(start + 30).await;
+    env.process(
+        &[instructions::take_sell_order(&accounts, &market, 1)],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    // 2 quote stood for 30s, then 4 quote for 10s until the observation
+    let stats: MarketStats = env.account(&market.stats.unwrap()).await;
+    assert_eq!(stats.last_trade_at, start + 30);
+    assert_eq!(stats.price_cumulative, 2 * UNIT as u128 * 30);
+
+    let end = stats.observe(start + 40);
+    assert_eq!(hybrid_dex_client::twap(&first, &end).unwrap(), 5 * UNIT / 2);
+    assert_eq!(
+        hybrid_dex_client::twap(&stats.observe(start + 30), &end).unwrap(),
+        4 * UNIT
+    );
+}
+
+#[tokio::test]
+async fn view_instructions() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let market = env.create_market(&admin, 8, 8).await.unwrap();
+    let funds = [
+        (market.base_mint, 100 * UNIT),
+        (market.quote_mint, 100 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let taker = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    env.create_open_orders(&taker, &market).await;
+
+    let maker_key = maker.pubkey();
+    let taker_key = taker.pubkey();
+
+    let best_prices: BestPrices = env
+        .simulate(instructions::get_best_prices(&market))
+        .await
+        .unwrap();
+    assert_eq!(best_prices, BestPrices::default());
+
+    // bids 0 and 1, asks 2, 3 and 4
+    let mut ixs = vec![];
+    for (price, quantity) in [(2 * UNIT, 2 * UNIT), (UNIT, 3 * UNIT)] {
+        ixs.push(instructions::place_buy_order(
+            &maker_key, &maker_key, 0, &market, price, quantity,
+        ));
+    }
+    for (price, quantity) in [(3 * UNIT, UNIT), (3 * UNIT, 2 * UNIT), (5 * UNIT, UNIT)] {
+        ixs.push(instructions::place_sell_order(
+            &maker_key, &maker_key, 0, &market, price, quantity,
+        ));
+    }
+    env.process(&ixs, &[&maker]).await.unwrap();
+
+    let best_prices: BestPrices = env
+        .simulate(instructions::get_best_prices(&market))
+        .await
+        .unwrap();
+    assert_eq!(
+        best_prices,
+        BestPrices {
+            best_bid: Some(PriceLevel {
+                price: 2 * UNIT,
+                quantity: 2 * UNIT,
+                orders_count: 1,
+            }),
+            best_ask: Some(PriceLevel {
+                price: 3 * UNIT,
+                quantity: 3 * UNIT,
+                orders_count: 2,
+            }),
+        }
+    );
+
+    // selling more than the bids hold fills what they cover
+    let quote: MarketOrderQuote = env
+        .simulate(instructions::quote_market_order(
+            &market,
+            Side::Ask,
+            10 * UNIT,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        quote,
+        MarketOrderQuote {
+            base_amount: 5 * UNIT,
+            quote_amount: 7 * UNIT,
+            orders_filled: 2,
+            worst_price: UNIT,
+        }
+    );
+
+    let result = env
+        .simulate::<MarketOrderQuote>(instructions::quote_market_order(&market, Side::Bid, 0))
+        .await;
+    assert_eq!(
+        result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(HybridDexError::ZeroAmount.into())
+        ))
+    );
+
+    let position: UserPosition = env
+        .simulate(instructions::get_user_position(
+            &market,
+            &market.user_market_orders(&maker_key, 0),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        position,
+        UserPosition {
+            owner: maker_key,
+            account_index: 0,
+            opened_orders_count: 5,
+            base_locked: 4 * UNIT,
+            quote_locked: 7 * UNIT,
+            ..UserPosition::default()
+        }
+    );
+
+    // buying 4 base walks all asks, taking them costs the quoted amount
+    let quote: MarketOrderQuote = env
+        .simulate(instructions::quote_market_order(
+            &market,
+            Side::Bid,
+            4 * UNIT,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        quote,
+        MarketOrderQuote {
+            base_amount: 4 * UNIT,
+            quote_amount: 14 * UNIT,
+            orders_filled: 3,
+            worst_price: 5 * UNIT,
+        }
+    );
+
+    let accounts = take_accounts(&taker, &maker);
+    env.process(
+        &[
+            instructions::take_sell_order(&accounts, &market, 2),
+            instructions::take_sell_order(&accounts, &market, 3),
+            instructions::take_sell_order(&accounts, &market, 4),
+        ],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.token_balance(&taker_key, &market.quote_mint).await,
+        100 * UNIT - quote.quote_amount
+    );
+
+    let best_prices: BestPrices = env
+        .simulate(instructions::get_best_prices(&market))
+        .await
+        .unwrap();
+    assert_eq!(best_prices.best_ask, None);
+}
+
+#[tokio::test]
+async fn oracle_price_band() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let mut market = env.create_market(&admin, 8, 16).await.unwrap();
+    let funds = [
+        (market.base_mint, 10 * UNIT),
+        (market.quote_mint, 10 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    let maker_key = maker.pubkey();
+
+    let now = 1_800_000_000;
+    env.set_unix_timestamp(now).await;
+
+    // 2.0 quote per base with 8 price decimals
+    let pyth = Pubkey::new_unique();
+    env.set_pyth_price(&pyth, 200_000_000, -8, now);
+
+    let keys = market;
+    let set_oracle = |kind, oracle, band_bps| {
+        instructions::set_oracle(&admin.pubkey(), &keys, kind, oracle, None, band_bps)
+    };
+
+    assert_dex_error(
+        env.process(
+            &[instructions::set_oracle(
+                &maker_key,
+                &market,
+                OracleKind::Pyth,
+                Some(pyth),
+                None,
+                500,
+            )],
+            &[&maker],
+        )
+        .await,
+        HybridDexError::InvalidMarketAuthority,
+    );
+    for band_bps in [0, MAX_PRICE_BAND_BPS + 1] {
+        assert_dex_error(
+            env.process(&[set_oracle(OracleKind::Pyth, Some(pyth), band_bps)], &[])
+                .await,
+            HybridDexError::InvalidPriceBand,
+        );
+    }
+    // clmm observations need their pool, a pyth account is not one
+    for pool in [None, Some(pyth)] {
+        assert_dex_error(
+            env.process(
+                &[instructions::set_oracle(
+                    &admin.pubkey(),
+                    &market,
+                    OracleKind::RaydiumClmm,
+                    Some(pyth),
+                    pool,
+                    500,
+                )],
+                &[],
+            )
+            .await,
+            HybridDexError::InvalidOracleAccount,
+        );
+    }
+
+    env.process(&[set_oracle(OracleKind::Pyth, Some(pyth), 500)], &[])
+        .await
+        .unwrap();
+    let state: Market = env.account(&market.address).await;
+    assert_eq!(
+        (state.oracle_kind, state.oracle, state.price_band_bps),
+        (OracleKind::Pyth, pyth, 500)
+    );
+
+    // placing without the oracle account fails once the market has a band
+    assert_dex_error(
+        env.process(
+            &[instructions::place_buy_order(
+                &maker_key,
+                &maker_key,
+                0,
+                &market,
+                2 * UNIT,
+                UNIT,
+            )],
+            &[&maker],
+        )
+        .await,
+        HybridDexError::InvalidOracleAccount,
+    );
+    market.oracle = Some(pyth);
+
+    // band is 1.9 to 2.1
+    for (side, price) in [(Side::Bid, 1_899_999), (Side::Ask, 2_100_001)] {
+        let ix = match side {
+            Side::Bid => instructions::place_buy_order,
+            Side::Ask => instructions::place_sell_order,
+        };
+        assert_dex_error(
+            env.process(
+                &[ix(&maker_key, &maker_key, 0, &market, price, UNIT)],
+                &[&maker],
+            )
+            .await,
+            HybridDexError::PriceOutsideBand,
+        );
+    }
+    env.process(
+        &[
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 1_900_000, UNIT),
+            instructions::place_sell_order(&maker_key, &maker_key, 0, &market, 2_100_000, UNIT),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // prices with a confidence interval wider than 2% are refused
+    env.set_pyth_price_confidence(&pyth, 200_000_000, 4_000_001, -8, now);
+    assert_dex_error(
+        env.process(
+            &[instructions::place_buy_order(
+                &maker_key,
+                &maker_key,
+                0,
+                &market,
+                2 * UNIT,
+                UNIT,
+            )],
+            &[&maker],
+        )
+        .await,
+        HybridDexError::OracleConfidenceTooWide,
+    );
+    env.set_pyth_price_confidence(&pyth, 200_000_000, 4_000_000, -8, now);
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            2 * UNIT,
+            UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // prices older than the staleness window are refused
+    env.set_unix_timestamp(now + MAX_ORACLE_STALENESS + 1).await;
+    assert_dex_error(
+        env.process(
+            &[instructions::place_buy_order(
+                &maker_key,
+                &maker_key,
+                0,
+                &market,
+                2 * UNIT,
+                UNIT,
+            )],
+            &[&maker],
+        )
+        .await,
+        HybridDexError::StaleOraclePrice,
+    );
+
+    // pool traded at tick 6932, 2.0 quote per base, then a swap moved it to 4.0 for the
+    // last 10 seconds, the TWAP over 300 seconds stays near 2.05
+    let now = now + MAX_ORACLE_STALENESS + 1;
+    let tick: i64 = if market.base_mint < market.quote_mint {
+        6_932
+    } else {
+        -6_932
+    };
+    let at = |seconds_ago: i64| (now - seconds_ago) as u32;
+    let (pool, observation) = (Pubkey::new_unique(), Pubkey::new_unique());
+    env.set_clmm_pool(&pool, &market.base_mint, &market.quote_mint, &observation);
+    env.set_clmm_observations(&observation, &[(at(100), 0), (at(10), tick * 90)]);
+    let set_clmm_oracle = |oracle, pool| {
+        instructions::set_oracle(
+            &admin.pubkey(),
+            &keys,
+            OracleKind::RaydiumClmm,
+            Some(oracle),
+            Some(pool),
+            1_000,
+        )
+    };
+
+    // observations must be those of the pool
+    let other_observation = Pubkey::new_unique();
+    env.set_clmm_observations(&other_observation, &[(at(600), 0), (at(0), tick * 600)]);
+    assert_dex_error(
+        env.process(&[set_clmm_oracle(other_observation, pool)], &[])
+            .await,
+        HybridDexError::InvalidOracleAccount,
+    );
+    // observations must cover the TWAP window
+    assert_dex_error(
+        env.process(&[set_clmm_oracle(observation, pool)], &[])
+            .await,
+        HybridDexError::StaleOraclePrice,
+    );
+
+    env.set_clmm_observations(
+        &observation,
+        &[
+            (at(600), 0),
+            (at(300), tick * 300),
+            (at(10), tick * 590),
+            (at(0), tick * 590 + 2 * tick * 10),
+        ],
+    );
+    env.process(&[set_clmm_oracle(observation, pool)], &[])
+        .await
+        .unwrap();
+    market.oracle = Some(observation);
+    let state: Market = env.account(&market.address).await;
+    assert_eq!(
+        (state.oracle_kind, state.oracle),
+        (OracleKind::RaydiumClmm, observation)
+    );
+
+    // band is about 1.84 to 2.25, a band around the moved spot price would take both orders
+    for (side, price) in [(Side::Bid, 3_600_000), (Side::Ask, 4_400_000)] {
+        let ix = match side {
+            Side::Bid => instructions::place_buy_order,
+            Side::Ask => instructions::place_sell_order,
+        };
+        assert_dex_error(
+            env.process(
+                &[ix(&maker_key, &maker_key, 0, &market, price, UNIT)],
+                &[&maker],
+            )
+            .await,
+            HybridDexError::PriceOutsideBand,
+        );
+    }
+    env.process(
+        &[instructions::place_sell_order(
+            &maker_key, &maker_key, 0, &market, 2_200_000, UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // detached oracle accepts any price again
+    env.process(&[set_oracle(OracleKind::None, None, 0)], &[])
+        .await
+        .unwrap();
+    market.oracle = None;
+    env.process(
+        &[instructions::place_buy_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            UNIT / 100,
+            UNIT,
+        )],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+    let state: Market = env.account(&market.address).await;
+    assert_eq!(state.oracle_kind, OracleKind::None);
+}
+
+#[tokio::test]
+async fn oracle_pegged_orders() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let mut market = env.create_market(&admin, 8, 16).await.unwrap();
+    let funds = [
+        (market.base_mint, 10 * UNIT),
+        (market.quote_mint, 10 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let taker = env.create_user(&funds).await;
+    env.create_open_orders(&maker, &market).await;
+    env.create_open_orders(&taker, &market).await;
+    let maker_key = maker.pubkey();
+    let taker_key = taker.pubkey();
+    let accounts = take_accounts(&taker, &maker);
+
+    let now = 1_800_000_000;
+    env.set_unix_timestamp(now).await;
+
+    // 2.0 quote per base, orders rest within 10%
+    let pyth = Pubkey::new_unique();
+    env.set_pyth_price(&pyth, 200_000_000, -8, now);
+    env.process(
+        &[instructions::set_oracle(
+            &admin.pubkey(),
+            &market,
+            OracleKind::Pyth,
+            Some(pyth),
+            None,
+            1_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    market.oracle = Some(pyth);
+
+    let place = |side, price_offset, limit_price, quantity| {
+        instructions::place_pegged_order(
+            &maker_key,
+            &maker_key,
+            0,
+            &market,
+            side,
+            price_offset,
+            limit_price,
+            quantity,
+        )
+    };
+
+    for side in [Side::Bid, Side::Ask] {
+        assert_dex_error(
+            env.process(&[place(side, -50_000, 0, UNIT)], &[&maker])
+                .await,
+            HybridDexError::PeggedLimitRequired,
+        );
+    }
+    assert_dex_error(
+        env.process(&[place(Side::Ask, 500_000, 2 * UNIT, UNIT)], &[&maker])
+            .await,
+        HybridDexError::PriceOutsideBand,
+    );
+    // 0.4 base at 2.05 is worth less than one quote token
+    let state: Market = env.account(&market.address).await;
+    assert_eq!(state.min_order_value, UNIT);
+    assert_eq!(state.min_trigger_trade_value, UNIT);
+    assert_dex_error(
+        env.process(&[place(Side::Ask, 50_000, 2 * UNIT, 400_000)], &[&maker])
+            .await,
+        HybridDexError::OrderValueTooSmall,
+    );
+
+    // market authority sets its own minimum, a whole base at 2.05 is then too small
+    let set_minimum = |authority: &Pubkey, min_order_value| {
+        instructions::set_order_minimums(authority, &market, min_order_value, UNIT)
+    };
+    assert_dex_error(
+        env.process(&[set_minimum(&maker_key, 3 * UNIT)], &[&maker])
+            .await,
+        HybridDexError::InvalidMarketAuthority,
+    );
+    assert_dex_error(
+        env.process(&[set_minimum(&admin.pubkey(), 0)], &[]).await,
+        HybridDexError::InvalidOrderMinimum,
+    );
+    env.process(&[set_minimum(&admin.pubkey(), 3 * UNIT)], &[])
+        .await
+        .unwrap();
+    assert_dex_error(
+        env.process(&[place(Side::Ask, 50_000, 2 * UNIT, UNIT)], &[&maker])
+            .await,
+        HybridDexError::OrderValueTooSmall,
+    );
+    env.process(&[set_minimum(&admin.pubkey(), UNIT)], &[])
+        .await
+        .unwrap();
+
+    // bid 0.05 under the oracle up to 2.1, ask 0.05 over it down to 2.0
+    env.process(
+        &[
+            place(Side::Bid, -50_000, 2_100_000, 2 * UNIT),
+            place(Side::Ask, 50_000, 2 * UNIT, 3 * UNIT),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let pegged_book: PeggedBook = env.account(&market.pegged_book.unwrap()).await;
+    assert_eq!(pegged_book.orders.len(), 2);
+    let maker_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&maker_key, 0)).await;
+    assert_eq!(maker_orders.opened_orders_count, 2);
+    // bid locks its quote value at the limit price
+    assert_eq!(maker_orders.quote_deposit_total, 4_200_000);
+    assert_eq!(maker_orders.base_deposit_total, 3 * UNIT);
+
+    // taker sells into the bid at 1.95, the rest of the 2.1 lock returns to maker
+    let take = |order_id, amount, limit_price| {
+        instructions::take_pegged_order(&accounts, &market, order_id, amount, limit_price)
+    };
+    assert_dex_error(
+        env.process(&[take(0, UNIT, 0)], &[&taker]).await,
+        HybridDexError::PeggedLimitRequired,
+    );
+    assert_dex_error(
+        env.process(&[take(0, UNIT, 1_960_000)], &[&taker]).await,
+        HybridDexError::PeggedPriceBeyondLimit,
+    );
+    env.process(&[take(0, UNIT, 1_950_000)], &[&taker])
+        .await
+        .unwrap();
+    let maker_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&maker_key, 0)).await;
+    assert_eq!(maker_orders.quote_deposit_total, 2_100_000);
+    assert_eq!(maker_orders.quote_free, 150_000);
+
+    // oracle at 2.2 moves both orders, the bid stops at its limit
+    env.set_pyth_price(&pyth, 220_000_000, -8, now);
+    assert_dex_error(
+        env.process(&[take(0, 2 * UNIT, 2_100_000)], &[&taker])
+            .await,
+        HybridDexError::PartialOrderAmountExceed,
+    );
+    env.process(
+        &[take(0, UNIT, 2_100_000), take(1, UNIT, 2_250_000)],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    let trade_history: TradeHistory = env.account(&market.trade_history.unwrap()).await;
+    let prices: Vec<_> = trade_history
+        .recent()
+        .iter()
+        .map(|trade| (trade.price, trade.taker_side))
+        .collect();
+    assert_eq!(
+        prices,
+        vec![
+            (2_250_000, Side::Bid),
+            (2_100_000, Side::Ask),
+            (1_950_000, Side::Ask)
+        ]
+    );
+
+    // filled bid left the book, its lock is fully released
+    let maker_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&maker_key, 0)).await;
+    assert_eq!(maker_orders.opened_orders_count, 1);
+    assert_eq!(maker_orders.quote_deposit_total, 0);
+    assert_eq!(maker_orders.quote_free, 150_000);
+    assert_eq!(maker_orders.base_deposit_total, 2 * UNIT);
+    assert_eq!(
+        env.token_balance(&maker_key, &market.base_mint).await,
+        9 * UNIT
+    );
+    assert_eq!(
+        env.token_balance(&maker_key, &market.quote_mint).await,
+        5_800_000 + 2_250_000
+    );
+    assert_eq!(
+        env.token_balance(&taker_key, &market.quote_mint).await,
+        10 * UNIT + 1_950_000 + 2_100_000 - 2_250_000
+    );
+
+    // cancel releases the rest of the ask, pegged orders need no oracle to cancel
+    let cancel =
+        |order_id| instructions::cancel_pegged_order(&maker_key, &maker_key, 0, &market, order_id);
+    assert_dex_error(
+        env.process(&[cancel(0)], &[&maker]).await,
+        HybridDexError::OrderNotFound,
+    );
+    env.process(&[cancel(1)], &[&maker]).await.unwrap();
+
+    let maker_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&maker_key, 0)).await;
+    assert_eq!(maker_orders.opened_orders_count, 0);
+    assert_eq!(maker_orders.base_deposit_total, 0);
+    assert_eq!(maker_orders.base_free, 2 * UNIT);
+    let pegged_book: PeggedBook = env.account(&market.pegged_book.unwrap()).await;
+    assert!(pegged_book.orders.is_empty());
+}
+
+#[tokio::test]
+async fn trigger_orders() {
+    let mut env = TestEnv::new().await;
+    let admin = env.admin.insecure_clone();
+
+    let mut market = env.create_market(&admin, 8, 16).await.unwrap();
+    let funds = [
+        (market.base_mint, 10 * UNIT),
+        (market.quote_mint, 10 * UNIT),
+    ];
+    let maker = env.create_user(&funds).await;
+    let taker = env.create_user(&funds).await;
+    let owner = env.create_user(&funds).await;
+    let keeper = env.create_user(&[]).await;
+    for user in [&maker, &taker, &owner] {
+        env.create_open_orders(user, &market).await;
+    }
+    let maker_key = maker.pubkey();
+    let owner_key = owner.pubkey();
+
+    // bids at 1.85, 1.8 and 1.7, a partial take of the 1.8 bid worth 0.9 quote
+    env.process(
+        &[
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 1_850_000, 2 * UNIT),
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 1_800_000, 2 * UNIT),
+            instructions::place_buy_order(&maker_key, &maker_key, 0, &market, 1_700_000, UNIT),
+        ],
+        &[&maker],
+    )
+    .await
+    .unwrap();
+    env.process(
+        &[instructions::partial_take_buy_order(
+            &take_accounts(&taker, &maker),
+            &market,
+            1,
+            UNIT / 2,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+
+    env.process(
+        &[instructions::deposit(
+            &owner_key,
+            &owner_key,
+            0,
+            &market,
+            5 * UNIT,
+            10 * UNIT,
+        )],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    let params = |side, source, condition, order_type, trigger_price, limit_price, quantity| {
+        TriggerOrderParams {
+            side,
+            source,
+            condition,
+            order_type,
+            trigger_price,
+            limit_price,
+            quantity,
+        }
+    };
+    let place =
+        |params| instructions::place_trigger_order(&owner_key, &owner_key, 0, &market, params);
+    let stop_loss = params(
+        Side::Ask,
+        TriggerSource::LastPrice,
+        TriggerCondition::Stop,
+        TriggerOrderType::Market,
+        1_900_000,
+        1_800_000,
+        3 * UNIT,
+    );
+
+    assert_dex_error(
+        env.process(
+            &[place(TriggerOrderParams {
+                order_type: TriggerOrderType::Limit,
+                limit_price: 0,
+                ..stop_loss
+            })],
+            &[&owner],
+        )
+        .await,
+        HybridDexError::TriggerLimitRequired,
+    );
+    assert_dex_error(
+        env.process(
+            &[place(TriggerOrderParams {
+                side: Side::Bid,
+                limit_price: 0,
+                ..stop_loss
+            })],
+            &[&owner],
+        )
+        .await,
+        HybridDexError::TriggerLimitRequired,
+    );
+    assert_dex_error(
+        env.process(
+            &[place(TriggerOrderParams {
+                source: TriggerSource::Oracle,
+                ..stop_loss
+            })],
+            &[&owner],
+        )
+        .await,
+        HybridDexError::InvalidOracleAccount,
+    );
+    assert_dex_error(
+        env.process(
+            &[place(TriggerOrderParams {
+                quantity: 6 * UNIT,
+                ..stop_loss
+            })],
+            &[&owner],
+        )
+        .await,
+        HybridDexError::InsufficientFreeBalance,
+    );
+    // 0.5 base at the 1.8 limit is worth less than one quote token
+    assert_dex_error(
+        env.process(
+            &[place(TriggerOrderParams {
+                quantity: UNIT / 2,
+                ..stop_loss
+            })],
+            &[&owner],
+        )
+        .await,
+        HybridDexError::OrderValueTooSmall,
+    );
+
+    // stop loss escrows its base and the keeper bounty
+    let book_lamports = env.lamports(&market.trigger_book.unwrap()).await;
+    env.process(&[place(stop_loss)], &[&owner]).await.unwrap();
+
+    let trigger_book: TriggerBook = env.account(&market.trigger_book.unwrap()).await;
+    let order = trigger_book.orders[0];
+    assert_eq!(order.order_id, 3);
+    assert_eq!(order.bounty, TRIGGER_KEEPER_BOUNTY);
+    assert_eq!(
+        env.lamports(&market.trigger_book.unwrap()).await,
+        book_lamports + TRIGGER_KEEPER_BOUNTY
+    );
+    let owner_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&owner_key, 0)).await;
+    assert_eq!(owner_orders.opened_orders_count, 1);
+    assert_eq!(owner_orders.base_free, 2 * UNIT);
+    assert_eq!(owner_orders.base_deposit_total, 3 * UNIT);
+
+    // owner bid at 1.86 tops the book, the trigger skips it
+    env.process(
+        &[instructions::place_buy_order(
+            &owner_key, &owner_key, 0, &market, 1_860_000, UNIT,
+        )],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    // trades worth less than the market minimum, one quote token by default, don't set the
+    // last price of triggers
+    let keeper_key = keeper.pubkey();
+    let execute = |order_id, makers: &[instructions::TriggerMaker]| {
+        instructions::execute_trigger(&keeper_key, &owner_key, 0, &market, order_id, makers)
+    };
+    assert_dex_error(
+        env.process(&[execute(3, &[])], &[&keeper]).await,
+        HybridDexError::TriggerNotReached,
+    );
+
+    // with a minimum of 2 quote tokens a trade worth 1.8 doesn't fire it either
+    let set_trade_minimum = |min_trigger_trade_value| {
+        instructions::set_order_minimums(&admin.pubkey(), &market, UNIT, min_trigger_trade_value)
+    };
+    env.process(&[set_trade_minimum(2 * UNIT)], &[])
+        .await
+        .unwrap();
+    env.process(
+        &[instructions::partial_take_buy_order(
+            &take_accounts(&taker, &maker),
+            &market,
+            1,
+            UNIT,
+        )],
+        &[&taker],
+    )
+    .await
+    .unwrap();
+    assert_dex_error(
+        env.process(&[execute(3, &[])], &[&keeper]).await,
+        HybridDexError::TriggerNotReached,
+    );
+    assert_dex_error(
+        env.process(&[set_trade_minimum(0)], &[]).await,
+        HybridDexError::InvalidOrderMinimum,
+    );
+    env.process(&[set_trade_minimum(UNIT)], &[]).await.unwrap();
+
+    // last price 1.8 crossed 1.9, the market order needs every maker it fills
+    assert_dex_error(
+        env.process(&[execute(3, &[])], &[&keeper]).await,
+        HybridDexError::InvalidTriggerMakerAccount,
+    );
+
+    // last price triggers read the trade history
+    let history_less_market = MarketKeys {
+        trade_history: None,
+        ..market
+    };
+    assert_dex_error(
+        env.process(
+            &[instructions::execute_trigger(
+                &keeper_key,
+                &owner_key,
+                0,
+                &history_less_market,
+                3,
+                &[],
+            )],
+            &[&keeper],
+        )
+        .await,
+        HybridDexError::TradeHistoryRequired,
+    );
+
+    let bids: Book = env.account(&market.bids).await;
+    let makers = instructions::trigger_makers(&market, &bids, &order);
+    assert_eq!(makers.len(), 2);
+
+    // makers are paid to their ATA, not to another token account they own
+    let maker_ata = env
+        .ctx
+        .banks_client
+        .get_account(makers[0].token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let other_token_account = Pubkey::new_unique();
+    env.ctx.set_account(&other_token_account, &maker_ata.into());
+    let mut other_makers = makers.clone();
+    other_makers[0].token_account = other_token_account;
+    assert_dex_error(
+        env.process(&[execute(3, &other_makers)], &[&keeper]).await,
+        HybridDexError::InvalidTriggerMakerAccount,
+    );
+    let keeper_lamports = env.lamports(&keeper_key).await;
+    env.process(&[execute(3, &makers)], &[&keeper])
+        .await
+        .unwrap();
+    assert_eq!(
+        env.lamports(&keeper_key).await,
+        keeper_lamports + TRIGGER_KEEPER_BOUNTY
+    );
+    assert_eq!(
+        env.lamports(&market.trigger_book.unwrap()).await,
+        book_lamports
+    );
+
+    // 2 base filled at 1.85 and 0.5 at 1.8, the 1.7 bid is under the limit
+    let owner_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&owner_key, 0)).await;
+    assert_eq!(owner_orders.opened_orders_count, 1);
+    assert_eq!(owner_orders.base_deposit_total, 0);
+    assert_eq!(owner_orders.base_free, 2 * UNIT + UNIT / 2);
+    assert_eq!(
+        owner_orders.quote_free,
+        10 * UNIT - 1_860_000 + 3_700_000 + 900_000
+    );
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders_count, 2);
+    assert_eq!(
+        (bids.orders[0].owner, bids.orders[0].price),
+        (owner_key, 1_860_000)
+    );
+    assert_eq!(bids.orders[1].price, 1_700_000);
+    let maker_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&maker_key, 0)).await;
+    assert_eq!(maker_orders.opened_orders_count, 1);
+    assert_eq!(maker_orders.quote_deposit_total, 1_700_000);
+    assert_eq!(
+        env.token_balance(&maker_key, &market.base_mint).await,
+        14 * UNIT
+    );
+
+    let trade_history: TradeHistory = env.account(&market.trade_history.unwrap()).await;
+    let trades: Vec<_> = trade_history
+        .recent()
+        .iter()
+        .map(|trade| (trade.price, trade.base_amount, trade.taker))
+        .collect();
+    assert_eq!(
+        trades,
+        vec![
+            (1_800_000, UNIT / 2, owner_key),
+            (1_850_000, 2 * UNIT, owner_key),
+            (1_800_000, UNIT, taker.pubkey()),
+            (1_800_000, UNIT / 2, taker.pubkey()),
+        ]
+    );
+
+    // oracle buy stop rests a limit bid at 2.3 once the oracle reaches 2.2
+    let now = 1_800_000_000;
+    env.set_unix_timestamp(now).await;
+    let pyth = Pubkey::new_unique();
+    env.set_pyth_price(&pyth, 200_000_000, -8, now);
+    env.process(
+        &[instructions::set_oracle(
+            &admin.pubkey(),
+            &market,
+            OracleKind::Pyth,
+            Some(pyth),
+            None,
+            1_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    market.oracle = Some(pyth);
+
+    let place =
+        |params| instructions::place_trigger_order(&owner_key, &owner_key, 0, &market, params);
+    let execute = |order_id| {
+        instructions::execute_trigger(&keeper_key, &owner_key, 0, &market, order_id, &[])
+    };
+    env.process(
+        &[place(params(
+            Side::Bid,
+            TriggerSource::Oracle,
+            TriggerCondition::Stop,
+            TriggerOrderType::Limit,
+            2_200_000,
+            2_300_000,
+            UNIT,
+        ))],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    let owner_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&owner_key, 0)).await;
+    assert_eq!(owner_orders.quote_deposit_total, 1_860_000 + 2_300_000);
+
+    assert_dex_error(
+        env.process(&[execute(5)], &[&keeper]).await,
+        HybridDexError::TriggerNotReached,
+    );
+    env.set_pyth_price(&pyth, 225_000_000, -8, now);
+    env.process(&[execute(5)], &[&keeper]).await.unwrap();
+
+    let bids: Book = env.account(&market.bids).await;
+    assert_eq!(bids.orders_count, 3);
+    assert_eq!(
+        (bids.orders[0].order_id, bids.orders[0].price),
+        (5, 2_300_000)
+    );
+    let owner_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&owner_key, 0)).await;
+    assert_eq!(owner_orders.opened_orders_count, 2);
+    assert_eq!(owner_orders.quote_deposit_total, 1_860_000 + 2_300_000);
+
+    // cancel releases the escrow and returns the bounty to the signer
+    env.process(
+        &[place(params(
+            Side::Ask,
+            TriggerSource::LastPrice,
+            TriggerCondition::TakeProfit,
+            TriggerOrderType::Limit,
+            3 * UNIT,
+            3 * UNIT,
+            UNIT,
+        ))],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    let owner_lamports = env.lamports(&owner_key).await;
+    assert_dex_error(
+        env.process(
+            &[instructions::cancel_trigger_order(
+                &keeper_key,
+                &owner_key,
+                0,
+                &market,
+                6,
+            )],
+            &[&keeper],
+        )
+        .await,
+        HybridDexError::InvalidDelegate,
+    );
+    env.process(
+        &[instructions::cancel_trigger_order(
+            &owner_key, &owner_key, 0, &market, 6,
+        )],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        env.lamports(&owner_key).await,
+        owner_lamports + TRIGGER_KEEPER_BOUNTY
+    );
+    let owner_orders: UserMarketOrders =
+        env.account(&market.user_market_orders(&owner_key, 0)).await;
+    assert_eq!(owner_orders.opened_orders_count, 2);
+    assert_eq!(owner_orders.base_free, 2 * UNIT + UNIT / 2);
+    assert_eq!(owner_orders.base_deposit_total, 0);
+    let trigger_book: TriggerBook = env.account(&market.trigger_book.unwrap()).await;
+    assert!(trigger_book.orders.is_empty());
+}